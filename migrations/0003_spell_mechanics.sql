@@ -0,0 +1,34 @@
+-- Spell Mechanics (aus dem Freitext geparst, siehe core::spell_parser; wird beim Start neu aufgebaut)
+CREATE TABLE IF NOT EXISTS spell_mechanics (
+    spell_id TEXT PRIMARY KEY, -- ID aus all_spells
+    verbal BOOLEAN NOT NULL DEFAULT 0,
+    somatic BOOLEAN NOT NULL DEFAULT 0,
+    material BOOLEAN NOT NULL DEFAULT 0,
+    material_text TEXT,
+    material_cost_gp REAL,
+    material_consumed BOOLEAN NOT NULL DEFAULT 0,
+    range_type TEXT NOT NULL CHECK(range_type IN ('self', 'touch', 'distance', 'sight', 'unlimited', 'special')),
+    range_ft INTEGER,
+    range_m REAL,
+    range_area TEXT,
+    duration_type TEXT NOT NULL CHECK(duration_type IN ('instantaneous', 'timed', 'until_dispelled', 'special')),
+    duration_amount INTEGER,
+    duration_unit TEXT CHECK(duration_unit IN ('round', 'minute', 'hour', 'day', NULL)),
+    duration_seconds INTEGER,
+    duration_up_to BOOLEAN NOT NULL DEFAULT 0,
+    concentration BOOLEAN NOT NULL DEFAULT 0,
+    casting_time_type TEXT NOT NULL CHECK(casting_time_type IN ('action', 'bonus_action', 'reaction', 'time', 'special')),
+    casting_time_amount INTEGER,
+    casting_time_unit TEXT CHECK(casting_time_unit IN ('round', 'minute', 'hour', 'day', NULL)),
+    casting_time_seconds INTEGER,
+    ritual BOOLEAN NOT NULL DEFAULT 0,
+    casting_trigger TEXT,
+    scaling_type TEXT CHECK(scaling_type IN ('slot_level', 'character_level', NULL)),
+    scaling_base_level INTEGER,
+    scaling_dice TEXT,
+    updated_at INTEGER DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS idx_spell_mechanics_range ON spell_mechanics(range_type, range_ft);
+CREATE INDEX IF NOT EXISTS idx_spell_mechanics_duration ON spell_mechanics(duration_type, duration_seconds);
+CREATE INDEX IF NOT EXISTS idx_spell_mechanics_casting_time ON spell_mechanics(casting_time_type, casting_time_seconds);
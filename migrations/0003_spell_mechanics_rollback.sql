@@ -0,0 +1,5 @@
+-- Rollback: Spell Mechanics
+DROP INDEX IF EXISTS idx_spell_mechanics_range;
+DROP INDEX IF EXISTS idx_spell_mechanics_duration;
+DROP INDEX IF EXISTS idx_spell_mechanics_casting_time;
+DROP TABLE IF EXISTS spell_mechanics;
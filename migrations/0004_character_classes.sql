@@ -0,0 +1,18 @@
+-- Name, Gesamtstufe und Klassenstufen als eigene Spalten bzw. Tabelle statt im JSON-Blob.
+-- Bestehende Zeilen (storage_version 2) überführt db::characters::convert_legacy_characters beim Start.
+ALTER TABLE characters ADD COLUMN name TEXT;
+ALTER TABLE characters ADD COLUMN level INTEGER;
+
+CREATE TABLE IF NOT EXISTS character_classes (
+    id TEXT PRIMARY KEY,
+    character_id TEXT NOT NULL,
+    class_id TEXT NOT NULL,
+    subclass_id TEXT,
+    level INTEGER NOT NULL,
+    position INTEGER NOT NULL, -- 0 = Startklasse
+    created_at INTEGER DEFAULT (unixepoch()),
+    updated_at INTEGER DEFAULT (unixepoch()),
+    FOREIGN KEY (character_id) REFERENCES characters(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_character_classes_character ON character_classes(character_id, position);
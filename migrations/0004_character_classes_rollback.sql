@@ -0,0 +1,21 @@
+-- Rollback: Character Classes
+-- Name, Stufe und Klassen zurück in den JSON-Blob, damit ältere App-Versionen sie wiederfinden
+UPDATE characters
+SET data = json_set(
+        data,
+        '$.meta.name', COALESCE(name, ''),
+        '$.meta.level', COALESCE(level, 1),
+        '$.meta.class_id', (SELECT class_id FROM character_classes WHERE character_id = characters.id ORDER BY position LIMIT 1),
+        '$.meta.subclass_id', (SELECT subclass_id FROM character_classes WHERE character_id = characters.id ORDER BY position LIMIT 1),
+        '$.meta.classes', json((
+            SELECT json_group_array(json_object('class_id', class_id, 'subclass_id', subclass_id, 'level', level))
+            FROM (SELECT * FROM character_classes WHERE character_id = characters.id ORDER BY position)
+        ))
+    ),
+    storage_version = 2
+WHERE storage_version = 3;
+
+DROP INDEX IF EXISTS idx_character_classes_character;
+DROP TABLE IF EXISTS character_classes;
+ALTER TABLE characters DROP COLUMN level;
+ALTER TABLE characters DROP COLUMN name;
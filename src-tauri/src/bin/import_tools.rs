@@ -2,7 +2,9 @@ use rusqlite::{params, Connection};
 use serde_json::Value;
 use std::fs;
 
+// Nutzt nur `run_migrations`; der Rest des Moduls ist für App und `run_migrations`-Werkzeug
 #[path = "../db/migrations.rs"]
+#[allow(dead_code)]
 mod migrations;
 
 fn slugify(text: &str) -> String {
@@ -2,13 +2,22 @@ use rusqlite::Connection;
 use std::path::Path;
 use std::env;
 
+// Die Start-Helfer `run_migrations`/`run_user_migrations` braucht nur die App
 #[path = "../db/migrations.rs"]
+#[allow(dead_code)]
 mod migrations;
 
 fn main() {
     let cwd = env::current_dir().unwrap();
     println!("Current working directory: {:?}", cwd);
 
+    // --status: Stand anzeigen, --dry-run: ausführen und zurückrollen, --rollback <version>: bis zu dieser Version zurück,
+    // --user-db <pfad>: Benutzerdatenbank statt Kompendium
+    let args: Vec<String> = env::args().skip(1).collect();
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let rollback_target = args.iter().position(|a| a == "--rollback").map(|i| args.get(i + 1).and_then(|v| v.parse::<i64>().ok()));
+    let user_db = args.iter().position(|a| a == "--user-db").and_then(|i| args.get(i + 1)).cloned();
+
     // NUR Root-Datenbank verwenden - KEINE DB in src-tauri
     let possible_paths = [
         "../dnd-nexus.db",  // Root-DB (Haupt-DB)
@@ -24,9 +33,10 @@ fn main() {
         }
     }
 
-    let db_path = match db_path {
-        Some(p) => p,
-        None => {
+    let (db_path, list) = match (user_db, db_path) {
+        (Some(p), _) => (p, migrations::USER_MIGRATIONS),
+        (None, Some(p)) => (p, migrations::MIGRATIONS),
+        (None, None) => {
             eprintln!("Database not found in any expected location.");
             return;
         }
@@ -34,9 +44,39 @@ fn main() {
 
     println!("Using database at: {}", db_path);
     let conn = Connection::open(db_path).expect("Failed to open database");
-    
-    match migrations::run_migrations(&conn) {
-        Ok(_) => println!("Migrations applied successfully!"),
-        Err(e) => eprintln!("Migration error: {}", e),
+
+    let result = if args.iter().any(|a| a == "--status") {
+        print_status(&conn, list)
+    } else if let Some(target) = rollback_target {
+        match target {
+            Some(target) => migrations::rollback(&conn, list, target, dry_run).map(|records| report(&records, "Rolled back", dry_run)),
+            None => Err("--rollback expects a target version".to_string()),
+        }
+    } else {
+        migrations::migrate(&conn, list, dry_run).map(|records| report(&records, "Applied", dry_run))
+    };
+
+    if let Err(e) = result {
+        eprintln!("Migration error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn report(records: &[migrations::MigrationRecord], action: &str, dry_run: bool) {
+    if records.is_empty() {
+        println!("Nothing to do.");
+    }
+    for record in records {
+        println!("{}{} {:04} ({})", if dry_run { "[dry run] " } else { "" }, action, record.version, record.name);
+    }
+}
+
+fn print_status(conn: &Connection, list: &'static [migrations::Migration]) -> Result<(), String> {
+    for record in migrations::applied_migrations(conn)? {
+        println!("applied  {:04} ({})", record.version, record.name);
+    }
+    for migration in migrations::pending_migrations(conn, list)? {
+        println!("pending  {:04} ({})", migration.version, migration.name);
     }
+    Ok(())
 }
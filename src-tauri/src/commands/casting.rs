@@ -0,0 +1,43 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::casting::{CastRequest, CastResult};
+use crate::error::{AppResult};
+
+/// Casts a spell for a character.
+///
+/// The spell must be prepared (or known, for classes that don't prepare spells).
+/// A slot of the requested level is consumed, falling back to a Pact Magic slot;
+/// cantrips and rituals use no slot. Concentration spells start concentration.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `spell_id` - Spell ID
+/// * `slot_level` - Slot level to cast with; defaults to the spell's level
+/// * `as_ritual` - Cast as a ritual without using a slot
+///
+/// # Returns
+/// Cast level, used slot, save DC and the `higher_levels` text resolved for the cast level
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the spell isn't prepared, the slot level is below
+/// the spell's level, no slot is left or the spell can't be cast as a ritual
+#[tauri::command]
+pub async fn cast_spell(
+    db: State<'_, Database>,
+    character_id: String,
+    spell_id: String,
+    slot_level: Option<i32>,
+    as_ritual: Option<bool>,
+) -> Result<CastResult, String> {
+    let result: AppResult<CastResult> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let request = CastRequest { slot_level, as_ritual: as_ritual.unwrap_or(false) };
+        let cast = crate::db::casting::cast_spell(&tx, &character_id, &spell_id, &request)?;
+        tx.commit()?;
+        Ok(cast)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
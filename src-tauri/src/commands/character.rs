@@ -2,8 +2,10 @@ use tauri::State;
 use crate::db::Database;
 use crate::types::character::{Character, CharacterInventoryEntry};
 use crate::db::inventory::StartingEquipmentOption;
+use crate::db::spell_preparation::PreparableSpells;
+use crate::core::spell_preparation::PreparationStatus;
 use serde_json::Value;
-use crate::error::{AppError, AppResult, map_lock_error};
+use crate::error::{AppError, AppResult};
 use uuid::Uuid;
 use crate::db::queries;
 use rusqlite::params;
@@ -14,7 +16,7 @@ pub async fn get_class_starting_equipment_options(
     class_id: String,
 ) -> Result<Vec<StartingEquipmentOption>, String> {
     let result: AppResult<Vec<StartingEquipmentOption>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         crate::db::inventory::get_class_starting_equipment_options(&conn, &class_id)
     })();
     result.map_err(|e| e.to_string())
@@ -28,7 +30,7 @@ pub async fn get_starting_equipment(
     option_label: String,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         crate::db::inventory::get_starting_equipment(&conn, &character_id, &class_id, &option_label)
     })();
     result.map_err(|e| e.to_string())
@@ -41,7 +43,7 @@ pub async fn clear_starting_equipment(
     source: String,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         crate::db::inventory::clear_starting_equipment(&conn, &character_id, &source)
     })();
     result.map_err(|e| e.to_string())
@@ -55,7 +57,7 @@ pub async fn apply_background_starting_equipment(
     gold: i32,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         crate::db::inventory::apply_background_starting_equipment(&conn, &character_id, items, gold)
     })();
     result.map_err(|e| e.to_string())
@@ -67,7 +69,7 @@ pub async fn get_character_inventory(
     character_id: String,
 ) -> Result<Vec<CharacterInventoryEntry>, String> {
     let result: AppResult<Vec<CharacterInventoryEntry>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         
         let mut stmt = conn.prepare(
             "SELECT id, character_id, item_id, item_type, quantity, is_equipped, is_attuned,
@@ -116,7 +118,7 @@ pub async fn update_inventory_item(
     item: CharacterInventoryEntry,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         
         conn.execute(
             "UPDATE character_inventory SET 
@@ -162,29 +164,13 @@ pub async fn create_character(
     mut character: Character,
 ) -> Result<Character, String> {
     let result: AppResult<Character> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         
         if character.id == Uuid::nil() {
             character.id = Uuid::new_v4();
         }
-        
-        let data = serde_json::to_string(&character)?;
-        
-        conn.execute(
-            queries::INSERT_CHARACTER,
-            params![character.id.to_string(), data],
-        )?;
-
-        // Sync normalized inventory
-        crate::db::inventory::sync_inventory(&conn, &character)?;
-        // Sync normalized spells
-        crate::db::spells::sync_spells(&conn, &character)?;
-        // Sync normalized stats (attributes & health)
-        crate::db::stats::sync_stats(&conn, &character)?;
-        // Sync normalized features & proficiencies
-        crate::db::features::sync_features(&conn, &character)?;
-        // Sync normalized modifiers
-        crate::db::modifiers::sync_modifiers(&conn, &character)?;
+        crate::core::multiclass::normalize_classes(&mut character.meta);
+        crate::db::characters::insert_character(&conn, &character)?;
         
         Ok(character)
     })();
@@ -209,34 +195,10 @@ pub async fn get_character(
     id: String,
 ) -> Result<Character, String> {
     let result: AppResult<Character> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
-        
-        let mut stmt = conn.prepare("SELECT data FROM characters WHERE id = ?")?;
-        
-        let data: String = stmt.query_row(params![id.clone()], |row: &rusqlite::Row| row.get(0))
-            .map_err(|_| AppError::CharacterNotFound(id.clone()))?;
-        
-        let mut character: Character = serde_json::from_str(&data)?;
-        
-        // SYNC INVENTORY FROM SQL (SOURCE OF TRUTH)
-        // This overrides the stale/empty inventory list from the JSON blob
-        // and ensures we have item_type and other fields correctly populated.
-        let sql_inventory = crate::db::inventory::get_all_inventory_entries(&conn, &id)?;
-        
-        character.inventory = sql_inventory.into_iter().map(|entry| crate::types::character::CharacterItem {
-             id: entry.id,
-             item_id: entry.item_id,
-             item_type: Some(entry.item_type), // New field
-             quantity: entry.quantity,
-             is_equipped: entry.is_equipped,
-             is_attuned: entry.is_attuned,
-             location: entry.location,
-             source: entry.source,
-             is_starting_equipment: entry.is_starting_equipment,
-             custom_data: entry.data,
-        }).collect();
+        let conn = db.read()?;
 
-        Ok(character)
+        // Inventory is taken from SQL (source of truth), see db::characters
+        crate::db::characters::load_character(&conn, &id)
     })();
     
     result.map_err(|e| e.to_string())
@@ -255,31 +217,14 @@ pub async fn get_character(
 pub async fn update_character(
     db: State<'_, Database>,
     id: String,
-    character: Character,
+    mut character: Character,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
-        let data = serde_json::to_string(&character)?;
-        
-        let rows_affected = conn.execute(
-            queries::UPDATE_CHARACTER,
-            params![data, id.clone()],
-        )?;
-        
-        if rows_affected == 0 {
-            return Err(AppError::CharacterNotFound(id));
-        }
-
-        // Sync normalized inventory
-        crate::db::inventory::sync_inventory(&conn, &character)?;
-        // Sync normalized spells
-        crate::db::spells::sync_spells(&conn, &character)?;
-        // Sync normalized stats (attributes & health)
-        crate::db::stats::sync_stats(&conn, &character)?;
-        // Sync normalized features & proficiencies
-        crate::db::features::sync_features(&conn, &character)?;
-        // Sync normalized modifiers
-        crate::db::modifiers::sync_modifiers(&conn, &character)?;
+        let conn = db.write()?;
+        crate::core::multiclass::normalize_classes(&mut character.meta);
+        character.id = Uuid::parse_str(&id)
+            .map_err(|_| AppError::InvalidInput(format!("Ungültige Charakter-ID: {}", id)))?;
+        crate::db::characters::save_character(&conn, &character)?;
         
         Ok(())
     })();
@@ -301,7 +246,7 @@ pub async fn delete_character(
     id: String,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         conn.execute(queries::DELETE_CHARACTER, params![id])?;
         Ok(())
     })();
@@ -324,23 +269,8 @@ pub async fn list_characters(
     db: State<'_, Database>,
 ) -> Result<Vec<Character>, String> {
     let result: AppResult<Vec<Character>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
-        
-        let mut stmt = conn.prepare(queries::SELECT_ALL_CHARACTERS)?;
-        
-        let character_iter = stmt.query_map([], |row: &rusqlite::Row| {
-            let data: String = row.get(1)?;
-            Ok(data)
-        })?;
-        
-        let mut characters = Vec::new();
-        for data_result in character_iter {
-            let data = data_result?;
-            let character: Character = serde_json::from_str(&data)?;
-            characters.push(character);
-        }
-        
-        Ok(characters)
+        let conn = db.read()?;
+        crate::db::characters::list_characters(&conn)
     })();
     
     result.map_err(|e| e.to_string())
@@ -353,50 +283,67 @@ pub async fn get_character_spells(
     character_id: String,
 ) -> Result<Vec<crate::types::character::CharacterSpell>, String> {
     let result: AppResult<Vec<crate::types::character::CharacterSpell>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, spell_id, is_prepared, is_always_prepared, source 
-             FROM character_spells 
-             WHERE character_id = ?"
-        )?;
-        
-        let iter = stmt.query_map(params![character_id], |row| {
-             Ok(crate::types::character::CharacterSpell {
-                 id: row.get(0)?,
-                 spell_id: row.get(1)?,
-                 is_prepared: row.get::<_, i32>(2)? != 0,
-                 is_always_prepared: row.get::<_, i32>(3)? != 0,
-                 source: row.get(4)?,
-             })
-        })?;
-        
-        let mut spells = Vec::new();
-        for spell in iter {
-            spells.push(spell?);
-        }
-        Ok(spells)
+        let conn = db.read()?;
+        crate::db::spells::load_character_spells(&conn, &character_id)
     })();
     
     result.map_err(|e| e.to_string())
 }
 
+/// Prepares or unprepares a spell of a character.
+///
+/// Preparing checks the prepared-spell count from the class progression (always prepared
+/// spells and cantrips don't count), the class spell lists and the castable spell level.
+/// Unpreparing is always allowed.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `id` - `character_spells` row ID
+/// * `is_prepared` - New preparation state
+///
+/// # Returns
+/// Prepared count, limit and the issues that blocked the change; with issues nothing is changed
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the spell entry doesn't exist
 #[tauri::command]
 pub async fn update_spell_preparation(
     db: State<'_, Database>,
     id: String,
     is_prepared: bool,
-) -> Result<(), String> {
-    let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
-        
-        conn.execute(
-            "UPDATE character_spells SET is_prepared = ?, updated_at = (unixepoch()) WHERE id = ?",
-            params![if is_prepared { 1 } else { 0 }, id],
-        )?;
-        
-        Ok(())
+) -> Result<PreparationStatus, String> {
+    let result: AppResult<PreparationStatus> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let status = crate::db::spell_preparation::update_spell_preparation(&tx, &id, is_prepared)?;
+        tx.commit()?;
+        Ok(status)
     })();
     
     result.map_err(|e| e.to_string())
 }
+
+/// Lists the spells a character may prepare.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// The current preparation status and every spell (level 1+) on one of the character's
+/// class lists whose level the class can cast
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn get_preparable_spells(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<PreparableSpells, String> {
+    let result: AppResult<PreparableSpells> = (|| {
+        let conn = db.read()?;
+        crate::db::spell_preparation::get_preparable_spells(&conn, &character_id)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
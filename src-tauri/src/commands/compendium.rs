@@ -1,7 +1,10 @@
 use tauri::State;
 use crate::db::Database;
-use crate::error::{AppResult, map_lock_error};
+use crate::error::{AppResult};
 use crate::types::spell::Spell;
+use crate::core::spell_parser::SpellMechanics;
+use crate::db::compendium_search::{CompendiumEntity, CompendiumFilters, CompendiumPage, CompendiumSearchResult, CompendiumSort};
+use crate::db::fulltext::{FulltextEntity, FulltextHit, DEFAULT_FULLTEXT_LIMIT};
 use crate::types::compendium::{Species, Class, Gear, Tool, Feat, Armor, ArmorProperty, Skill, Background, Item, Equipment, MagicItem, FeatureOption, ItemMinimal, SpellMinimal};
 use crate::types::weapons::{Weapon, WeaponProperty, WeaponMastery, WeaponMinimal};
 use serde_json::{from_str, json, Value};
@@ -26,7 +29,7 @@ pub async fn get_all_spells(
     offset: Option<usize>,
 ) -> Result<Vec<Spell>, String> {
     let result: AppResult<Vec<Spell>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         
         let limit = limit.unwrap_or(1000);
         let offset = offset.unwrap_or(0);
@@ -83,7 +86,7 @@ pub async fn get_all_spells(
 #[tauri::command]
 pub async fn get_all_species(db: State<'_, Database>) -> Result<Vec<Species>, String> {
     let result: AppResult<Vec<Species>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare("SELECT id, name, data, source FROM all_species ORDER BY name")?;
 
         let iter = stmt.query_map([], |row: &rusqlite::Row| {
@@ -109,7 +112,7 @@ pub async fn get_all_species(db: State<'_, Database>) -> Result<Vec<Species>, St
 #[tauri::command]
 pub async fn get_all_classes(db: State<'_, Database>) -> Result<Vec<Class>, String> {
     let result: AppResult<Vec<Class>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare("SELECT id, name, data, source FROM all_classes ORDER BY name")?;
 
         let iter = stmt.query_map([], |row: &rusqlite::Row| {
@@ -135,7 +138,7 @@ pub async fn get_all_classes(db: State<'_, Database>) -> Result<Vec<Class>, Stri
 #[tauri::command]
 pub async fn get_all_gear(db: State<'_, Database>) -> Result<Vec<Gear>, String> {
     let result: AppResult<Vec<Gear>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, description, cost_gp, weight_kg, data, source FROM all_gear ORDER BY name",
         )?;
@@ -166,7 +169,7 @@ pub async fn get_all_gear(db: State<'_, Database>) -> Result<Vec<Gear>, String>
 #[tauri::command]
 pub async fn get_all_tools(db: State<'_, Database>) -> Result<Vec<Tool>, String> {
     let result: AppResult<Vec<Tool>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, category, cost_gp, weight_kg, data, source FROM all_tools ORDER BY name",
         )?;
@@ -197,7 +200,7 @@ pub async fn get_all_tools(db: State<'_, Database>) -> Result<Vec<Tool>, String>
 #[tauri::command]
 pub async fn get_all_weapons(db: State<'_, Database>) -> Result<Vec<Weapon>, String> {
     let result: AppResult<Vec<Weapon>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
 
         // Optimiert: Alle Daten in einem Query mit LEFT JOINs
         let mut stmt = conn.prepare(
@@ -365,7 +368,7 @@ pub async fn get_all_weapons(db: State<'_, Database>) -> Result<Vec<Weapon>, Str
 #[tauri::command]
 pub async fn get_all_armor(db: State<'_, Database>) -> Result<Vec<Armor>, String> {
     let result: AppResult<Vec<Armor>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
 
         // 1. Rüstungen aus all_armors View laden
         let mut stmt = conn.prepare(
@@ -471,7 +474,7 @@ pub async fn get_all_armor(db: State<'_, Database>) -> Result<Vec<Armor>, String
 #[tauri::command]
 pub async fn get_all_feats(db: State<'_, Database>) -> Result<Vec<Feat>, String> {
     let result: AppResult<Vec<Feat>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt =
             conn.prepare("SELECT id, name, category, data, source FROM all_feats ORDER BY name")?;
 
@@ -499,7 +502,7 @@ pub async fn get_all_feats(db: State<'_, Database>) -> Result<Vec<Feat>, String>
 #[tauri::command]
 pub async fn get_all_skills(db: State<'_, Database>) -> Result<Vec<Skill>, String> {
     let result: AppResult<Vec<Skill>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, ability, description, source FROM all_skills ORDER BY name",
         )?;
@@ -527,7 +530,7 @@ pub async fn get_all_skills(db: State<'_, Database>) -> Result<Vec<Skill>, Strin
 #[tauri::command]
 pub async fn get_all_backgrounds(db: State<'_, Database>) -> Result<Vec<Background>, String> {
     let result: AppResult<Vec<Background>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt =
             conn.prepare("SELECT id, name, data, source FROM all_backgrounds ORDER BY name")?;
 
@@ -555,7 +558,7 @@ pub async fn get_all_backgrounds(db: State<'_, Database>) -> Result<Vec<Backgrou
 pub async fn get_all_items(db: State<'_, Database>) -> Result<Vec<Item>, String> {
     println!("[get_all_items] Starting fetch");
     let result: AppResult<Vec<Item>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, description, cost_gp, weight_kg, category, data, source FROM all_items ORDER BY name",
         )?;
@@ -597,7 +600,7 @@ pub async fn get_all_items(db: State<'_, Database>) -> Result<Vec<Item>, String>
 pub async fn get_all_equipment(db: State<'_, Database>) -> Result<Vec<Equipment>, String> {
     println!("[get_all_equipment] Starting fetch");
     let result: AppResult<Vec<Equipment>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         
         // 1. Basis-Equipment aus View laden (ohne items/tools Spalten)
         let mut stmt = conn.prepare(
@@ -706,7 +709,7 @@ pub async fn get_all_equipment(db: State<'_, Database>) -> Result<Vec<Equipment>
 pub async fn get_all_magic_items(db: State<'_, Database>) -> Result<Vec<MagicItem>, String> {
     println!("[get_all_magic_items] Starting fetch");
     let result: AppResult<Vec<MagicItem>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, rarity, category, source_book, source_page, requires_attunement, facts_json, source, data 
              FROM all_mag_items_base 
@@ -763,7 +766,7 @@ pub async fn get_feature_options(
     feature_id: String,
 ) -> Result<Vec<FeatureOption>, String> {
     let result: AppResult<Vec<FeatureOption>> = (|| {
-        let conn = map_lock_error(state.0.lock())?;
+        let conn = state.read()?;
         
         let mut stmt = conn.prepare(
             "SELECT id, feature_id, option_name, option_description, display_order, source 
@@ -802,7 +805,7 @@ pub async fn get_all_feature_options(
     state: State<'_, Database>,
 ) -> Result<Vec<FeatureOption>, String> {
     let result: AppResult<Vec<FeatureOption>> = (|| {
-        let conn = map_lock_error(state.0.lock())?;
+        let conn = state.read()?;
         
         let mut stmt = conn.prepare(
             "SELECT id, feature_id, option_name, option_description, display_order, source 
@@ -848,7 +851,7 @@ pub async fn get_all_feature_options(
 #[tauri::command]
 pub async fn get_weapons_minimal(db: State<'_, Database>) -> Result<Vec<WeaponMinimal>, String> {
     let result: AppResult<Vec<WeaponMinimal>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, category, damage_dice, damage_type, cost_gp, source 
              FROM all_weapons_minimal 
@@ -890,7 +893,7 @@ pub async fn get_weapons_minimal(db: State<'_, Database>) -> Result<Vec<WeaponMi
 #[tauri::command]
 pub async fn get_items_minimal(db: State<'_, Database>) -> Result<Vec<ItemMinimal>, String> {
     let result: AppResult<Vec<ItemMinimal>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, category, cost_gp, weight_kg, source 
              FROM all_items_minimal 
@@ -931,7 +934,7 @@ pub async fn get_items_minimal(db: State<'_, Database>) -> Result<Vec<ItemMinima
 #[tauri::command]
 pub async fn get_spells_minimal(db: State<'_, Database>) -> Result<Vec<SpellMinimal>, String> {
     let result: AppResult<Vec<SpellMinimal>> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         let mut stmt = conn.prepare(
             "SELECT id, name, level, school, casting_time, source 
              FROM all_spells_minimal 
@@ -958,3 +961,103 @@ pub async fn get_spells_minimal(db: State<'_, Database>) -> Result<Vec<SpellMini
     
     result.map_err(|e| e.to_string())
 }
+
+/// Retrieves the structured data parsed from a spell's text fields.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `spell_id` - Spell ID
+///
+/// # Returns
+/// Components (V/S/M, material cost), range, duration, casting time and scaling,
+/// or `None` if the spell doesn't exist
+///
+/// # Errors
+/// Returns `AppError` if database operation fails
+#[tauri::command]
+pub async fn get_spell_mechanics(db: State<'_, Database>, spell_id: String) -> Result<Option<SpellMechanics>, String> {
+    let result: AppResult<Option<SpellMechanics>> = (|| {
+        let conn = db.read()?;
+        crate::db::spell_mechanics::get_spell_mechanics(&conn, &spell_id)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Searches a compendium entity on the server instead of loading the whole table.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `entity` - `spells`, `weapons`, `armor`, `items`, `magic_items`, `feats` or `backgrounds`
+/// * `query` - Case-insensitive part of the name
+/// * `filters` - Typed filters (spell level/school/class, weapon category/property,
+///   rarity/attunement, cost and weight ranges, source)
+/// * `sort` - Sort field and direction; defaults to name (spells: level, name)
+/// * `page` - Zero-based page and page size (default 50, max 500)
+///
+/// # Returns
+/// The rows of the requested page and the total number of matches
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` for an unknown sort field or a filter that
+/// doesn't apply to the entity
+#[tauri::command]
+pub async fn search_compendium(
+    db: State<'_, Database>,
+    entity: CompendiumEntity,
+    query: Option<String>,
+    filters: Option<CompendiumFilters>,
+    sort: Option<CompendiumSort>,
+    page: Option<CompendiumPage>,
+) -> Result<CompendiumSearchResult, String> {
+    let result: AppResult<CompendiumSearchResult> = (|| {
+        let conn = db.read()?;
+        crate::db::compendium_search::search_compendium(
+            &conn,
+            entity,
+            query.as_deref(),
+            &filters.unwrap_or_default(),
+            sort.as_ref(),
+            &page.unwrap_or_default(),
+        )
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Full-text search over names and descriptions of spells, feats, class features,
+/// magic items and items.
+///
+/// Umlauts match with or without diacritics, and "ss"/"ue" spellings find "ß"/"ü".
+/// Every word is matched as a prefix and all words must occur.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `query` - Search text, e.g. "Vorteil auf Rettungswürfe"
+/// * `entities` - Restrict to these entity types (default: all)
+/// * `limit` - Maximum number of hits (default: 50)
+///
+/// # Returns
+/// Hits ordered by relevance with `<mark>`-highlighted name and description snippet
+///
+/// # Errors
+/// Returns `AppError` if database operation fails
+#[tauri::command]
+pub async fn fulltext_search(
+    db: State<'_, Database>,
+    query: String,
+    entities: Option<Vec<FulltextEntity>>,
+    limit: Option<usize>,
+) -> Result<Vec<FulltextHit>, String> {
+    let result: AppResult<Vec<FulltextHit>> = (|| {
+        let conn = db.read()?;
+        crate::db::fulltext::fulltext_search(
+            &conn,
+            &query,
+            &entities.unwrap_or_default(),
+            limit.unwrap_or(DEFAULT_FULLTEXT_LIMIT),
+        )
+    })();
+
+    result.map_err(|e| e.to_string())
+}
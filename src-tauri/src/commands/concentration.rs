@@ -0,0 +1,63 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::concentration::{ConcentrationChange, ConcentrationResult};
+use crate::error::{AppResult};
+
+/// Starts concentrating on a spell. A running concentration ends automatically.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `spell_id` - ID of a concentration spell
+/// * `slot_level` - Optional slot level the spell was cast with
+///
+/// # Returns
+/// The new concentration and the concentration it replaced, if any
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the spell doesn't exist or doesn't require concentration,
+/// or if the character is incapacitated or at 0 HP
+#[tauri::command]
+pub async fn start_concentration(
+    db: State<'_, Database>,
+    character_id: String,
+    spell_id: String,
+    slot_level: Option<i32>,
+) -> Result<ConcentrationChange, String> {
+    let result: AppResult<ConcentrationChange> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let change = crate::db::concentration::start_concentration(&tx, &character_id, &spell_id, slot_level)?;
+        tx.commit()?;
+        Ok(change)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Ends a character's concentration.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// The ended concentration, or `None` if the character wasn't concentrating
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn end_concentration(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<Option<ConcentrationResult>, String> {
+    let result: AppResult<Option<ConcentrationResult>> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let ended = crate::db::concentration::end_concentration(&tx, &character_id)?;
+        tx.commit()?;
+        Ok(ended)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,94 @@
+use tauri::State;
+use crate::db::Database;
+use crate::types::character::CharacterCondition;
+use crate::error::{AppResult};
+
+/// Adds a condition to a character, or updates source and duration if it is already active.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `condition` - Condition to add; German names are accepted (`"Vergiftet"` -> `poisoned`).
+///   Exhaustion without a level raises the current level by one.
+///
+/// # Returns
+/// All active conditions of the character
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the condition ID is empty or the exhaustion level is out of range
+#[tauri::command]
+pub async fn add_condition(
+    db: State<'_, Database>,
+    character_id: String,
+    condition: CharacterCondition,
+) -> Result<Vec<CharacterCondition>, String> {
+    let result: AppResult<Vec<CharacterCondition>> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let conditions = crate::db::conditions::add_condition(&tx, &character_id, condition)?;
+        tx.commit()?;
+        Ok(conditions)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Removes a condition from a character.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `condition_id` - Condition ID or German name
+///
+/// # Returns
+/// All remaining conditions of the character
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn remove_condition(
+    db: State<'_, Database>,
+    character_id: String,
+    condition_id: String,
+) -> Result<Vec<CharacterCondition>, String> {
+    let result: AppResult<Vec<CharacterCondition>> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let conditions = crate::db::conditions::remove_condition(&tx, &character_id, &condition_id)?;
+        tx.commit()?;
+        Ok(conditions)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Sets a character's exhaustion level (0-6). Level 6 is death.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `level` - New exhaustion level; 0 removes exhaustion
+/// * `source` - Optional source, e.g. "Gewaltmarsch"
+///
+/// # Returns
+/// All active conditions of the character
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the level is outside 0-6
+#[tauri::command]
+pub async fn set_exhaustion(
+    db: State<'_, Database>,
+    character_id: String,
+    level: i32,
+    source: Option<String>,
+) -> Result<Vec<CharacterCondition>, String> {
+    let result: AppResult<Vec<CharacterCondition>> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let conditions = crate::db::conditions::set_exhaustion(&tx, &character_id, level, source)?;
+        tx.commit()?;
+        Ok(conditions)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,52 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::dice::{roll, RollResult};
+use crate::error::{AppError, AppResult};
+
+/// Rolls a dice expression such as `2d6+3`, `1d20+5 adv`, `4d6kh3` or `1d8+@str`.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `expression` - Dice expression
+/// * `seed` - Optional seed to reproduce an earlier roll
+/// * `character_id` - Optional character whose values resolve `@str` … `@cha`, `@prof` and `@level`
+///
+/// # Returns
+/// The roll with every individual die and the seed used
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the expression is invalid or uses unknown variables
+#[tauri::command]
+pub async fn roll_dice(
+    db: State<'_, Database>,
+    expression: String,
+    seed: Option<u64>,
+    character_id: Option<String>,
+) -> Result<RollResult, String> {
+    let result: AppResult<RollResult> = (|| {
+        let sheet = match character_id {
+            Some(id) => {
+                let conn = db.read()?;
+                let character = crate::db::characters::load_character(&conn, &id)?;
+                Some(crate::db::sheet::compute_sheet(&conn, &character)?)
+            }
+            None => None,
+        };
+
+        let variables = |name: &str| -> Option<i32> {
+            let sheet = sheet.as_ref()?;
+            match name {
+                "prof" | "pb" => Some(sheet.proficiency_bonus),
+                "level" => Some(sheet.level),
+                ability => sheet
+                    .abilities
+                    .iter()
+                    .find(|a| a.ability == ability)
+                    .map(|a| a.modifier),
+            }
+        };
+        roll(&expression, seed, &variables).map_err(AppError::InvalidInput)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use crate::db::Database;
-use crate::error::{AppError, AppResult, map_lock_error};
+use crate::error::{AppError, AppResult};
 use rusqlite::{params, Row};
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -57,7 +57,7 @@ pub async fn get_class_features(
     subclass_id: Option<String>,
 ) -> Result<Vec<FeatureData>, String> {
     let result: AppResult<Vec<FeatureData>> = (|| {
-        let conn = map_lock_error(state.0.lock())?;
+        let conn = state.read()?;
         
         // Konvertiere subclass_id (kann Name oder ID sein) zur ID
         let resolved_subclass_id = if let Some(ref sub_id) = subclass_id {
@@ -138,7 +138,7 @@ pub async fn create_custom_class_feature(
     feature_data: serde_json::Value,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(state.0.lock())?;
+        let conn = state.write()?;
         
         if class_source != "core" && class_source != "custom" {
             return Err(AppError::InvalidInput(
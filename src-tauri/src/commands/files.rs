@@ -1,69 +1,27 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 use tauri_plugin_dialog::DialogExt;
+use crate::db::Database;
 use crate::error::{AppError, AppResult};
 use crate::types::character::Character;
 use std::fs;
 
-/// Creates a backup of the database by copying it to a user-selected location.
+/// Creates a backup of the user database (characters, homebrew, settings) at a user-selected
+/// location. The compendium is bundled with the app and not part of the backup.
 ///
 /// # Arguments
 /// * `app` - Tauri application handle
+/// * `db` - Database connection state
 ///
 /// # Returns
 /// Path to the backup file
 ///
 /// # Errors
 /// Returns `AppError::Other` if user cancels the dialog
-/// Returns `AppError::Io` if file operation fails
+/// Returns `AppError::Io` if the backup cannot be moved into place
+/// Returns `AppError::Database` if the backup cannot be written
 #[tauri::command]
-pub async fn backup_database(app: AppHandle) -> Result<String, String> {
+pub async fn backup_database(app: AppHandle, db: State<'_, Database>) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        // Finde Root-Datenbank (gleiche Logik wie init_database)
-        let mut db_paths: Vec<std::path::PathBuf> = Vec::new();
-        
-        // Relativ zum Executable
-        if let Ok(exe) = std::env::current_exe() {
-            if let Some(exe_dir) = exe.parent() {
-                let mut current = exe_dir.to_path_buf();
-                for _ in 0..5 {
-                    let db_path = current.join("dnd-nexus.db");
-                    if db_path.exists() {
-                        db_paths.push(db_path);
-                    }
-                    if let Some(parent) = current.parent() {
-                        current = parent.to_path_buf();
-                    } else {
-                        break;
-                    }
-                }
-            }
-        }
-        
-        // Aktuelles Arbeitsverzeichnis
-        if let Ok(cwd) = std::env::current_dir() {
-            let db_path = cwd.join("dnd-nexus.db");
-            if db_path.exists() {
-                db_paths.push(db_path);
-            }
-            if let Some(parent) = cwd.parent() {
-                let db_path = parent.join("dnd-nexus.db");
-                if db_path.exists() {
-                    db_paths.push(db_path);
-                }
-            }
-        }
-        
-        #[cfg(debug_assertions)]
-        {
-            let hardcoded_path = std::path::PathBuf::from("/daten/projects/dnd_nexus-sheet_engine/dnd-nexus.db");
-            if hardcoded_path.exists() {
-                db_paths.push(hardcoded_path);
-            }
-        }
-        
-        let db_path = db_paths.first()
-            .ok_or_else(|| AppError::Other("Root-Datenbank (dnd-nexus.db) nicht gefunden!".into()))?;
-        
         let file_path = app.dialog()
             .file()
             .set_file_name("dnd-nexus-backup.db")
@@ -72,7 +30,19 @@ pub async fn backup_database(app: AppHandle) -> Result<String, String> {
         if let Some(path) = file_path {
             let path_buf = path.into_path().map_err(|e| AppError::Other(e.to_string()))?;
             let path_str = path_buf.to_string_lossy().to_string();
-            fs::copy(db_path, &path_str)?;
+            // VACUUM INTO verweigert bestehende Dateien: erst in eine temporäre Datei im selben
+            // Verzeichnis schreiben, dann ersetzen. Schlägt das Backup fehl, bleibt das alte erhalten.
+            let file_name = path_buf.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let temp_path = path_buf.with_file_name(format!(".{}.tmp", file_name));
+            if temp_path.exists() {
+                fs::remove_file(&temp_path)?;
+            }
+            let conn = db.read()?;
+            if let Err(e) = conn.execute("VACUUM main INTO ?1", [temp_path.to_string_lossy().as_ref()]) {
+                let _ = fs::remove_file(&temp_path);
+                return Err(e.into());
+            }
+            fs::rename(&temp_path, &path_buf)?;
             return Ok(path_str);
         }
         
@@ -0,0 +1,127 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::health::{DamageResult, DeathSaveResult, HealingResult, TempHpResult};
+use crate::error::{AppResult};
+
+/// Applies damage to a character. Temporary HP absorb damage first.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `amount` - Damage dealt
+/// * `critical` - Damage from a critical hit (two failed death saves at 0 HP)
+/// * `seed` - Optional seed to reproduce the concentration check
+///
+/// # Returns
+/// How the damage was applied, including instant death, death save failures
+/// and the concentration check
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the amount is negative or the character is dead
+#[tauri::command]
+pub async fn apply_damage(
+    db: State<'_, Database>,
+    character_id: String,
+    amount: i32,
+    critical: Option<bool>,
+    seed: Option<u64>,
+) -> Result<DamageResult, String> {
+    let result: AppResult<DamageResult> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let result = crate::db::health::apply_damage(&tx, &character_id, amount, critical.unwrap_or(false), seed)?;
+        tx.commit()?;
+        Ok(result)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Heals a character up to their HP maximum. Healing from 0 HP clears death saves.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `amount` - HP regained
+///
+/// # Returns
+/// HP before and after healing
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the amount is negative or the character is dead
+#[tauri::command]
+pub async fn apply_healing(
+    db: State<'_, Database>,
+    character_id: String,
+    amount: i32,
+) -> Result<HealingResult, String> {
+    let result: AppResult<HealingResult> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let result = crate::db::health::apply_healing(&tx, &character_id, amount)?;
+        tx.commit()?;
+        Ok(result)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Grants temporary HP. They don't stack: the higher value is kept unless `replace` is set.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `amount` - Temporary HP
+/// * `replace` - Overwrite existing temporary HP (e.g. 0 to remove them)
+///
+/// # Returns
+/// Temporary HP before and after
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the amount is negative
+#[tauri::command]
+pub async fn set_temp_hp(
+    db: State<'_, Database>,
+    character_id: String,
+    amount: i32,
+    replace: Option<bool>,
+) -> Result<TempHpResult, String> {
+    let result: AppResult<TempHpResult> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let result = crate::db::health::set_temp_hp(&tx, &character_id, amount, replace.unwrap_or(false))?;
+        tx.commit()?;
+        Ok(result)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Rolls a death saving throw (d20, DC 10; natural 1 = two failures, natural 20 = 1 HP).
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `seed` - Optional seed to reproduce the roll
+///
+/// # Returns
+/// The roll, its outcome and the resulting death save counters
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the character is not dying
+#[tauri::command]
+pub async fn roll_death_save(
+    db: State<'_, Database>,
+    character_id: String,
+    seed: Option<u64>,
+) -> Result<DeathSaveResult, String> {
+    let result: AppResult<DeathSaveResult> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let result = crate::db::health::roll_death_save(&tx, &character_id, seed)?;
+        tx.commit()?;
+        Ok(result)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
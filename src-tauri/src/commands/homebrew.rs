@@ -1,6 +1,6 @@
 use tauri::State;
 use crate::db::Database;
-use crate::error::{AppError, AppResult, map_lock_error};
+use crate::error::{AppError, AppResult};
 use crate::types::spell::CustomSpell;
 use crate::types::weapons::CustomWeapon;
 use crate::types::compendium::{CustomArmor, CustomItem, CustomMagicItem, CustomSpecies, CustomClass, CustomFeat, CustomBackground};
@@ -24,7 +24,7 @@ pub async fn upsert_custom_spell(
     spell: CustomSpell,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         
         let id = spell.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = spell.is_homebrew.unwrap_or(spell.parent_id.is_none());
@@ -48,6 +48,7 @@ pub async fn upsert_custom_spell(
                 data_json, spell.parent_id, is_homebrew
             ],
         )?;
+        crate::db::spell_mechanics::refresh_spell_mechanics(&conn, &id)?;
         
         Ok(id)
     })();
@@ -72,7 +73,7 @@ pub async fn upsert_custom_weapon(
     weapon: CustomWeapon,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = weapon.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = weapon.is_homebrew.unwrap_or(weapon.parent_id.is_none());
         let data_json = serde_json::to_string(&weapon.data)?;
@@ -115,7 +116,7 @@ pub async fn upsert_custom_armor(
     armor: CustomArmor,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = armor.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = armor.is_homebrew.unwrap_or(armor.parent_id.is_none());
         let data_json = serde_json::to_string(&armor.data)?;
@@ -159,7 +160,7 @@ pub async fn upsert_custom_item(
     item: CustomItem,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = item.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = item.is_homebrew.unwrap_or(item.parent_id.is_none());
         let data_json = serde_json::to_string(&item.data)?;
@@ -209,7 +210,7 @@ pub async fn delete_custom_entry(
     table_type: String,
 ) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let table = match table_type.as_str() {
             "spell" => "custom_spells",
             "weapon" => "custom_weapons",
@@ -226,6 +227,9 @@ pub async fn delete_custom_entry(
 
         let sql = format!("DELETE FROM {} WHERE id = ?", table);
         conn.execute(&sql, params![id])?;
+        if table == "custom_spells" {
+            crate::db::spell_mechanics::refresh_spell_mechanics(&conn, &id)?;
+        }
         
         Ok(())
     })();
@@ -250,7 +254,7 @@ pub async fn upsert_custom_magic_item(
     item: CustomMagicItem,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = item.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = item.is_homebrew.unwrap_or(item.parent_id.is_none());
 
@@ -283,7 +287,7 @@ pub async fn upsert_custom_species(
     species: CustomSpecies,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = species.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = species.is_homebrew.unwrap_or(species.parent_id.is_none());
         let data_json = serde_json::to_string(&species.data)?;
@@ -309,7 +313,7 @@ pub async fn upsert_custom_class(
     class: CustomClass,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = class.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = class.is_homebrew.unwrap_or(class.parent_id.is_none());
         let data_json = serde_json::to_string(&class.data)?;
@@ -335,7 +339,7 @@ pub async fn upsert_custom_feat(
     feat: CustomFeat,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = feat.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = feat.is_homebrew.unwrap_or(feat.parent_id.is_none());
         let data_json = serde_json::to_string(&feat.data)?;
@@ -361,7 +365,7 @@ pub async fn upsert_custom_background(
     background: CustomBackground,
 ) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         let id = background.id.clone().unwrap_or_else(|| Uuid::new_v4().to_string());
         let is_homebrew = background.is_homebrew.unwrap_or(background.parent_id.is_none());
         let data_json = serde_json::to_string(&background.data)?;
@@ -0,0 +1,83 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::level_up::{LevelUpChoices, LevelUpPreview, LevelUpRecord};
+use crate::error::{AppResult};
+
+/// Shows what a character gains at the next level.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `class_id` - Class to level in; defaults to the starting class, a new class means multiclassing
+///
+/// # Returns
+/// New features, pending choices (subclass, ASI or feat, fighting style, feature options),
+/// HP options, spell slot changes and, when multiclassing, unmet prerequisites and gained proficiencies
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the character has no class or is already level 20
+#[tauri::command]
+pub async fn preview_level_up(
+    db: State<'_, Database>,
+    character_id: String,
+    class_id: Option<String>,
+) -> Result<LevelUpPreview, String> {
+    let result: AppResult<LevelUpPreview> = (|| {
+        let conn = db.read()?;
+        crate::db::level_up::preview_level_up(&conn, &character_id, class_id.as_deref())
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Raises a character by one level and records it in the level history.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `choices` - Class to level in, selections for every pending choice of `preview_level_up`,
+///   ability increases, multiclass skills or instrument and an optional hit die roll or seed
+///
+/// # Returns
+/// The level history entry
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if a choice is missing or invalid or multiclass prerequisites are not met;
+/// the character is left unchanged
+#[tauri::command]
+pub async fn apply_level_up(
+    db: State<'_, Database>,
+    character_id: String,
+    choices: LevelUpChoices,
+) -> Result<LevelUpRecord, String> {
+    let result: AppResult<LevelUpRecord> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let record = crate::db::level_up::apply_level_up(&tx, &character_id, &choices)?;
+        tx.commit()?;
+        Ok(record)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Lists all recorded level-ups of a character.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// Level history entries, oldest first
+#[tauri::command]
+pub async fn get_level_history(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<Vec<LevelUpRecord>, String> {
+    let result: AppResult<Vec<LevelUpRecord>> = (|| {
+        let conn = db.read()?;
+        crate::db::level_up::get_level_history(&conn, &character_id)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
@@ -9,4 +9,14 @@ pub mod rag;
 pub mod logging;
 
 pub mod features;
-pub mod subclasses;
\ No newline at end of file
+pub mod subclasses;
+pub mod sheet;
+pub mod dice;
+pub mod rest;
+pub mod resources;
+pub mod health;
+pub mod conditions;
+pub mod concentration;
+pub mod level_up;
+pub mod spell_slots;
+pub mod casting;
\ No newline at end of file
@@ -2,7 +2,7 @@ use tauri::{AppHandle, Manager, WebviewWindowBuilder, WebviewUrl};
 use tauri_plugin_dialog::DialogExt;
 use crate::core::types::Character;
 use crate::db::Database;
-use crate::error::{AppError, AppResult, map_lock_error};
+use crate::error::{AppError, AppResult};
 use std::fs;
 
 #[tauri::command]
@@ -51,14 +51,9 @@ pub async fn export_character_pdf(
     let result: AppResult<String> = (|| {
         // 1. Charakter laden
         let db = app.state::<Database>();
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         
-        let mut stmt = conn.prepare("SELECT data FROM characters WHERE id = ?")?;
-        
-        let data: String = stmt.query_row([character_id.clone()], |row: &rusqlite::Row| row.get(0))
-            .map_err(|_| AppError::CharacterNotFound(character_id.clone()))?;
-        
-        let character: Character = serde_json::from_str(&data)?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
 
         // 2. HTML Template generieren
         let html = render_character_html(&character);
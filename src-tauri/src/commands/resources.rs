@@ -0,0 +1,87 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::resources::FeatureResource;
+use crate::error::{AppResult};
+
+/// Lists all limited-use features of a character (Rage, Channel Divinity, Focus Points, …).
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// Each resource with its resolved maximum, spent and remaining uses
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn get_feature_resources(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<Vec<FeatureResource>, String> {
+    let result: AppResult<Vec<FeatureResource>> = (|| {
+        let conn = db.read()?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
+        crate::db::resources::load_feature_resources(&conn, &character)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Spends uses of a feature resource.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `feature_id` - Class feature ID, e.g. `barbar_kampfrausch_l1`
+/// * `amount` - Uses to spend (default 1)
+///
+/// # Returns
+/// The updated resource
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the feature has no limited uses or not enough remain
+#[tauri::command]
+pub async fn spend_feature_resource(
+    db: State<'_, Database>,
+    character_id: String,
+    feature_id: String,
+    amount: Option<i32>,
+) -> Result<FeatureResource, String> {
+    let result: AppResult<FeatureResource> = (|| {
+        let conn = db.write()?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
+        crate::db::resources::spend_feature_resource(&conn, &character, &feature_id, amount.unwrap_or(1))
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Restores uses of a feature resource.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `feature_id` - Class feature ID
+/// * `amount` - Uses to restore (all if omitted)
+///
+/// # Returns
+/// The updated resource
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the feature has no limited uses
+#[tauri::command]
+pub async fn restore_feature_resource(
+    db: State<'_, Database>,
+    character_id: String,
+    feature_id: String,
+    amount: Option<i32>,
+) -> Result<FeatureResource, String> {
+    let result: AppResult<FeatureResource> = (|| {
+        let conn = db.write()?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
+        crate::db::resources::restore_feature_resource(&conn, &character, &feature_id, amount)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
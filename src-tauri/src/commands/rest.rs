@@ -0,0 +1,64 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::rest::RestSummary;
+use crate::error::{AppResult};
+
+/// Takes a short rest: spends Hit Dice to heal and restores Pact Magic slots
+/// and features that recharge on a short rest.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `hit_dice_to_spend` - Number of Hit Dice to roll for healing
+/// * `seed` - Optional seed to reproduce the Hit Dice rolls
+///
+/// # Returns
+/// Summary of healed HP, spent Hit Dice, restored slots and features
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the character is at 0 HP or lacks the Hit Dice
+#[tauri::command]
+pub async fn take_short_rest(
+    db: State<'_, Database>,
+    character_id: String,
+    hit_dice_to_spend: i32,
+    seed: Option<u64>,
+) -> Result<RestSummary, String> {
+    let result: AppResult<RestSummary> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let summary = crate::db::rest::take_short_rest(&tx, &character_id, hit_dice_to_spend, seed)?;
+        tx.commit()?;
+        Ok(summary)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Takes a long rest: restores all HP, Hit Dice, spell slots and features,
+/// and ends temporary HP.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// Summary of everything that was restored
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the character is at 0 HP
+#[tauri::command]
+pub async fn take_long_rest(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<RestSummary, String> {
+    let result: AppResult<RestSummary> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let summary = crate::db::rest::take_long_rest(&tx, &character_id)?;
+        tx.commit()?;
+        Ok(summary)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
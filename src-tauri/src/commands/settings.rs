@@ -1,6 +1,8 @@
-use tauri::State;
+use std::path::PathBuf;
+use tauri::{AppHandle, State};
 use crate::db::Database;
-use crate::error::{AppResult, map_lock_error};
+use crate::db::location::{self, DatabaseLocation};
+use crate::error::{AppError, AppResult};
 use rusqlite::params;
 
 /// Retrieves a setting value from the database.
@@ -17,7 +19,7 @@ use rusqlite::params;
 #[tauri::command]
 pub async fn get_setting(db: State<'_, Database>, key: String) -> Result<String, String> {
     let result: AppResult<String> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.read()?;
         
         let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?")?;
         
@@ -44,7 +46,7 @@ pub async fn get_setting(db: State<'_, Database>, key: String) -> Result<String,
 #[tauri::command]
 pub async fn set_setting(db: State<'_, Database>, key: String, value: String) -> Result<(), String> {
     let result: AppResult<()> = (|| {
-        let conn = map_lock_error(db.0.lock())?;
+        let conn = db.write()?;
         
         conn.execute(
             "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
@@ -57,13 +59,47 @@ pub async fn set_setting(db: State<'_, Database>, key: String, value: String) ->
     result.map_err(|e| e.to_string())
 }
 
+/// Returns where the user database and the compendium are located and whether the user
+/// database path comes from the environment, the setting or the default.
+///
+/// # Arguments
+/// * `app` - Tauri application handle
+///
+/// # Returns
+/// The resolved database location (as used on the next start)
+///
+/// # Errors
+/// Returns `AppError` if the setting cannot be read or the compendium is not found
+#[tauri::command]
+pub async fn get_database_location(app: AppHandle) -> Result<DatabaseLocation, String> {
+    location::resolve_location(&app).map_err(|e| e.to_string())
+}
 
+/// Sets the user database path, effective after a restart. The environment variable
+/// `DND_NEXUS_USER_DB` still takes precedence.
+///
+/// # Arguments
+/// * `app` - Tauri application handle
+/// * `path` - New path, or `None` for the default in the app data dir
+///
+/// # Returns
+/// The resolved database location for the next start
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` if the path is empty or relative
+/// Returns `AppError` if the setting cannot be written
+#[tauri::command]
+pub async fn set_database_location(app: AppHandle, path: Option<String>) -> Result<DatabaseLocation, String> {
+    let result: AppResult<DatabaseLocation> = (|| {
+        let path = path.map(PathBuf::from);
+        if let Some(path) = &path {
+            if !path.is_absolute() {
+                return Err(AppError::InvalidInput(format!("Pfad muss absolut sein: {}", path.to_string_lossy())));
+            }
+        }
+        location::set_user_database_setting(&app, path)?;
+        location::resolve_location(&app)
+    })();
 
-
-
-
-
-
-
-
-
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,55 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::attacks::WeaponAttack;
+use crate::core::sheet::DerivedSheet;
+use crate::error::{AppResult};
+
+/// Computes all derived values (modifiers, saves, skills, AC, speed, ...) for a character.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// The derived sheet as computed by `core::sheet`
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn get_derived_sheet(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<DerivedSheet, String> {
+    let result: AppResult<DerivedSheet> = (|| {
+        let conn = db.read()?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
+        crate::db::sheet::compute_sheet(&conn, &character)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
+
+/// Computes to-hit and damage for every equipped weapon of a character.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+///
+/// # Returns
+/// One entry per equipped weapon, including usable weapon masteries
+///
+/// # Errors
+/// Returns `AppError::CharacterNotFound` if the character doesn't exist
+#[tauri::command]
+pub async fn get_character_attacks(
+    db: State<'_, Database>,
+    character_id: String,
+) -> Result<Vec<WeaponAttack>, String> {
+    let result: AppResult<Vec<WeaponAttack>> = (|| {
+        let conn = db.read()?;
+        let character = crate::db::characters::load_character(&conn, &character_id)?;
+        crate::db::attacks::compute_attacks(&conn, &character)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
@@ -0,0 +1,36 @@
+use tauri::State;
+use crate::db::Database;
+use crate::core::sheet::DerivedSheet;
+use crate::error::{AppResult};
+
+/// Overrides a derived spell slot maximum. The override is stored as a modifier,
+/// so the sheet breakdown shows it next to the value from the class progression.
+///
+/// # Arguments
+/// * `db` - Database connection state
+/// * `character_id` - Character UUID as string
+/// * `target` - `spell_slots:<1-9>`, `pact_slots` or `pact_slot_level`
+/// * `value` - New maximum; `None` removes the override
+///
+/// # Returns
+/// The recomputed derived sheet
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` for an unknown target or a negative value
+#[tauri::command]
+pub async fn set_spell_slot_override(
+    db: State<'_, Database>,
+    character_id: String,
+    target: String,
+    value: Option<i32>,
+) -> Result<DerivedSheet, String> {
+    let result: AppResult<DerivedSheet> = (|| {
+        let mut conn = db.write()?;
+        let tx = conn.transaction()?;
+        let sheet = crate::db::spell_slots::set_spell_slot_override(&tx, &character_id, &target, value)?;
+        tx.commit()?;
+        Ok(sheet)
+    })();
+
+    result.map_err(|e| e.to_string())
+}
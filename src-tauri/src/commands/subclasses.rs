@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 use crate::db::Database;
-use crate::error::{AppResult, map_lock_error};
+use crate::error::{AppResult};
 use rusqlite::params;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,7 +17,7 @@ pub async fn get_subclasses(
     class_id: String,
 ) -> Result<Vec<SubclassData>, String> {
     let result: AppResult<Vec<SubclassData>> = (|| {
-        let conn = map_lock_error(state.0.lock())?;
+        let conn = state.read()?;
         
         let mut stmt = conn.prepare(
             "SELECT id, name, class_id FROM all_subclasses WHERE class_id = ? ORDER BY name"
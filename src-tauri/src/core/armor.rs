@@ -0,0 +1,526 @@
+//! Rüstungsklasse (RK) nach den Regeln von 2024.
+//!
+//! Jede mögliche Berechnung (angelegte Rüstung, ungerüstet, Ungerüstete
+//! Verteidigung, Magierrüstung, Drakonische Widerstandskraft …) wird als
+//! `AcOption` ausgewertet; der Charakter nutzt automatisch die beste gültige.
+
+use serde::Serialize;
+use crate::core::conditions::is_condition;
+use crate::core::multiclass::class_key;
+use crate::types::character::ClassLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArmorCategory {
+    Light,
+    Medium,
+    Heavy,
+    Shield,
+}
+
+impl ArmorCategory {
+    /// Kategorie-ID aus `core_armors.category`.
+    pub fn from_db(category: &str) -> Option<Self> {
+        match category {
+            "leichte_ruestung" => Some(Self::Light),
+            "mittelschwere_ruestung" => Some(Self::Medium),
+            "schwere_ruestung" => Some(Self::Heavy),
+            "schild" => Some(Self::Shield),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Medium => "medium",
+            Self::Heavy => "heavy",
+            Self::Shield => "shield",
+        }
+    }
+
+    /// Prüft einen Eintrag aus `CharacterProficiencies.armor` ("light_armor", "Leichte Rüstung", "schild" …).
+    pub fn matches_training(&self, entry: &str) -> bool {
+        let entry = entry.trim().to_lowercase().replace(['_', '-'], " ");
+        if matches!(entry.as_str(), "all armor" | "alle rüstungen" | "alle ruestungen") {
+            return *self != Self::Shield;
+        }
+        let accepted: &[&str] = match self {
+            Self::Light => &["light", "light armor", "leicht", "leichte ruestung", "leichte rüstung"],
+            Self::Medium => &["medium", "medium armor", "mittelschwer", "mittelschwere ruestung", "mittelschwere rüstung"],
+            Self::Heavy => &["heavy", "heavy armor", "schwer", "schwere ruestung", "schwere rüstung"],
+            Self::Shield => &["shield", "shields", "schild", "schilde"],
+        };
+        accepted.contains(&entry.as_str())
+    }
+
+    /// DEX-Grenze, falls die Rüstung keine Formel mitbringt.
+    fn default_dex_cap(&self) -> Option<i32> {
+        match self {
+            Self::Light | Self::Shield => None,
+            Self::Medium => Some(2),
+            Self::Heavy => Some(0),
+        }
+    }
+}
+
+/// Angelegte Rüstung oder angelegter Schild.
+#[derive(Debug, Clone)]
+pub struct ArmorPiece {
+    pub name: String,
+    pub category: ArmorCategory,
+    pub base_ac: Option<i32>,
+    /// Bonus von Schilden (+2)
+    pub ac_bonus: i32,
+    /// z.B. "12 + DEX (max. 2)", "16", "+2"
+    pub ac_formula: Option<String>,
+    /// Magischer Bonus (`core_mag_armor.ac_bonus`, z.B. Rüstung +1)
+    pub magic_bonus: i32,
+    /// Name des magischen Gegenstands für die Herleitung
+    pub magic_source: Option<String>,
+    /// Mindest-Stärke, sonst -10 ft Bewegungsrate
+    pub strength_requirement: Option<i32>,
+    pub stealth_disadvantage: bool,
+}
+
+impl ArmorPiece {
+    pub fn new(name: &str, category: ArmorCategory) -> Self {
+        Self {
+            name: name.to_string(),
+            category,
+            base_ac: None,
+            ac_bonus: 0,
+            ac_formula: None,
+            magic_bonus: 0,
+            magic_source: None,
+            strength_requirement: None,
+            stealth_disadvantage: false,
+        }
+    }
+
+    /// Grundformel der Rüstung; fehlt `ac_formula`, gelten `base_ac` und die DEX-Grenze der Kategorie.
+    pub fn formula(&self) -> AcFormula {
+        if let Some(formula) = self.ac_formula.as_deref().and_then(parse_ac_formula) {
+            return formula;
+        }
+        if self.category == ArmorCategory::Shield {
+            return AcFormula { base: self.ac_bonus, abilities: Vec::new() };
+        }
+        AcFormula {
+            base: self.base_ac.unwrap_or(10),
+            abilities: vec![AbilityTerm {
+                ability: "dex".to_string(),
+                cap: self.category.default_dex_cap(),
+            }],
+        }
+    }
+
+    /// RK-Bonus eines Schilds (Formel "+2" oder `ac_bonus`).
+    fn shield_bonus(&self) -> i32 {
+        if self.ac_bonus > 0 {
+            self.ac_bonus
+        } else {
+            self.formula().base
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbilityTerm {
+    pub ability: String,
+    /// Höchstwert, z.B. 2 bei mittelschwerer Rüstung
+    pub cap: Option<i32>,
+}
+
+/// Zerlegte RK-Formel: Grundwert plus Attributsmodifikatoren.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcFormula {
+    pub base: i32,
+    pub abilities: Vec<AbilityTerm>,
+}
+
+fn normalize_ability(token: &str) -> Option<&'static str> {
+    match token.to_uppercase().as_str() {
+        "STR" | "STÄ" => Some("str"),
+        "DEX" | "GES" => Some("dex"),
+        "CON" | "KON" => Some("con"),
+        "INT" => Some("int"),
+        "WIS" | "WEI" => Some("wis"),
+        "CHA" => Some("cha"),
+        _ => None,
+    }
+}
+
+/// Parst Formeln wie "12 + DEX (max. 2)", "10 + DEX + CON", "16" oder "+2".
+pub fn parse_ac_formula(formula: &str) -> Option<AcFormula> {
+    let mut base = 0;
+    let mut abilities = Vec::new();
+    let mut has_term = false;
+
+    for part in formula.split('+').map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+        has_term = true;
+        if let Ok(value) = part.parse::<i32>() {
+            base += value;
+            continue;
+        }
+        let (name, rest) = part.split_once('(').unwrap_or((part, ""));
+        let ability = normalize_ability(name.trim())?;
+        let cap = if rest.is_empty() {
+            None
+        } else {
+            let digits: String = rest.chars().filter(|c| c.is_ascii_digit()).collect();
+            Some(digits.parse().ok()?)
+        };
+        abilities.push(AbilityTerm { ability: ability.to_string(), cap });
+    }
+
+    has_term.then_some(AcFormula { base, abilities })
+}
+
+/// Alternative Grundformeln ohne Rüstung.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcAlternative {
+    /// Barbar: 10 + DEX + CON, Schild erlaubt
+    BarbarianUnarmoredDefense,
+    /// Mönch: 10 + DEX + WIS, ohne Schild
+    MonkUnarmoredDefense,
+    /// Magierrüstung: 13 + DEX
+    MageArmor,
+    /// Drakonische Zauberei: 10 + DEX + CHA
+    DraconicResilience,
+}
+
+impl AcAlternative {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BarbarianUnarmoredDefense | Self::MonkUnarmoredDefense => "Ungerüstete Verteidigung",
+            Self::MageArmor => "Magierrüstung",
+            Self::DraconicResilience => "Drakonische Widerstandskraft",
+        }
+    }
+
+    fn formula(&self) -> AcFormula {
+        let term = |ability: &str| AbilityTerm { ability: ability.to_string(), cap: None };
+        match self {
+            Self::BarbarianUnarmoredDefense => AcFormula { base: 10, abilities: vec![term("dex"), term("con")] },
+            Self::MonkUnarmoredDefense => AcFormula { base: 10, abilities: vec![term("dex"), term("wis")] },
+            Self::MageArmor => AcFormula { base: 13, abilities: vec![term("dex")] },
+            Self::DraconicResilience => AcFormula { base: 10, abilities: vec![term("dex"), term("cha")] },
+        }
+    }
+
+    fn allows_shield(&self) -> bool {
+        !matches!(self, Self::MonkUnarmoredDefense)
+    }
+}
+
+/// Alternative RK-Formeln aus den Klassen (mit eigener Stufe und Unterklasse) und aktiven Effekten.
+///
+/// Ungerüstete Verteidigung gibt es nur einmal: eine zweite Klasse mit dem Merkmal bringt
+/// keine weitere Formel (PHB 2024, Mehrklassen). Maßgeblich ist die zuerst genommene Klasse.
+pub fn ac_alternatives(classes: &[ClassLevel], active_conditions: &[String]) -> Vec<AcAlternative> {
+    let mut alternatives = Vec::new();
+    let mut unarmored_defense = false;
+    for class in classes {
+        let unarmored = match class_key(&class.class_id).as_str() {
+            "barbar" => Some(AcAlternative::BarbarianUnarmoredDefense),
+            "moench" => Some(AcAlternative::MonkUnarmoredDefense),
+            _ => None,
+        };
+        if let Some(alternative) = unarmored.filter(|_| !unarmored_defense) {
+            alternatives.push(alternative);
+            unarmored_defense = true;
+        }
+        if class_key(&class.class_id) == "zauberer"
+            && class.level >= 3
+            && class.subclass_id.as_deref().is_some_and(|s| s.contains("drakonisch"))
+        {
+            alternatives.push(AcAlternative::DraconicResilience);
+        }
+    }
+    if active_conditions.iter().any(|c| is_condition(c, &["mage_armor", "Magierrüstung"])) {
+        alternatives.push(AcAlternative::MageArmor);
+    }
+    alternatives
+}
+
+/// Rüstungsausbildung der Klassen (PHB 2024).
+pub fn class_armor_training(class_id: &str) -> &'static [ArmorCategory] {
+    use ArmorCategory::*;
+    match class_key(class_id).as_str() {
+        "kaempfer" | "paladin" => &[Light, Medium, Heavy, Shield],
+        "barbar" | "kleriker" | "waldlaeufer" => &[Light, Medium, Shield],
+        "druide" => &[Light, Shield],
+        "barde" | "schurke" | "hexenmeister" => &[Light],
+        _ => &[],
+    }
+}
+
+/// Folgen der getragenen Rüstung (PHB 2024, Kapitel 6).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArmorPenalties {
+    /// Abzug auf die Bewegungsrate, wenn die Stärke-Anforderung nicht erfüllt ist
+    pub speed_penalty_ft: i32,
+    pub stealth_disadvantage: bool,
+    /// Nachteil bei allen W20-Tests mit STR oder DEX (Rüstung ohne Ausbildung)
+    pub str_dex_disadvantage: bool,
+    /// Kein Zauberwirken möglich (Rüstung ohne Ausbildung)
+    pub spellcasting_blocked: bool,
+    /// Schild ohne Ausbildung gibt keinen RK-Bonus
+    pub shield_ignored: bool,
+}
+
+/// Prüft Stärke-Anforderung, Heimlichkeits-Nachteil und Rüstungsausbildung.
+/// `training` sind die Einträge aus `CharacterProficiencies.armor`.
+pub fn armor_penalties(
+    armor: Option<&ArmorPiece>,
+    shield: Option<&ArmorPiece>,
+    str_score: i32,
+    class_id: Option<&str>,
+    training: &[String],
+) -> ArmorPenalties {
+    let trained = |category: ArmorCategory| {
+        class_id.is_some_and(|c| class_armor_training(c).contains(&category))
+            || training.iter().any(|t| category.matches_training(t))
+    };
+    let mut penalties = ArmorPenalties::default();
+
+    if let Some(armor) = armor {
+        if armor.strength_requirement.is_some_and(|req| str_score < req) {
+            penalties.speed_penalty_ft = 10;
+        }
+        penalties.stealth_disadvantage = armor.stealth_disadvantage;
+        if !trained(armor.category) {
+            penalties.str_dex_disadvantage = true;
+            penalties.spellcasting_blocked = true;
+        }
+    }
+    if let Some(shield) = shield {
+        penalties.stealth_disadvantage |= shield.stealth_disadvantage;
+        penalties.shield_ignored = !trained(ArmorCategory::Shield);
+    }
+    penalties
+}
+
+/// Eine mögliche RK-Berechnung samt Herleitung.
+#[derive(Debug, Clone, Serialize)]
+pub struct AcOption {
+    pub source: String,
+    pub parts: Vec<(String, i32)>,
+    pub total: i32,
+}
+
+/// Alle gültigen RK-Berechnungen; `options[best]` wird verwendet.
+#[derive(Debug, Clone, Serialize)]
+pub struct AcResolution {
+    pub options: Vec<AcOption>,
+    pub best: usize,
+}
+
+impl AcResolution {
+    pub fn best_option(&self) -> &AcOption {
+        &self.options[self.best]
+    }
+}
+
+fn formula_parts(label: &str, formula: &AcFormula, ability_mod: &dyn Fn(&str) -> i32) -> Vec<(String, i32)> {
+    let mut parts = vec![(label.to_string(), formula.base)];
+    for term in &formula.abilities {
+        let value = ability_mod(&term.ability);
+        let value = term.cap.map_or(value, |cap| value.min(cap));
+        parts.push((term.ability.to_uppercase(), value));
+    }
+    parts
+}
+
+/// Wertet alle RK-Optionen aus und wählt die höchste.
+pub fn resolve_armor_class(
+    armor: Option<&ArmorPiece>,
+    shield: Option<&ArmorPiece>,
+    alternatives: &[AcAlternative],
+    ability_mod: &dyn Fn(&str) -> i32,
+) -> AcResolution {
+    // (Quelle, Teile ohne Schild)
+    let mut candidates: Vec<(String, Vec<(String, i32)>)> = Vec::new();
+
+    match armor {
+        Some(armor) => {
+            let mut parts = formula_parts("Rüstung", &armor.formula(), ability_mod);
+            if armor.magic_bonus != 0 {
+                let source = armor.magic_source.clone().unwrap_or_else(|| armor.name.clone());
+                parts.push((source, armor.magic_bonus));
+            }
+            candidates.push((armor.name.clone(), parts));
+        }
+        None => {
+            let unarmored = AcFormula {
+                base: 10,
+                abilities: vec![AbilityTerm { ability: "dex".to_string(), cap: None }],
+            };
+            candidates.push(("Ungerüstet".to_string(), formula_parts("Basis", &unarmored, ability_mod)));
+            for alternative in alternatives.iter().filter(|a| shield.is_none() || a.allows_shield()) {
+                candidates.push((
+                    alternative.label().to_string(),
+                    formula_parts("Basis", &alternative.formula(), ability_mod),
+                ));
+            }
+        }
+    }
+
+    let options: Vec<AcOption> = candidates
+        .into_iter()
+        .map(|(source, mut parts)| {
+            if let Some(shield) = shield {
+                parts.push(("Schild".to_string(), shield.shield_bonus()));
+                if shield.magic_bonus != 0 {
+                    let magic = shield.magic_source.clone().unwrap_or_else(|| shield.name.clone());
+                    parts.push((magic, shield.magic_bonus));
+                }
+            }
+            let total = parts.iter().map(|(_, v)| v).sum();
+            AcOption { source, parts, total }
+        })
+        .collect();
+
+    // Bei Gleichstand gewinnt die zuerst genannte Option (Rüstung bzw. ungerüstet)
+    let best = options
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, o)| if o.total > options[best].total { i } else { best });
+
+    AcResolution { options, best }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mods(ability: &str) -> i32 {
+        match ability {
+            "dex" => 3,
+            "con" => 2,
+            "wis" => 4,
+            "cha" => 1,
+            _ => 0,
+        }
+    }
+
+    fn armor(category: ArmorCategory, formula: &str) -> ArmorPiece {
+        ArmorPiece {
+            ac_formula: Some(formula.to_string()),
+            ..ArmorPiece::new("Testrüstung", category)
+        }
+    }
+
+    #[test]
+    fn test_parse_ac_formula() {
+        let medium = parse_ac_formula("12 + DEX (max. 2)").unwrap();
+        assert_eq!(medium.base, 12);
+        assert_eq!(medium.abilities, vec![AbilityTerm { ability: "dex".into(), cap: Some(2) }]);
+
+        assert_eq!(parse_ac_formula("16").unwrap(), AcFormula { base: 16, abilities: vec![] });
+        assert_eq!(parse_ac_formula("+2").unwrap().base, 2);
+        assert_eq!(parse_ac_formula("10 + GES + KON").unwrap().abilities.len(), 2);
+        assert!(parse_ac_formula("").is_none());
+        assert!(parse_ac_formula("12 + Glück").is_none());
+    }
+
+    #[test]
+    fn test_resolve_armor_formulas() {
+        let medium = armor(ArmorCategory::Medium, "14 + DEX (max. 2)");
+        let ac = resolve_armor_class(Some(&medium), None, &[], &mods);
+        assert_eq!(ac.best_option().total, 16);
+
+        let mut heavy = armor(ArmorCategory::Heavy, "18");
+        heavy.magic_bonus = 1;
+        heavy.magic_source = Some("Rüstung +1".into());
+        let shield = armor(ArmorCategory::Shield, "+2");
+        let ac = resolve_armor_class(Some(&heavy), Some(&shield), &[], &mods);
+        assert_eq!(ac.best_option().total, 21);
+        assert_eq!(ac.best_option().parts.last().unwrap(), &("Schild".to_string(), 2));
+
+        // Ohne Formel: base_ac + DEX-Grenze der Kategorie
+        let legacy = ArmorPiece { base_ac: Some(13), ..ArmorPiece::new("Kettenhemd", ArmorCategory::Medium) };
+        assert_eq!(resolve_armor_class(Some(&legacy), None, &[], &mods).best_option().total, 15);
+    }
+
+    #[test]
+    fn test_resolve_picks_best_alternative() {
+        let alternatives = [AcAlternative::MonkUnarmoredDefense, AcAlternative::MageArmor];
+        let ac = resolve_armor_class(None, None, &alternatives, &mods);
+        assert_eq!(ac.options.len(), 3);
+        assert_eq!(ac.best_option().source, "Ungerüstete Verteidigung");
+        assert_eq!(ac.best_option().total, 17);
+
+        // Mönch verliert die Ungerüstete Verteidigung mit Schild
+        let shield = armor(ArmorCategory::Shield, "+2");
+        let ac = resolve_armor_class(None, Some(&shield), &alternatives, &mods);
+        assert_eq!(ac.best_option().source, "Magierrüstung");
+        assert_eq!(ac.best_option().total, 18);
+
+        // Alternativen gelten nur ohne Rüstung
+        let light = armor(ArmorCategory::Light, "11 + DEX");
+        let ac = resolve_armor_class(Some(&light), None, &alternatives, &mods);
+        assert_eq!(ac.options.len(), 1);
+        assert_eq!(ac.best_option().total, 14);
+    }
+
+    #[test]
+    fn test_armor_penalties() {
+        let mut plate = armor(ArmorCategory::Heavy, "18");
+        plate.strength_requirement = Some(15);
+        plate.stealth_disadvantage = true;
+        let shield = armor(ArmorCategory::Shield, "+2");
+
+        let fighter = armor_penalties(Some(&plate), Some(&shield), 16, Some("kaempfer"), &[]);
+        assert_eq!(fighter, ArmorPenalties { stealth_disadvantage: true, ..ArmorPenalties::default() });
+
+        let weak = armor_penalties(Some(&plate), None, 13, Some("paladin"), &[]);
+        assert_eq!(weak.speed_penalty_ft, 10);
+
+        let wizard = armor_penalties(Some(&plate), Some(&shield), 16, Some("magier"), &[]);
+        assert!(wizard.str_dex_disadvantage && wizard.spellcasting_blocked && wizard.shield_ignored);
+
+        // Zusätzliche Ausbildung am Charakter (z.B. durch ein Talent)
+        let leather = armor(ArmorCategory::Light, "11 + DEX");
+        let trained = armor_penalties(Some(&leather), None, 8, Some("magier"), &["Leichte Rüstung".into()]);
+        assert_eq!(trained, ArmorPenalties::default());
+    }
+
+    fn class(class_id: &str, subclass_id: Option<&str>, level: i32) -> ClassLevel {
+        ClassLevel { class_id: class_id.to_string(), subclass_id: subclass_id.map(String::from), level }
+    }
+
+    #[test]
+    fn test_ac_alternatives() {
+        assert_eq!(ac_alternatives(&[class("barbar", None, 1)], &[]), vec![AcAlternative::BarbarianUnarmoredDefense]);
+        assert!(ac_alternatives(&[class("zauberer", Some("zauberer_drakonische_zauberei"), 2)], &[]).is_empty());
+        assert_eq!(
+            ac_alternatives(&[class("zauberer", Some("zauberer_drakonische_zauberei"), 3)], &["Magierrüstung".into()]),
+            vec![AcAlternative::DraconicResilience, AcAlternative::MageArmor]
+        );
+        for spelling in ["mage_armor", "Mage Armor", "magierruestung", "Magier-Rüstung"] {
+            assert_eq!(ac_alternatives(&[], &[spelling.into()]), vec![AcAlternative::MageArmor]);
+        }
+    }
+
+    #[test]
+    fn test_ac_alternatives_multiclass() {
+        // Nebenklasse zählt mit ihrer eigenen Stufe
+        assert_eq!(
+            ac_alternatives(&[class("kaempfer", None, 5), class("barbar", None, 1)], &[]),
+            vec![AcAlternative::BarbarianUnarmoredDefense]
+        );
+        // Drakonische Widerstandskraft erst ab Zauberer-Stufe 3, nicht Gesamtstufe 3
+        let sorcerer = class("zauberer", Some("zauberer_drakonische_zauberei"), 2);
+        assert!(ac_alternatives(&[class("kaempfer", None, 4), sorcerer], &[]).is_empty());
+        // Ungerüstete Verteidigung nur einmal, von der ersten Klasse
+        assert_eq!(
+            ac_alternatives(&[class("mönch", None, 3), class("barbar", None, 2)], &[]),
+            vec![AcAlternative::MonkUnarmoredDefense]
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Waffenangriffe: Angriffsbonus und Schaden je angelegter Waffe.
+//!
+//! Baut auf dem `DerivedSheet` auf (Attributsmodifikatoren inkl. Modifikatoren,
+//! Übungsbonus, Rüstungsfolgen). Zusätzliche Modifikator-Ziele:
+//! `attack`, `damage` (alle Waffen) sowie `attack:<weapon_id>`, `damage:<weapon_id>`.
+
+use serde::Serialize;
+use crate::core::modifiers::{active_modifiers, modifiers_for, ModifierBreakdown};
+use crate::core::multiclass::{class_key, normalize_classes};
+use crate::core::sheet::{condition_context, DerivedSheet, SheetInputs};
+use crate::types::character::{Character, Modifier};
+use crate::types::weapons::Weapon;
+
+/// Eine angelegte Waffe samt Griff und magischem Bonus.
+#[derive(Debug, Clone)]
+pub struct EquippedWeapon {
+    pub weapon: Weapon,
+    /// Anzeigename, z.B. der Name der magischen Waffe
+    pub display_name: Option<String>,
+    pub magic_attack_bonus: i32,
+    pub magic_damage_bonus: i32,
+    pub magic_source: Option<String>,
+    /// Vielseitige Waffe wird beidhändig geführt
+    pub two_handed_grip: bool,
+    /// Waffe in der Nebenhand (Zwei-Waffen-Kampf)
+    pub offhand: bool,
+}
+
+impl EquippedWeapon {
+    pub fn new(weapon: Weapon) -> Self {
+        Self {
+            weapon,
+            display_name: None,
+            magic_attack_bonus: 0,
+            magic_damage_bonus: 0,
+            magic_source: None,
+            two_handed_grip: false,
+            offhand: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct MasteryInfo {
+    pub id: String,
+    pub name: String,
+    /// true, wenn der Charakter die Meisterschaft dieser Waffe nutzen kann
+    pub usable: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct WeaponAttack {
+    pub weapon_id: String,
+    pub name: String,
+    /// Attribut für Angriff und Schaden ("str" oder "dex")
+    pub ability: String,
+    pub attack_bonus: i32,
+    pub proficient: bool,
+    /// Nachteil, z.B. durch Rüstung ohne Ausbildung oder Zustände wie Vergiftet
+    pub disadvantage: bool,
+    pub damage_dice: String,
+    pub damage_bonus: i32,
+    pub damage_type: String,
+    /// Lesbarer Schaden, z.B. "1W8+3 hieb"
+    pub damage: String,
+    /// Schadenswürfel bei beidhändiger Führung einer vielseitigen Waffe
+    pub versatile_dice: Option<String>,
+    /// Kampf mit großen Waffen: 1 und 2 auf Schadenswürfeln zählen als 3
+    pub great_weapon_fighting: bool,
+    pub properties: Vec<String>,
+    pub mastery: Option<MasteryInfo>,
+    pub attack_breakdown: ModifierBreakdown,
+    pub damage_breakdown: ModifierBreakdown,
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase().replace(['_', '-', ' '], "")
+}
+
+/// Prüft eine Waffeneigenschaft per ID oder Name (deutsch und englisch).
+pub fn has_property(weapon: &Weapon, ids: &[&str]) -> bool {
+    weapon.properties.iter().any(|p| {
+        let id = normalize(&p.id);
+        let name = normalize(&p.name);
+        ids.iter().map(|i| normalize(i)).any(|i| i == id || i == name)
+    })
+}
+
+pub fn is_ranged(weapon: &Weapon) -> bool {
+    let category = weapon.category.to_lowercase();
+    category.contains("ranged")
+        || category.contains("fernkampf")
+        || has_property(weapon, &["geschosse", "munition", "ammunition"])
+}
+
+fn is_martial(weapon: &Weapon) -> bool {
+    let category = weapon.category.to_lowercase();
+    category.starts_with("martial") || category.contains("kriegswaffe")
+}
+
+/// Schadenswürfel bei beidhändiger Führung (Eigenschaft Vielseitig).
+pub fn versatile_dice(weapon: &Weapon) -> Option<String> {
+    let from_property = weapon
+        .properties
+        .iter()
+        .filter(|p| matches!(normalize(&p.id).as_str(), "vielseitig" | "versatile"))
+        .find_map(|p| p.parameter_value.as_ref()?.get("damage")?.as_str().map(str::to_string));
+    from_property.or_else(|| {
+        weapon.data.get("versatile_damage")?.as_str().map(str::to_string)
+    })
+    .filter(|d| !d.trim().is_empty())
+}
+
+/// Waffenausbildung der Klassen (PHB 2024).
+fn class_weapon_training(class_id: &str, weapon: &Weapon) -> bool {
+    if !is_martial(weapon) {
+        return true;
+    }
+    match class_key(class_id).as_str() {
+        "barbar" | "kaempfer" | "paladin" | "waldlaeufer" => true,
+        "schurke" => has_property(weapon, &["finesse", "leicht", "light"]),
+        "moench" => has_property(weapon, &["leicht", "light"]),
+        _ => false,
+    }
+}
+
+/// Prüft `CharacterProficiencies.weapons` ("simple_weapons", "Kriegswaffen", Waffen-ID oder Name).
+fn has_weapon_training(character: &Character, weapon: &Weapon) -> bool {
+    if character
+        .meta
+        .class_id
+        .as_deref()
+        .is_some_and(|c| class_weapon_training(c, weapon))
+    {
+        return true;
+    }
+    let martial = is_martial(weapon);
+    character.proficiencies.weapons.iter().any(|entry| {
+        match normalize(entry).as_str() {
+            "simple" | "simpleweapons" | "einfachewaffen" => !martial,
+            "martial" | "martialweapons" | "kriegswaffen" => martial,
+            other => other == normalize(&weapon.id) || other == normalize(&weapon.name),
+        }
+    })
+}
+
+/// Anzahl der Waffen, deren Meisterschaft eine Klasse nutzen kann (0 = keine Waffenmeisterschaft).
+pub fn weapon_mastery_count(class_id: &str, level: i32) -> usize {
+    match class_key(class_id).as_str() {
+        "kaempfer" => match level {
+            16.. => 6,
+            10.. => 5,
+            4.. => 4,
+            _ => 3,
+        },
+        "barbar" => match level {
+            10.. => 4,
+            4.. => 3,
+            _ => 2,
+        },
+        "paladin" | "waldlaeufer" | "schurke" => 2,
+        _ => 0,
+    }
+}
+
+fn has_fighting_style(character: &Character, ids: &[&str]) -> bool {
+    character
+        .meta
+        .fighting_styles
+        .as_deref()
+        .unwrap_or_default()
+        .iter()
+        .any(|s| ids.iter().any(|id| normalize(s) == normalize(id)))
+}
+
+/// Kann der Charakter die Meisterschaft dieser Waffe nutzen?
+/// Ohne gespeicherte Auswahl (`weapon_masteries`) gelten alle geübten Waffen als gewählt.
+/// Bei Mehrklassen-Charakteren zählt jede Klasse mit ihrer eigenen Stufe.
+fn mastery_usable(character: &Character, weapon: &Weapon, proficient: bool) -> bool {
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
+    let count: usize = meta
+        .classes
+        .iter()
+        .map(|c| weapon_mastery_count(&c.class_id, c.level))
+        .sum();
+    if count == 0 || !proficient {
+        return false;
+    }
+    match &character.meta.weapon_masteries {
+        Some(chosen) => chosen.iter().take(count).any(|id| normalize(id) == normalize(&weapon.id)),
+        None => true,
+    }
+}
+
+fn format_damage(dice: &str, bonus: i32, damage_type: &str) -> String {
+    match bonus {
+        0 => format!("{} {}", dice, damage_type),
+        b if b > 0 => format!("{}+{} {}", dice, b, damage_type),
+        b => format!("{}{} {}", dice, b, damage_type),
+    }
+}
+
+/// Berechnet Angriff und Schaden für jede angelegte Waffe.
+pub fn derive_attacks(
+    character: &Character,
+    inputs: &SheetInputs,
+    sheet: &DerivedSheet,
+    weapons: &[EquippedWeapon],
+) -> Vec<WeaponAttack> {
+    let modifiers = active_modifiers(&character.modifiers, &condition_context(character, inputs));
+    let held_weapons = weapons.len();
+    weapons
+        .iter()
+        .map(|equipped| derive_attack(character, sheet, &modifiers, equipped, held_weapons))
+        .collect()
+}
+
+fn derive_attack(
+    character: &Character,
+    sheet: &DerivedSheet,
+    modifiers: &[Modifier],
+    equipped: &EquippedWeapon,
+    held_weapons: usize,
+) -> WeaponAttack {
+    let weapon = &equipped.weapon;
+    let ranged = is_ranged(weapon);
+    let thrown = has_property(weapon, &["wurfwaffe", "thrown"]);
+    let two_handed = has_property(weapon, &["zweihaendig", "zweihändig", "two-handed"]);
+
+    // 1. Attribut: Fernkampf -> DEX, Finesse -> das bessere, sonst STR
+    let str_mod = sheet.ability_modifier("str");
+    let dex_mod = sheet.ability_modifier("dex");
+    let ability = if ranged || (has_property(weapon, &["finesse"]) && dex_mod > str_mod) {
+        "dex"
+    } else {
+        "str"
+    };
+    let ability_mod = sheet.ability_modifier(ability);
+    let label = ability.to_uppercase();
+
+    // 2. Übung & magische Boni (Waffe, Eigenschaft "bonus", Inventar)
+    let proficient = has_weapon_training(character, weapon);
+    let (property_attack, property_damage) = property_bonus(weapon);
+    let magic_source = equipped.magic_source.clone().unwrap_or_else(|| "Magie".to_string());
+    let magic_attack = equipped.magic_attack_bonus + property_attack;
+    let magic_damage = equipped.magic_damage_bonus + property_damage;
+
+    // 3. Kampfstile
+    let archery = ranged && has_fighting_style(character, &["bogenschiessen", "archery"]);
+    let dueling = !ranged
+        && !two_handed
+        && !equipped.two_handed_grip
+        && held_weapons == 1
+        && has_fighting_style(character, &["duellieren", "dueling"]);
+    let thrown_style = thrown && has_fighting_style(character, &["wurfwaffenkampf", "thrown-weapon-fighting"]);
+    let great_weapon_fighting = !ranged
+        && (two_handed || (equipped.two_handed_grip && versatile_dice(weapon).is_some()))
+        && has_fighting_style(character, &["kampf-mit-grossen-waffen", "great-weapon-fighting"]);
+    let offhand_ability = !equipped.offhand
+        || has_fighting_style(character, &["zwei-waffen-kampf", "two-weapon-fighting"]);
+
+    let attack_breakdown = ModifierBreakdown::from_nonzero_parts(&[
+        (label.as_str(), ability_mod),
+        ("Übung", if proficient { sheet.proficiency_bonus } else { 0 }),
+        (magic_source.as_str(), magic_attack),
+        ("Bogenschießen", if archery { 2 } else { 0 }),
+        ("Erschöpfung", -sheet.condition_effects.d20_penalty),
+    ])
+    .apply(&modifiers_for(modifiers, "attack"))
+    .apply(&modifiers_for(modifiers, &format!("attack:{}", weapon.id)));
+
+    let damage_breakdown = ModifierBreakdown::from_nonzero_parts(&[
+        (label.as_str(), if offhand_ability || ability_mod < 0 { ability_mod } else { 0 }),
+        (magic_source.as_str(), magic_damage),
+        ("Duellieren", if dueling { 2 } else { 0 }),
+        ("Wurfwaffenkampf", if thrown_style { 2 } else { 0 }),
+    ])
+    .apply(&modifiers_for(modifiers, "damage"))
+    .apply(&modifiers_for(modifiers, &format!("damage:{}", weapon.id)));
+
+    // 4. Schadenswürfel (vielseitig beidhändig)
+    let versatile = versatile_dice(weapon);
+    let damage_dice = match (&versatile, equipped.two_handed_grip) {
+        (Some(dice), true) => dice.clone(),
+        _ => weapon.damage_dice.clone(),
+    };
+    let damage_bonus = damage_breakdown.total;
+
+    let mastery = weapon.mastery.as_ref().map(|m| MasteryInfo {
+        id: m.id.clone(),
+        name: m.name.clone(),
+        usable: mastery_usable(character, weapon, proficient),
+    });
+
+    WeaponAttack {
+        weapon_id: weapon.id.clone(),
+        name: equipped.display_name.clone().unwrap_or_else(|| weapon.name.clone()),
+        ability: ability.to_string(),
+        attack_bonus: attack_breakdown.total,
+        proficient,
+        disadvantage: sheet.armor_penalties.str_dex_disadvantage || sheet.condition_effects.attack_disadvantage,
+        damage: format_damage(&damage_dice, damage_bonus, &weapon.damage_type),
+        damage_dice,
+        damage_bonus,
+        damage_type: weapon.damage_type.clone(),
+        versatile_dice: versatile,
+        great_weapon_fighting,
+        properties: weapon.properties.iter().map(|p| p.name.clone()).collect(),
+        mastery,
+        attack_breakdown,
+        damage_breakdown,
+    }
+}
+
+/// Boni aus Eigenschaften vom Typ "bonus" (z.B. benutzerdefinierte +1-Waffen).
+/// Fehlt `damage_bonus`, gilt der Angriffsbonus auch für den Schaden.
+fn property_bonus(weapon: &Weapon) -> (i32, i32) {
+    weapon
+        .properties
+        .iter()
+        .filter(|p| p.parameter_type.as_deref() == Some("bonus"))
+        .filter_map(|p| p.parameter_value.as_ref())
+        .fold((0, 0), |(attack, damage), value| {
+            let atk = value.get("attack_bonus").and_then(|v| v.as_i64()).map(|v| v as i32);
+            let dmg = value.get("damage_bonus").and_then(|v| v.as_i64()).map(|v| v as i32);
+            (attack + atk.unwrap_or(0), damage + dmg.or(atk).unwrap_or(0))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::derive_sheet;
+    use crate::core::sheet::tests::test_character;
+    use crate::types::character::ClassLevel;
+    use crate::types::weapons::{WeaponMastery, WeaponProperty};
+
+    fn property(id: &str, parameter_value: Option<serde_json::Value>) -> WeaponProperty {
+        WeaponProperty {
+            id: id.into(),
+            name: id.into(),
+            description: String::new(),
+            has_parameter: parameter_value.is_some(),
+            parameter_type: None,
+            parameter_value,
+        }
+    }
+
+    fn weapon(id: &str, category: &str, dice: &str, properties: Vec<WeaponProperty>) -> Weapon {
+        Weapon {
+            id: id.into(),
+            name: id.into(),
+            category: category.into(),
+            category_label: None,
+            weapon_subtype: None,
+            mastery_id: "auslaugen".into(),
+            damage_dice: dice.into(),
+            damage_type: "hieb".into(),
+            weight_kg: 1.0,
+            cost_gp: 1.0,
+            properties,
+            mastery: Some(WeaponMastery {
+                id: "auslaugen".into(),
+                name: "Auslaugen".into(),
+                description: String::new(),
+            }),
+            data: serde_json::json!({}),
+            source: "core".into(),
+        }
+    }
+
+    fn attacks(character: &Character, weapons: &[EquippedWeapon]) -> Vec<WeaponAttack> {
+        let inputs = SheetInputs::default();
+        let sheet = derive_sheet(character, &inputs);
+        derive_attacks(character, &inputs, &sheet, weapons)
+    }
+
+    #[test]
+    fn test_versatile_and_proficiency() {
+        // Kämpfer Stufe 5: STR 16 (+3), Übung +3
+        let longsword = weapon(
+            "langschwert",
+            "martial_melee",
+            "1W8",
+            vec![property("vielseitig", Some(serde_json::json!({ "damage": "1W10" })))],
+        );
+        let mut two_handed = EquippedWeapon::new(longsword.clone());
+        two_handed.two_handed_grip = true;
+        let result = attacks(&test_character(), &[EquippedWeapon::new(longsword), two_handed]);
+
+        assert_eq!(result[0].attack_bonus, 6);
+        assert_eq!(result[0].damage, "1W8+3 hieb");
+        assert_eq!(result[1].damage_dice, "1W10");
+        assert!(result[0].mastery.as_ref().unwrap().usable);
+
+        let mut wizard = test_character();
+        wizard.meta.class_id = Some("magier".into());
+        let result = attacks(&wizard, &[EquippedWeapon::new(weapon("langschwert", "martial_melee", "1W8", vec![]))]);
+        assert!(!result[0].proficient);
+        assert_eq!(result[0].attack_bonus, 3);
+        assert!(!result[0].mastery.as_ref().unwrap().usable);
+    }
+
+    #[test]
+    fn test_finesse_ranged_and_magic() {
+        let mut character = test_character();
+        character.attributes.dex = 18;
+        let rapier = weapon("rapier", "martial_melee", "1W8", vec![property("finesse", None)]);
+        let mut magic = EquippedWeapon::new(rapier);
+        magic.magic_attack_bonus = 1;
+        magic.magic_damage_bonus = 1;
+        magic.magic_source = Some("Waffe +1".into());
+        let bow = weapon("langbogen", "martial_ranged", "1W8", vec![property("geschosse", None)]);
+
+        let result = attacks(&character, &[magic, EquippedWeapon::new(bow)]);
+        assert_eq!(result[0].ability, "dex");
+        assert_eq!(result[0].attack_bonus, 8);
+        assert_eq!(result[0].damage, "1W8+5 hieb");
+        assert_eq!(result[0].attack_breakdown.describe(), "4 DEX + 3 Übung + 1 Waffe +1");
+        assert_eq!(result[1].ability, "dex");
+    }
+
+    #[test]
+    fn test_fighting_styles() {
+        let mut character = test_character();
+        character.meta.fighting_styles = Some(vec!["duellieren".into(), "bogenschiessen".into()]);
+        let result = attacks(&character, &[EquippedWeapon::new(weapon("langschwert", "martial_melee", "1W8", vec![]))]);
+        assert_eq!(result[0].damage_bonus, 5);
+
+        let bow = weapon("langbogen", "martial_ranged", "1W8", vec![]);
+        let result = attacks(&character, &[EquippedWeapon::new(bow)]);
+        assert_eq!(result[0].attack_bonus, 7);
+
+        // Nebenhand ohne Zwei-Waffen-Kampf: kein Attributsbonus auf den Schaden
+        let dagger = weapon("dolch", "simple_melee", "1W4", vec![property("leicht", None)]);
+        let mut offhand = EquippedWeapon::new(dagger.clone());
+        offhand.offhand = true;
+        let result = attacks(&test_character(), &[EquippedWeapon::new(dagger), offhand]);
+        assert_eq!(result[0].damage_bonus, 3);
+        assert_eq!(result[1].damage_bonus, 0);
+    }
+
+    #[test]
+    fn test_mastery_selection() {
+        let mut character = test_character();
+        character.meta.weapon_masteries = Some(vec!["dolch".into()]);
+        let result = attacks(&character, &[EquippedWeapon::new(weapon("langschwert", "martial_melee", "1W8", vec![]))]);
+        assert!(!result[0].mastery.as_ref().unwrap().usable);
+        assert_eq!(weapon_mastery_count("kaempfer", 4), 4);
+        assert_eq!(weapon_mastery_count("Kämpfer", 4), 4);
+        assert_eq!(weapon_mastery_count("magier", 20), 0);
+    }
+
+    #[test]
+    fn test_mastery_count_multiclass() {
+        let longsword = || EquippedWeapon::new(weapon("langschwert", "martial_melee", "1W8", vec![]));
+        let mut character = test_character();
+        character.meta.level = 11;
+        character.meta.classes = vec![
+            ClassLevel { class_id: "kaempfer".into(), subclass_id: None, level: 1 },
+            ClassLevel { class_id: "magier".into(), subclass_id: None, level: 10 },
+        ];
+        // Kämpfer 1: drei Meisterschaften, nicht fünf wie auf Gesamtstufe 11
+        let masteries: Vec<String> = ["dolch", "speer", "axt", "langschwert"].iter().map(|s| s.to_string()).collect();
+        character.meta.weapon_masteries = Some(masteries.clone());
+        assert!(!attacks(&character, &[longsword()])[0].mastery.as_ref().unwrap().usable);
+
+        // Startklasse ohne Meisterschaft, Nebenklasse Kämpfer (Übung aus dem Mehrklassen-Einstieg)
+        character.meta.class_id = Some("magier".into());
+        character.proficiencies.weapons = vec!["martial".into()];
+        character.meta.classes.reverse();
+        character.meta.weapon_masteries = Some(masteries[3..].to_vec());
+        assert!(attacks(&character, &[longsword()])[0].mastery.as_ref().unwrap().usable);
+    }
+}
@@ -0,0 +1,282 @@
+//! Zauber wirken: Platz verbrauchen, Hochstufen, Rituale, Konzentration.
+//!
+//! Grad-0-Zauber und Rituale verbrauchen keinen Platz. Sonst wird ein freier Platz des
+//! gewählten Grades genommen, ersatzweise ein Paktmagie-Platz (immer auf Paktgrad).
+//! Die Maxima kommen aus dem Derived Sheet (`core::spell_slots`), verbraucht wird in
+//! `spell_slots_used_N` bzw. `pact_slots_used`.
+
+use regex::Regex;
+use serde::Serialize;
+use crate::core::concentration::{self, ConcentrationChange};
+use crate::core::sheet::DerivedSheet;
+use crate::types::character::Character;
+use crate::types::spell::Spell;
+
+#[derive(Debug, Clone, Default)]
+pub struct CastRequest {
+    /// Grad des Platzes (Standard: Grad des Zaubers bzw. Paktgrad)
+    pub slot_level: Option<i32>,
+    pub as_ritual: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlotKind {
+    Spell,
+    Pact,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CastResult {
+    pub spell_id: String,
+    pub spell_name: String,
+    /// Grad, auf dem der Zauber wirkt (0 = Zaubertrick)
+    pub cast_level: i32,
+    /// Verbrauchter Platz (`None` bei Zaubertricks und Ritualen)
+    pub slot: Option<SlotKind>,
+    pub ritual: bool,
+    pub save_dc: Option<i32>,
+    pub attack_bonus: Option<i32>,
+    /// Auf Grad bzw. Stufe aufgelöster Text aus `higher_levels`
+    pub higher_levels: Option<String>,
+    pub concentration: Option<ConcentrationChange>,
+}
+
+/// Darf der Charakter den Zauber gerade wirken (vorbereitet, immer vorbereitet oder Zaubertrick)?
+/// Klassen, die Zauber nur kennen, brauchen keine Vorbereitung.
+fn is_ready(character: &Character, spell: &Spell, requires_preparation: bool) -> bool {
+    match character.spells.iter().find(|s| s.spell_id == spell.id) {
+        Some(entry) => spell.level == 0 || entry.is_prepared || entry.is_always_prepared || !requires_preparation,
+        None => character.spellcasting.as_ref().is_some_and(|s| s.prepared_spells.contains(&spell.id)),
+    }
+}
+
+/// Löst den Text zum Hochstufen auf: Würfel und Zahlen werden mit der Anzahl der Grade über
+/// dem Grundgrad multipliziert. Bei Zaubertricks zählt die höchste erreichte Charakterstufe.
+pub fn resolve_higher_levels(spell: &Spell, cast_level: i32, character_level: i32) -> Option<String> {
+    let text = spell.higher_levels.as_deref().map(str::trim).filter(|t| !t.is_empty())?;
+
+    if spell.level == 0 {
+        let tiers = Regex::new(r"(\d+)\.\s*\((\d+W\d+)\)").expect("gültiges Muster");
+        return tiers.captures_iter(text)
+            .filter_map(|c| Some((c[1].parse::<i32>().ok()?, c[2].to_string())))
+            .filter(|(level, _)| *level <= character_level)
+            .last()
+            .map(|(level, dice)| format!("{} (ab Stufe {})", dice, level));
+    }
+
+    let base_pattern = Regex::new(r"über dem (\d+)\.\s*").expect("gültiges Muster");
+    let Some(base) = base_pattern.captures(text) else {
+        let first = text.split_once(". ").map_or(text, |(s, _)| s).trim_end_matches('.');
+        return (cast_level > spell.level).then(|| format!("Grad {}: {}.", cast_level, first));
+    };
+    let base_level: i32 = base[1].parse().unwrap_or(spell.level);
+    let steps = cast_level - base_level;
+    if steps <= 0 {
+        return None;
+    }
+
+    let rest = &text[base.get(0).map_or(0, |m| m.end())..];
+    let sentence = rest.split_once('.').map_or(rest, |(s, _)| s).trim();
+    let dice = Regex::new(r"(\d+)W(\d+)").expect("gültiges Muster");
+    let scaled = dice.replace_all(sentence, |c: &regex::Captures| {
+        format!("{}W{}", c[1].parse::<i32>().unwrap_or(1) * steps, &c[2])
+    });
+    let numbers = Regex::new(r"um (jeweils )?(\d+)\b(?:[^W\d]|$)").expect("gültiges Muster");
+    let scaled = numbers.replace_all(&scaled, |c: &regex::Captures| {
+        let whole = &c[0];
+        let value = c[2].parse::<i32>().unwrap_or(0) * steps;
+        let tail = &whole[whole.find(&c[2]).map_or(whole.len(), |i| i + c[2].len())..];
+        format!("um {}{}{}", c.get(1).map_or("", |m| m.as_str()), value, tail)
+    });
+    let scaled = if steps > 1 {
+        scaled.replace("um eins", &format!("um {}", steps)).replace("eine weitere", &format!("{} weitere", steps))
+    } else {
+        scaled.into_owned()
+    };
+    Some(format!("Grad {} ({} über dem {}.): Insgesamt {}.", cast_level, steps, base_level, scaled))
+}
+
+/// Wirkt einen Zauber. `requires_preparation` ist false für Klassen, die Zauber nur kennen.
+pub fn cast_spell(
+    character: &mut Character,
+    spell: &Spell,
+    sheet: &DerivedSheet,
+    request: &CastRequest,
+    requires_preparation: bool,
+    started_at: Option<i64>,
+) -> Result<CastResult, String> {
+    if !is_ready(character, spell, requires_preparation) {
+        return Err(format!("{} ist weder vorbereitet noch bekannt", spell.name));
+    }
+    if sheet.condition_effects.incapacitated || character.health.current <= 0 {
+        return Err("Kampfunfähige Charaktere können keine Zauber wirken".to_string());
+    }
+    if sheet.spellcasting.as_ref().is_some_and(|s| !s.can_cast) {
+        return Err("Zauberwirken ist gerade nicht möglich (Rüstung ohne Ausbildung)".to_string());
+    }
+    if request.as_ritual && !spell.ritual {
+        return Err(format!("{} ist kein Ritual", spell.name));
+    }
+    if let Some(level) = request.slot_level {
+        if level < spell.level {
+            return Err(format!("{} hat Grad {} und kann nicht mit Grad {} gewirkt werden", spell.name, spell.level, level));
+        }
+        if level > 9 {
+            return Err(format!("Ungültiger Zauberplatzgrad: {}", level));
+        }
+    }
+
+    let mut updated = character.clone();
+    let (cast_level, slot) = if spell.level == 0 || request.as_ritual {
+        (spell.level, None)
+    } else {
+        let level = request.slot_level.unwrap_or(spell.level);
+        let regular = sheet.spell_slots.iter().find(|s| s.level == level).filter(|s| s.used < s.max);
+        let pact = sheet.pact_magic.as_ref()
+            .filter(|p| p.used < p.slots && p.slot_level >= spell.level)
+            .filter(|p| request.slot_level.is_none_or(|l| l == p.slot_level));
+        match (regular, pact) {
+            (Some(slot), _) => {
+                updated.meta.set_spell_slots_used(level, slot.used + 1);
+                (level, Some(SlotKind::Spell))
+            }
+            (None, Some(pact)) => {
+                updated.meta.pact_slots_used = pact.used + 1;
+                (pact.slot_level, Some(SlotKind::Pact))
+            }
+            (None, None) => return Err(format!("Kein freier Zauberplatz des {}. Grades", level)),
+        }
+    };
+
+    let concentration = if spell.concentration {
+        let slot_level = (cast_level > 0).then_some(cast_level);
+        Some(concentration::start_concentration(&mut updated, spell, slot_level, started_at)?)
+    } else {
+        None
+    };
+
+    *character = updated;
+    Ok(CastResult {
+        spell_id: spell.id.clone(),
+        spell_name: spell.name.clone(),
+        cast_level,
+        slot,
+        ritual: request.as_ritual,
+        save_dc: sheet.spellcasting.as_ref().map(|s| s.save_dc),
+        attack_bonus: sheet.spellcasting.as_ref().map(|s| s.attack_bonus),
+        higher_levels: resolve_higher_levels(spell, cast_level, character.meta.level),
+        concentration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+    use crate::core::sheet::{derive_sheet, SheetInputs};
+    use crate::core::spell_slots::{PactMagic, SlotMaxima};
+    use crate::types::character::CharacterSpell;
+
+    fn spell(id: &str, level: i32, higher_levels: Option<&str>) -> Spell {
+        Spell {
+            id: id.into(),
+            name: id.to_uppercase(),
+            level,
+            school: "Hervorrufung".into(),
+            casting_time: "1 Aktion".into(),
+            range: "18 m".into(),
+            components: "V, G".into(),
+            material_components: None,
+            duration: "Unmittelbar".into(),
+            concentration: false,
+            ritual: false,
+            description: String::new(),
+            higher_levels: higher_levels.map(Into::into),
+            classes: "Magier".into(),
+            data: serde_json::Value::Null,
+            source: "core".into(),
+        }
+    }
+
+    fn caster(prepared: &[&str]) -> (Character, DerivedSheet) {
+        let mut character = test_character();
+        character.spells = prepared.iter().map(|id| CharacterSpell {
+            id: format!("row-{}", id),
+            spell_id: id.to_string(),
+            is_prepared: true,
+            is_always_prepared: false,
+            source: None,
+        }).collect();
+        let inputs = SheetInputs {
+            spellcasting_ability: Some("int".into()),
+            spell_slots: Some(SlotMaxima {
+                slots: [4, 2, 0, 0, 0, 0, 0, 0, 0],
+                pact: Some(PactMagic { slots: 1, slot_level: 3 }),
+            }),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+        (character, sheet)
+    }
+
+    #[test]
+    fn test_cast_consumes_slots() {
+        let bolt = spell("donnerwoge", 1, Some("Für jeden Zauberplatzgrad über dem 1. wird der Schaden um 1W8 erhöht."));
+        let (mut character, sheet) = caster(&["donnerwoge"]);
+
+        let result = cast_spell(&mut character, &bolt, &sheet, &CastRequest { slot_level: Some(2), as_ritual: false }, true, None).unwrap();
+        assert_eq!((result.cast_level, result.slot), (2, Some(SlotKind::Spell)));
+        assert_eq!(character.meta.spell_slots_used_2, 1);
+        assert_eq!(result.save_dc, Some(11));
+        assert_eq!(result.higher_levels.as_deref(), Some("Grad 2 (1 über dem 1.): Insgesamt wird der Schaden um 1W8 erhöht."));
+
+        // Grad 3 gibt es nur als Paktmagie
+        let result = cast_spell(&mut character, &bolt, &sheet, &CastRequest { slot_level: Some(3), as_ritual: false }, true, None).unwrap();
+        assert_eq!((result.cast_level, result.slot), (3, Some(SlotKind::Pact)));
+        assert_eq!(character.meta.pact_slots_used, 1);
+        assert!(result.higher_levels.unwrap().contains("um 2W8"));
+
+        assert!(cast_spell(&mut character, &bolt, &sheet, &CastRequest { slot_level: Some(4), as_ritual: false }, true, None).is_err());
+        let fireball = spell("feuerball", 3, None);
+        assert!(cast_spell(&mut character, &fireball, &sheet, &CastRequest { slot_level: Some(2), as_ritual: false }, true, None).is_err());
+    }
+
+    #[test]
+    fn test_cast_ritual_and_concentration() {
+        let mut detect = spell("magie-entdecken", 1, None);
+        detect.ritual = true;
+        detect.concentration = true;
+        let (mut character, sheet) = caster(&["magie-entdecken"]);
+
+        let ritual = CastRequest { slot_level: None, as_ritual: true };
+        let result = cast_spell(&mut character, &detect, &sheet, &ritual, true, Some(42)).unwrap();
+        assert_eq!((result.cast_level, result.slot), (1, None));
+        assert_eq!(character.meta.spell_slots_used_1, 0);
+        assert_eq!(character.concentration.as_ref().map(|c| c.started_at), Some(Some(42)));
+
+        // Nicht vorbereitet: nur für Klassen ohne Vorbereitung erlaubt
+        let mut unprepared = character.clone();
+        unprepared.spells[0].is_prepared = false;
+        assert!(cast_spell(&mut unprepared, &detect, &sheet, &ritual, true, None).is_err());
+        assert!(cast_spell(&mut unprepared, &detect, &sheet, &ritual, false, None).is_ok());
+
+        let not_ritual = spell("schild", 1, None);
+        let (mut character, sheet) = caster(&["schild"]);
+        assert!(cast_spell(&mut character, &not_ritual, &sheet, &ritual, true, None).is_err());
+    }
+
+    #[test]
+    fn test_resolve_cantrip_scaling() {
+        let text = "Der Schaden wird um jeweils 1W6 erhöht, wenn du die 5. (2W6), die 11. (3W6) und die 17. (4W6) Stufe erreichst.";
+        let cantrip = spell("donnerschlag", 0, Some(text));
+        assert_eq!(resolve_higher_levels(&cantrip, 0, 12).as_deref(), Some("3W6 (ab Stufe 11)"));
+        assert_eq!(resolve_higher_levels(&cantrip, 0, 3), None);
+
+        let aid = spell("beistand", 2, Some("Für jeden Zauberplatzgrad über dem 2. werden die Trefferpunkte jedes Ziels um jeweils 5 erhöht."));
+        assert_eq!(
+            resolve_higher_levels(&aid, 4, 9).as_deref(),
+            Some("Grad 4 (2 über dem 2.): Insgesamt werden die Trefferpunkte jedes Ziels um jeweils 10 erhöht.")
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Konzentration (PHB 2024): immer nur ein Zauber gleichzeitig.
+//!
+//! Schaden erfordert einen KON-Rettungswurf gegen SG max(10, halber Schaden), höchstens 30.
+//! Kampfunfähig oder 0 TP beenden die Konzentration sofort.
+
+use serde::Serialize;
+use crate::core::conditions::condition_effects;
+use crate::core::dice::DiceRng;
+use crate::types::character::{Character, Concentration};
+use crate::types::spell::Spell;
+
+pub const MAX_CONCENTRATION_DC: i32 = 30;
+
+pub fn concentration_dc(damage: i32) -> i32 {
+    (damage / 2).clamp(10, MAX_CONCENTRATION_DC)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcentrationEndReason {
+    /// Neuer Konzentrationszauber
+    Replaced,
+    FailedSave,
+    Incapacitated,
+    ZeroHp,
+    Manual,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcentrationCheck {
+    pub dc: i32,
+    pub roll: u32,
+    pub bonus: i32,
+    pub total: i32,
+    pub success: bool,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcentrationResult {
+    pub spell_id: String,
+    pub spell_name: String,
+    pub check: Option<ConcentrationCheck>,
+    pub ended: bool,
+    pub reason: Option<ConcentrationEndReason>,
+}
+
+impl ConcentrationResult {
+    fn ended(concentration: Concentration, reason: ConcentrationEndReason) -> Self {
+        Self {
+            spell_id: concentration.spell_id,
+            spell_name: concentration.spell_name,
+            check: None,
+            ended: true,
+            reason: Some(reason),
+        }
+    }
+}
+
+/// Ergebnis von `start_concentration`: neue Konzentration und ggf. die dadurch beendete.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConcentrationChange {
+    pub concentration: Concentration,
+    pub replaced: Option<ConcentrationResult>,
+}
+
+/// Beginnt die Konzentration auf einen Zauber und beendet eine laufende.
+pub fn start_concentration(
+    character: &mut Character,
+    spell: &Spell,
+    slot_level: Option<i32>,
+    started_at: Option<i64>,
+) -> Result<ConcentrationChange, String> {
+    if !spell.concentration {
+        return Err(format!("{} erfordert keine Konzentration", spell.name));
+    }
+    if condition_effects(&character.conditions).incapacitated {
+        return Err("Kampfunfähige Charaktere können sich nicht konzentrieren".to_string());
+    }
+    if character.health.current <= 0 {
+        return Err("Bei 0 TP ist keine Konzentration möglich".to_string());
+    }
+
+    let concentration = Concentration {
+        spell_id: spell.id.clone(),
+        spell_name: spell.name.clone(),
+        slot_level,
+        duration: Some(spell.duration.clone()).filter(|d| !d.trim().is_empty()),
+        started_at,
+    };
+    let replaced = character.concentration.replace(concentration.clone())
+        .map(|c| ConcentrationResult::ended(c, ConcentrationEndReason::Replaced));
+    Ok(ConcentrationChange { concentration, replaced })
+}
+
+/// Beendet die Konzentration von Hand.
+pub fn end_concentration(character: &mut Character) -> Option<ConcentrationResult> {
+    character.concentration.take().map(|c| ConcentrationResult::ended(c, ConcentrationEndReason::Manual))
+}
+
+/// Beendet die Konzentration, wenn der Charakter kampfunfähig ist oder 0 TP hat.
+pub fn enforce_concentration(character: &mut Character) -> Option<ConcentrationResult> {
+    character.concentration.as_ref()?;
+    let effects = condition_effects(&character.conditions);
+    let reason = if character.health.current <= 0 {
+        ConcentrationEndReason::ZeroHp
+    } else if effects.incapacitated || effects.dead {
+        ConcentrationEndReason::Incapacitated
+    } else {
+        return None;
+    };
+    character.concentration.take().map(|c| ConcentrationResult::ended(c, reason))
+}
+
+/// Konzentrationsprüfung nach Schaden. `save_bonus` ist der KON-Rettungswurf-Bonus.
+/// Ohne laufende Konzentration oder ohne Schaden gibt es nichts zu prüfen.
+pub fn concentration_after_damage(
+    character: &mut Character,
+    damage: i32,
+    save_bonus: i32,
+    seed: u64,
+) -> Option<ConcentrationResult> {
+    if let Some(result) = enforce_concentration(character) {
+        return Some(result);
+    }
+    let concentration = character.concentration.clone()?;
+    if damage <= 0 {
+        return None;
+    }
+
+    let dc = concentration_dc(damage);
+    let roll = DiceRng::from_seed(seed).roll_die(20);
+    let total = roll as i32 + save_bonus;
+    let success = total >= dc;
+    if !success {
+        character.concentration = None;
+    }
+
+    Some(ConcentrationResult {
+        spell_id: concentration.spell_id,
+        spell_name: concentration.spell_name,
+        check: Some(ConcentrationCheck { dc, roll, bonus: save_bonus, total, success, seed }),
+        ended: !success,
+        reason: (!success).then_some(ConcentrationEndReason::FailedSave),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+    use crate::types::character::CharacterCondition;
+
+    fn spell(id: &str, concentration: bool) -> Spell {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": id.to_uppercase(), "level": 1, "school": "Verzauberung",
+            "casting_time": "1 Aktion", "range": "9 m", "components": "V, G",
+            "material_components": null, "duration": "Konzentration, bis zu 1 Minute",
+            "concentration": concentration, "ritual": false, "description": "",
+            "higher_levels": null, "classes": "", "data": {}, "source": "core"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_concentration_dc() {
+        assert_eq!(concentration_dc(5), 10);
+        assert_eq!(concentration_dc(22), 11);
+        assert_eq!(concentration_dc(29), 14);
+        assert_eq!(concentration_dc(100), 30);
+    }
+
+    #[test]
+    fn test_start_replaces_previous() {
+        let mut character = test_character();
+        assert!(start_concentration(&mut character, &spell("magisches-geschoss", false), None, None).is_err());
+
+        assert!(start_concentration(&mut character, &spell("segnen", true), Some(1), None).unwrap().replaced.is_none());
+        let change = start_concentration(&mut character, &spell("hast", true), Some(3), None).unwrap();
+        let replaced = change.replaced.unwrap();
+        assert_eq!((replaced.spell_id.as_str(), replaced.reason), ("segnen", Some(ConcentrationEndReason::Replaced)));
+        assert_eq!(character.concentration.as_ref().map(|c| c.spell_id.as_str()), Some("hast"));
+        assert_eq!(end_concentration(&mut character).map(|c| c.spell_id), Some("hast".to_string()));
+        assert!(character.concentration.is_none());
+        assert!(end_concentration(&mut character).is_none());
+    }
+
+    #[test]
+    fn test_concentration_after_damage() {
+        let mut character = test_character();
+        start_concentration(&mut character, &spell("segnen", true), Some(1), None).unwrap();
+
+        // Bonus +30 besteht immer, -30 scheitert immer
+        let result = concentration_after_damage(&mut character, 8, 30, 1).unwrap();
+        let check = result.check.unwrap();
+        assert_eq!(check.dc, 10);
+        assert!(check.success && !result.ended);
+        assert!(character.concentration.is_some());
+
+        let result = concentration_after_damage(&mut character, 40, -30, 1).unwrap();
+        assert_eq!(result.check.unwrap().dc, 20);
+        assert_eq!(result.reason, Some(ConcentrationEndReason::FailedSave));
+        assert!(character.concentration.is_none());
+        assert!(concentration_after_damage(&mut character, 10, 0, 1).is_none());
+    }
+
+    #[test]
+    fn test_concentration_ends_on_incapacitated_or_zero_hp() {
+        let mut character = test_character();
+        start_concentration(&mut character, &spell("segnen", true), None, None).unwrap();
+        assert!(enforce_concentration(&mut character).is_none());
+
+        character.conditions.push(CharacterCondition {
+            condition_id: "stunned".into(),
+            level: None,
+            source: None,
+            duration: None,
+            ends_on_rest: None,
+        });
+        let result = enforce_concentration(&mut character).unwrap();
+        assert_eq!(result.reason, Some(ConcentrationEndReason::Incapacitated));
+        assert!(start_concentration(&mut character, &spell("segnen", true), None, None).is_err());
+
+        character.conditions.clear();
+        start_concentration(&mut character, &spell("segnen", true), None, None).unwrap();
+        character.health.current = 0;
+        let result = concentration_after_damage(&mut character, 12, 30, 1).unwrap();
+        assert_eq!(result.reason, Some(ConcentrationEndReason::ZeroHp));
+        assert!(result.check.is_none());
+    }
+}
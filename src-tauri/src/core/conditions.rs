@@ -0,0 +1,413 @@
+//! Zustände (PHB 2024, Anhang C) und Erschöpfung mit ihren Auswirkungen auf den Bogen.
+//!
+//! Gespeichert werden sie als `CharacterCondition` am Charakter. Unbekannte IDs
+//! (z.B. "raging", "mage_armor") sind erlaubt: Sie haben keine eigenen Auswirkungen,
+//! erfüllen aber `condition:<id>` in bedingten Modifikatoren.
+
+use serde::Serialize;
+use crate::types::character::{Character, CharacterCondition};
+
+pub const EXHAUSTION: &str = "exhaustion";
+pub const MAX_EXHAUSTION: i32 = 6;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Effects {
+    attack_disadvantage: bool,
+    check_disadvantage: bool,
+    dex_save_disadvantage: bool,
+    str_dex_auto_fail: bool,
+    speed_zero: bool,
+    incapacitated: bool,
+    attacks_against_advantage: bool,
+}
+
+pub struct ConditionDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Kurzfassung der Auswirkungen für Hinweise
+    pub summary: &'static str,
+    effects: Effects,
+}
+
+const NONE: Effects = Effects {
+    attack_disadvantage: false,
+    check_disadvantage: false,
+    dex_save_disadvantage: false,
+    str_dex_auto_fail: false,
+    speed_zero: false,
+    incapacitated: false,
+    attacks_against_advantage: false,
+};
+
+/// Kampfunfähig, Bewegungsrate 0, STR/GES-Rettungswürfe scheitern, Angriffe gegen dich mit Vorteil
+const HELPLESS: Effects = Effects {
+    str_dex_auto_fail: true,
+    speed_zero: true,
+    incapacitated: true,
+    attacks_against_advantage: true,
+    ..NONE
+};
+
+pub const CONDITIONS: &[ConditionDef] = &[
+    ConditionDef {
+        id: "blinded", name: "Blind", aliases: &[],
+        summary: "Nachteil auf Angriffe, Angriffe gegen dich mit Vorteil",
+        effects: Effects { attack_disadvantage: true, attacks_against_advantage: true, ..NONE },
+    },
+    ConditionDef {
+        id: "charmed", name: "Bezaubert", aliases: &[],
+        summary: "Kann den Bezauberer nicht angreifen",
+        effects: NONE,
+    },
+    ConditionDef {
+        id: "deafened", name: "Taub", aliases: &[],
+        summary: "Kann nichts hören",
+        effects: NONE,
+    },
+    ConditionDef {
+        id: EXHAUSTION, name: "Erschöpfung", aliases: &["erschöpft"],
+        summary: "-2 je Stufe auf W20-Tests, -5 ft Bewegungsrate je Stufe",
+        effects: NONE,
+    },
+    ConditionDef {
+        id: "frightened", name: "Verängstigt", aliases: &[],
+        summary: "Nachteil auf Attributswürfe und Angriffe",
+        effects: Effects { attack_disadvantage: true, check_disadvantage: true, ..NONE },
+    },
+    ConditionDef {
+        id: "grappled", name: "Gepackt", aliases: &[],
+        summary: "Bewegungsrate 0",
+        effects: Effects { speed_zero: true, ..NONE },
+    },
+    ConditionDef {
+        id: "incapacitated", name: "Kampfunfähig", aliases: &[],
+        summary: "Keine Aktionen, Bonusaktionen oder Reaktionen; Konzentration endet",
+        effects: Effects { incapacitated: true, ..NONE },
+    },
+    ConditionDef {
+        id: "invisible", name: "Unsichtbar", aliases: &[],
+        summary: "Vorteil auf Angriffe, Angriffe gegen dich mit Nachteil",
+        effects: NONE,
+    },
+    ConditionDef {
+        id: "paralyzed", name: "Gelähmt", aliases: &[],
+        summary: "Kampfunfähig, Bewegungsrate 0, STR/GES-Rettungswürfe scheitern",
+        effects: HELPLESS,
+    },
+    ConditionDef {
+        id: "petrified", name: "Versteinert", aliases: &[],
+        summary: "Kampfunfähig, Bewegungsrate 0, STR/GES-Rettungswürfe scheitern",
+        effects: HELPLESS,
+    },
+    ConditionDef {
+        id: "poisoned", name: "Vergiftet", aliases: &[],
+        summary: "Nachteil auf Angriffe und Attributswürfe",
+        effects: Effects { attack_disadvantage: true, check_disadvantage: true, ..NONE },
+    },
+    ConditionDef {
+        id: "prone", name: "Liegend", aliases: &["liegt"],
+        summary: "Nachteil auf Angriffe, nur Kriechen",
+        effects: Effects { attack_disadvantage: true, ..NONE },
+    },
+    ConditionDef {
+        id: "restrained", name: "Festgesetzt", aliases: &[],
+        summary: "Bewegungsrate 0, Nachteil auf Angriffe und GES-Rettungswürfe",
+        effects: Effects {
+            attack_disadvantage: true,
+            dex_save_disadvantage: true,
+            speed_zero: true,
+            attacks_against_advantage: true,
+            ..NONE
+        },
+    },
+    ConditionDef {
+        id: "stunned", name: "Betäubt", aliases: &[],
+        summary: "Kampfunfähig, STR/GES-Rettungswürfe scheitern",
+        effects: Effects { speed_zero: false, ..HELPLESS },
+    },
+    ConditionDef {
+        id: "unconscious", name: "Bewusstlos", aliases: &[],
+        summary: "Kampfunfähig und liegend, Bewegungsrate 0, STR/GES-Rettungswürfe scheitern",
+        effects: Effects { attack_disadvantage: true, ..HELPLESS },
+    },
+];
+
+/// Vergleichsschlüssel für Zustands-IDs und -Namen: klein, Umlaute ausgeschrieben, ohne
+/// Leer-, Binde- und Unterstriche ("Magierrüstung", "magier_ruestung" -> "magierruestung").
+pub fn condition_key(entry: &str) -> String {
+    entry
+        .trim()
+        .to_lowercase()
+        .replace('ä', "ae")
+        .replace('ö', "oe")
+        .replace('ü', "ue")
+        .replace('ß', "ss")
+        .replace(['_', '-', ' '], "")
+}
+
+/// Passt `entry` zu einer der IDs bzw. Namen?
+pub fn is_condition(entry: &str, ids: &[&str]) -> bool {
+    let key = condition_key(entry);
+    ids.iter().any(|id| condition_key(id) == key)
+}
+
+/// Sucht einen Zustand über ID, deutschen Namen oder Alias.
+pub fn find_condition(entry: &str) -> Option<&'static ConditionDef> {
+    CONDITIONS
+        .iter()
+        .find(|c| is_condition(entry, &[c.id, c.name]) || is_condition(entry, c.aliases))
+}
+
+/// Normalisiert eine Zustands-ID ("Vergiftet" -> "poisoned"); unbekannte bleiben (klein geschrieben) erhalten.
+pub fn normalize_condition_id(entry: &str) -> String {
+    find_condition(entry)
+        .map(|c| c.id.to_string())
+        .unwrap_or_else(|| entry.trim().to_lowercase())
+}
+
+/// Zusammengefasste Auswirkungen aller aktiven Zustände.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConditionEffects {
+    pub exhaustion_level: i32,
+    /// Abzug auf alle W20-Tests (Erschöpfung: 2 je Stufe)
+    pub d20_penalty: i32,
+    pub speed_penalty_ft: i32,
+    pub speed_zero: bool,
+    pub attack_disadvantage: bool,
+    pub ability_check_disadvantage: bool,
+    /// Rettungswürfe mit Nachteil (Attributskürzel)
+    pub save_disadvantage: Vec<String>,
+    /// Automatisch misslungene Rettungswürfe (Attributskürzel)
+    pub save_auto_fail: Vec<String>,
+    pub incapacitated: bool,
+    pub attacks_against_advantage: bool,
+    /// Erschöpfungsstufe 6
+    pub dead: bool,
+}
+
+fn is_exhaustion(condition: &CharacterCondition) -> bool {
+    normalize_condition_id(&condition.condition_id) == EXHAUSTION
+}
+
+pub fn exhaustion_level(conditions: &[CharacterCondition]) -> i32 {
+    conditions
+        .iter()
+        .filter(|c| is_exhaustion(c))
+        .map(|c| c.level.unwrap_or(1).clamp(0, MAX_EXHAUSTION))
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn condition_effects(conditions: &[CharacterCondition]) -> ConditionEffects {
+    let mut effects = ConditionEffects::default();
+    let push = |list: &mut Vec<String>, ability: &str| {
+        if !list.iter().any(|a| a == ability) {
+            list.push(ability.to_string());
+        }
+    };
+
+    for condition in conditions {
+        let Some(def) = find_condition(&condition.condition_id) else { continue };
+        let e = def.effects;
+        effects.attack_disadvantage |= e.attack_disadvantage;
+        effects.ability_check_disadvantage |= e.check_disadvantage;
+        effects.speed_zero |= e.speed_zero;
+        effects.incapacitated |= e.incapacitated;
+        effects.attacks_against_advantage |= e.attacks_against_advantage;
+        if e.dex_save_disadvantage {
+            push(&mut effects.save_disadvantage, "dex");
+        }
+        if e.str_dex_auto_fail {
+            push(&mut effects.save_auto_fail, "str");
+            push(&mut effects.save_auto_fail, "dex");
+        }
+    }
+
+    let level = exhaustion_level(conditions);
+    effects.exhaustion_level = level;
+    effects.d20_penalty = 2 * level;
+    effects.speed_penalty_ft = 5 * level;
+    effects.dead = level >= MAX_EXHAUSTION;
+    effects
+}
+
+/// Namen für `condition:<name>` in bedingten Modifikatoren: ID und deutscher Name.
+pub fn condition_names(conditions: &[CharacterCondition]) -> Vec<String> {
+    let mut names = Vec::new();
+    for condition in conditions {
+        names.push(condition.condition_id.to_lowercase());
+        if let Some(def) = find_condition(&condition.condition_id) {
+            names.push(def.name.to_lowercase());
+        }
+    }
+    names
+}
+
+/// Fügt einen Zustand hinzu oder ersetzt Quelle/Dauer eines bereits aktiven.
+/// Erschöpfung ohne Stufe erhöht die vorhandene Stufe um 1.
+pub fn add_condition(character: &mut Character, mut condition: CharacterCondition) -> Result<(), String> {
+    condition.condition_id = normalize_condition_id(&condition.condition_id);
+    if condition.condition_id.is_empty() {
+        return Err("Zustand ohne ID".to_string());
+    }
+    if condition.condition_id == EXHAUSTION {
+        let level = condition.level.unwrap_or(exhaustion_level(&character.conditions) + 1);
+        condition.level = Some(level);
+        return set_exhaustion(character, level, condition);
+    }
+
+    condition.level = None;
+    match character.conditions.iter_mut().find(|c| c.condition_id == condition.condition_id) {
+        Some(existing) => *existing = condition,
+        None => character.conditions.push(condition),
+    }
+    Ok(())
+}
+
+/// Entfernt einen Zustand. Liefert false, wenn er nicht aktiv war.
+pub fn remove_condition(character: &mut Character, condition_id: &str) -> bool {
+    let id = normalize_condition_id(condition_id);
+    let before = character.conditions.len();
+    character.conditions.retain(|c| c.condition_id != id);
+    character.conditions.len() != before
+}
+
+/// Setzt die Erschöpfungsstufe (0 entfernt sie). Stufe 6 bedeutet den Tod.
+fn set_exhaustion(character: &mut Character, level: i32, template: CharacterCondition) -> Result<(), String> {
+    if !(0..=MAX_EXHAUSTION).contains(&level) {
+        return Err(format!("Erschöpfungsstufe muss zwischen 0 und {} liegen", MAX_EXHAUSTION));
+    }
+    character.conditions.retain(|c| !is_exhaustion(c));
+    if level > 0 {
+        character.conditions.push(CharacterCondition {
+            condition_id: EXHAUSTION.to_string(),
+            level: Some(level),
+            ..template
+        });
+    }
+    if level >= MAX_EXHAUSTION {
+        character.health.death_saves.failures = 3;
+    }
+    Ok(())
+}
+
+/// Setzt die Erschöpfungsstufe direkt (0-6).
+pub fn set_exhaustion_level(character: &mut Character, level: i32, source: Option<String>) -> Result<(), String> {
+    set_exhaustion(character, level, CharacterCondition {
+        condition_id: EXHAUSTION.to_string(),
+        level: None,
+        source,
+        duration: None,
+        ends_on_rest: None,
+    })
+}
+
+/// Reduziert die Erschöpfung um eine Stufe (lange Rast). Liefert true, wenn sich etwas änderte.
+pub fn reduce_exhaustion(character: &mut Character) -> bool {
+    let level = exhaustion_level(&character.conditions);
+    if level == 0 {
+        return false;
+    }
+    for condition in character.conditions.iter_mut().filter(|c| is_exhaustion(c)) {
+        condition.level = Some(level - 1);
+    }
+    character.conditions.retain(|c| !is_exhaustion(c) || c.level.unwrap_or(0) > 0);
+    true
+}
+
+/// Beendet Zustände, die mit einer Rast enden ("long" endet nur mit langer Rast).
+/// Liefert die IDs der beendeten Zustände.
+pub fn end_conditions_on_rest(character: &mut Character, long_rest: bool) -> Vec<String> {
+    let mut ended = Vec::new();
+    character.conditions.retain(|c| {
+        let ends = match c.ends_on_rest.as_deref().map(str::trim) {
+            Some("short") => true,
+            Some("long") => long_rest,
+            _ => false,
+        };
+        if ends {
+            ended.push(c.condition_id.clone());
+        }
+        !ends
+    });
+    ended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+
+    fn condition(id: &str) -> CharacterCondition {
+        CharacterCondition {
+            condition_id: id.to_string(),
+            level: None,
+            source: Some("Test".into()),
+            duration: None,
+            ends_on_rest: None,
+        }
+    }
+
+    #[test]
+    fn test_find_condition() {
+        assert_eq!(find_condition("Vergiftet").map(|c| c.id), Some("poisoned"));
+        assert_eq!(find_condition("GRAPPLED").map(|c| c.id), Some("grappled"));
+        assert!(find_condition("raging").is_none());
+        assert_eq!(find_condition("kampfunfaehig").map(|c| c.id), Some("incapacitated"));
+        assert_eq!(find_condition("erschöpft").map(|c| c.id), Some(EXHAUSTION));
+        assert_eq!(normalize_condition_id(" Raging "), "raging");
+    }
+
+    #[test]
+    fn test_condition_effects() {
+        let effects = condition_effects(&[condition("poisoned"), condition("grappled")]);
+        assert!(effects.attack_disadvantage && effects.ability_check_disadvantage);
+        assert!(effects.speed_zero);
+        assert!(!effects.incapacitated);
+
+        let effects = condition_effects(&[condition("paralyzed")]);
+        assert!(effects.incapacitated && effects.speed_zero);
+        assert_eq!(effects.save_auto_fail, vec!["str".to_string(), "dex".to_string()]);
+
+        let effects = condition_effects(&[CharacterCondition { level: Some(3), ..condition(EXHAUSTION) }]);
+        assert_eq!((effects.d20_penalty, effects.speed_penalty_ft), (6, 15));
+        assert!(!effects.dead);
+    }
+
+    #[test]
+    fn test_add_and_remove_conditions() {
+        let mut character = test_character();
+        add_condition(&mut character, condition("Vergiftet")).unwrap();
+        add_condition(&mut character, CharacterCondition { source: Some("Gift".into()), ..condition("poisoned") }).unwrap();
+        assert_eq!(character.conditions.len(), 1);
+        assert_eq!(character.conditions[0].source.as_deref(), Some("Gift"));
+
+        add_condition(&mut character, condition(EXHAUSTION)).unwrap();
+        add_condition(&mut character, condition("Erschöpfung")).unwrap();
+        assert_eq!(exhaustion_level(&character.conditions), 2);
+        assert!(reduce_exhaustion(&mut character));
+        assert_eq!(exhaustion_level(&character.conditions), 1);
+
+        assert!(remove_condition(&mut character, "vergiftet"));
+        assert!(!remove_condition(&mut character, "poisoned"));
+
+        assert!(set_exhaustion_level(&mut character, 7, None).is_err());
+        set_exhaustion_level(&mut character, 6, None).unwrap();
+        assert!(condition_effects(&character.conditions).dead);
+        assert_eq!(character.health.death_saves.failures, 3);
+    }
+
+    #[test]
+    fn test_end_conditions_on_rest() {
+        let mut character = test_character();
+        character.conditions = vec![
+            CharacterCondition { ends_on_rest: Some("short".into()), ..condition("frightened") },
+            CharacterCondition { ends_on_rest: Some("long".into()), ..condition("poisoned") },
+            condition("prone"),
+        ];
+        assert_eq!(end_conditions_on_rest(&mut character, false), vec!["frightened".to_string()]);
+        assert_eq!(end_conditions_on_rest(&mut character, true), vec!["poisoned".to_string()]);
+        assert_eq!(character.conditions.len(), 1);
+    }
+}
@@ -0,0 +1,433 @@
+//! Würfelausdrücke parsen und reproduzierbar würfeln.
+//!
+//! Unterstützt u.a. `2d6+3`, `1d20+5 adv`, `4d6kh3`, `1d8+@str`, `d20 nachteil`
+//! und die deutsche Schreibweise `1W8` aus den Kompendium-Daten.
+//! Jeder Wurf liefert seinen Seed zurück; mit demselben Seed und Ausdruck
+//! entsteht exakt dasselbe Ergebnis.
+
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RollMode {
+    Normal,
+    Advantage,
+    Disadvantage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Keep {
+    Highest(u32),
+    Lowest(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TermKind {
+    Dice { count: u32, sides: u32, keep: Option<Keep> },
+    Constant(i32),
+    /// `@str`, `@prof`, … – wird beim Würfeln über den Kontext aufgelöst
+    Variable(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Term {
+    /// +1 oder -1
+    pub sign: i32,
+    pub kind: TermKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiceExpression {
+    pub terms: Vec<Term>,
+    pub mode: RollMode,
+}
+
+/// Obergrenzen gegen versehentliche Riesenwürfe ("1000d1000")
+const MAX_DICE: u32 = 100;
+const MAX_SIDES: u32 = 1000;
+
+impl DiceExpression {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut text = input.trim().to_lowercase();
+        let mut mode = RollMode::Normal;
+        for (suffix, suffix_mode) in [
+            ("adv", RollMode::Advantage),
+            ("vorteil", RollMode::Advantage),
+            ("dis", RollMode::Disadvantage),
+            ("nachteil", RollMode::Disadvantage),
+        ] {
+            if let Some(rest) = text.strip_suffix(suffix) {
+                text = rest.trim().to_string();
+                mode = suffix_mode;
+                break;
+            }
+        }
+
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if compact.is_empty() {
+            return Err(format!("Leerer Würfelausdruck '{}'", input));
+        }
+
+        let mut terms = Vec::new();
+        let mut sign = 1;
+        let mut current = String::new();
+        for c in compact.chars() {
+            if c == '+' || c == '-' {
+                if !current.is_empty() {
+                    terms.push(Term { sign, kind: parse_term(&current, input)? });
+                    current.clear();
+                } else if !terms.is_empty() || sign == -1 {
+                    return Err(format!("Doppelter Operator in '{}'", input));
+                }
+                sign = if c == '-' { -1 } else { 1 };
+            } else {
+                current.push(c);
+            }
+        }
+        if current.is_empty() {
+            return Err(format!("Ausdruck endet mit Operator: '{}'", input));
+        }
+        terms.push(Term { sign, kind: parse_term(&current, input)? });
+
+        let expression = Self { terms, mode };
+        if mode != RollMode::Normal && expression.mode_term().is_none() {
+            return Err(format!(
+                "Vorteil/Nachteil braucht einen einzelnen Würfel ohne Behalten (z.B. 1d20): '{}'",
+                input
+            ));
+        }
+        Ok(expression)
+    }
+
+    /// Term, auf den Vorteil/Nachteil wirkt: der erste einzelne Würfel ohne kh/kl (üblicherweise der W20).
+    fn mode_term(&self) -> Option<usize> {
+        self.terms
+            .iter()
+            .position(|t| matches!(t.kind, TermKind::Dice { count: 1, keep: None, .. }))
+    }
+}
+
+fn parse_number(text: &str, input: &str) -> Result<u32, String> {
+    text.parse().map_err(|_| format!("Ungültige Zahl '{}' in '{}'", text, input))
+}
+
+fn parse_term(text: &str, input: &str) -> Result<TermKind, String> {
+    if let Some(name) = text.strip_prefix('@') {
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(format!("Ungültige Variable '@{}' in '{}'", name, input));
+        }
+        return Ok(TermKind::Variable(name.to_string()));
+    }
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        let value: i32 = text.parse().map_err(|_| format!("Zahl zu groß in '{}'", input))?;
+        return Ok(TermKind::Constant(value));
+    }
+
+    // NdS[kh|kl|k]K, "d" oder "w" (deutsch), "%" = 100
+    let Some(pos) = text.find(['d', 'w']) else {
+        return Err(format!("Unbekannter Term '{}' in '{}'", text, input));
+    };
+    let count = match &text[..pos] {
+        "" => 1,
+        n => parse_number(n, input)?,
+    };
+    let rest = &text[pos + 1..];
+    let (sides_text, keep) = match rest.find('k') {
+        Some(k) => {
+            let modifier = &rest[k + 1..];
+            let keep = if let Some(n) = modifier.strip_prefix('h') {
+                Keep::Highest(parse_number(n, input)?)
+            } else if let Some(n) = modifier.strip_prefix('l') {
+                Keep::Lowest(parse_number(n, input)?)
+            } else {
+                Keep::Highest(parse_number(modifier, input)?)
+            };
+            (&rest[..k], Some(keep))
+        }
+        None => (rest, None),
+    };
+    let sides = match sides_text {
+        "%" => 100,
+        s => parse_number(s, input)?,
+    };
+
+    if count == 0 || sides == 0 {
+        return Err(format!("Würfelanzahl und Seiten müssen größer als 0 sein: '{}'", input));
+    }
+    if count > MAX_DICE || sides > MAX_SIDES {
+        return Err(format!("Zu viele Würfel oder Seiten in '{}'", input));
+    }
+    if let Some(Keep::Highest(n) | Keep::Lowest(n)) = keep {
+        if n == 0 || n > count {
+            return Err(format!("Es können nur 1 bis {} Würfel behalten werden: '{}'", count, input));
+        }
+    }
+    Ok(TermKind::Dice { count, sides, keep })
+}
+
+/// Kleiner, reproduzierbarer Zufallsgenerator (SplitMix64).
+/// Bewusst ohne externe Abhängigkeit, damit protokollierte Seeds auch nach Updates gültig bleiben.
+#[derive(Debug, Clone)]
+pub struct DiceRng {
+    state: u64,
+}
+
+impl DiceRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Seed aus der aktuellen Zeit, wenn keiner vorgegeben ist.
+    /// Auf 53 Bit begrenzt, damit er als JavaScript-Zahl verlustfrei zurückkommt.
+    pub fn random_seed() -> u64 {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0x5EED);
+        nanos & ((1 << 53) - 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Gleichverteilt 1..=sides (Rejection Sampling gegen Modulo-Verzerrung).
+    pub fn roll_die(&mut self, sides: u32) -> u32 {
+        let sides = sides as u64;
+        let zone = u64::MAX - (u64::MAX % sides);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % sides) as u32 + 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DieResult {
+    pub sides: u32,
+    pub value: u32,
+    /// false, wenn der Würfel durch kh/kl, Vorteil oder Nachteil verworfen wurde
+    pub kept: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TermResult {
+    /// z.B. "+2d6", "-1", "+@str"
+    pub label: String,
+    pub dice: Vec<DieResult>,
+    pub value: i32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RollResult {
+    pub expression: String,
+    pub seed: u64,
+    pub mode: RollMode,
+    pub terms: Vec<TermResult>,
+    pub total: i32,
+}
+
+impl RollResult {
+    /// Alle Einzelwürfel in Wurfreihenfolge.
+    pub fn dice(&self) -> impl Iterator<Item = &DieResult> {
+        self.terms.iter().flat_map(|t| t.dice.iter())
+    }
+}
+
+/// Würfelt einen geparsten Ausdruck. Variablen (`@str`) werden über `variables` aufgelöst.
+pub fn roll_expression(
+    expression: &DiceExpression,
+    source: &str,
+    seed: u64,
+    variables: &dyn Fn(&str) -> Option<i32>,
+) -> Result<RollResult, String> {
+    let mut rng = DiceRng::from_seed(seed);
+    let mut terms = Vec::new();
+    let mode_term = match expression.mode {
+        RollMode::Normal => None,
+        _ => Some(expression.mode_term().ok_or_else(|| {
+            format!("Vorteil/Nachteil braucht einen einzelnen Würfel ohne Behalten (z.B. 1d20): '{}'", source.trim())
+        })?),
+    };
+    let overflow = || format!("Ergebnis zu groß in '{}'", source.trim());
+
+    for (index, term) in expression.terms.iter().enumerate() {
+        let prefix = if term.sign < 0 { "-" } else { "+" };
+        let result = match &term.kind {
+            TermKind::Constant(value) => TermResult {
+                label: format!("{}{}", prefix, value),
+                dice: Vec::new(),
+                value: term.sign.checked_mul(*value).ok_or_else(overflow)?,
+            },
+            TermKind::Variable(name) => {
+                let value = variables(name).ok_or_else(|| format!("Unbekannte Variable '@{}'", name))?;
+                TermResult {
+                    label: format!("{}@{}", prefix, name),
+                    dice: Vec::new(),
+                    value: term.sign.checked_mul(value).ok_or_else(overflow)?,
+                }
+            }
+            TermKind::Dice { count, sides, keep } => {
+                let (count, keep) = match expression.mode {
+                    RollMode::Advantage if mode_term == Some(index) => (2, Some(Keep::Highest(1))),
+                    RollMode::Disadvantage if mode_term == Some(index) => (2, Some(Keep::Lowest(1))),
+                    _ => (*count, *keep),
+                };
+                let mut dice: Vec<DieResult> = (0..count)
+                    .map(|_| DieResult { sides: *sides, value: rng.roll_die(*sides), kept: true })
+                    .collect();
+                apply_keep(&mut dice, keep);
+                let sum: i32 = dice.iter().filter(|d| d.kept).map(|d| d.value as i32).sum();
+                TermResult {
+                    label: format!("{}{}d{}", prefix, count, sides),
+                    dice,
+                    value: term.sign * sum,
+                }
+            }
+        };
+        terms.push(result);
+    }
+
+    let total = terms
+        .iter()
+        .try_fold(0i32, |total, t| total.checked_add(t.value))
+        .ok_or_else(overflow)?;
+    Ok(RollResult {
+        expression: source.trim().to_string(),
+        seed,
+        mode: expression.mode,
+        total,
+        terms,
+    })
+}
+
+/// Markiert alle nicht behaltenen Würfel; bei Gleichstand bleibt der zuerst gewürfelte.
+fn apply_keep(dice: &mut [DieResult], keep: Option<Keep>) {
+    let Some(keep) = keep else { return };
+    let mut order: Vec<usize> = (0..dice.len()).collect();
+    let n = match keep {
+        Keep::Highest(n) => {
+            order.sort_by(|&a, &b| dice[b].value.cmp(&dice[a].value));
+            n
+        }
+        Keep::Lowest(n) => {
+            order.sort_by(|&a, &b| dice[a].value.cmp(&dice[b].value));
+            n
+        }
+    };
+    for &index in order.iter().skip(n as usize) {
+        dice[index].kept = false;
+    }
+}
+
+/// Parst und würfelt in einem Schritt. Ohne `seed` wird ein neuer erzeugt.
+pub fn roll(
+    input: &str,
+    seed: Option<u64>,
+    variables: &dyn Fn(&str) -> Option<i32>,
+) -> Result<RollResult, String> {
+    let expression = DiceExpression::parse(input)?;
+    roll_expression(&expression, input, seed.unwrap_or_else(DiceRng::random_seed), variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_vars(_: &str) -> Option<i32> {
+        None
+    }
+
+    #[test]
+    fn test_parse_expressions() {
+        let expr = DiceExpression::parse("2d6+3").unwrap();
+        assert_eq!(expr.mode, RollMode::Normal);
+        assert_eq!(
+            expr.terms,
+            vec![
+                Term { sign: 1, kind: TermKind::Dice { count: 2, sides: 6, keep: None } },
+                Term { sign: 1, kind: TermKind::Constant(3) },
+            ]
+        );
+
+        assert_eq!(DiceExpression::parse("1d20 + 5 adv").unwrap().mode, RollMode::Advantage);
+        assert_eq!(
+            DiceExpression::parse("4d6kh3").unwrap().terms[0].kind,
+            TermKind::Dice { count: 4, sides: 6, keep: Some(Keep::Highest(3)) }
+        );
+        assert_eq!(
+            DiceExpression::parse("1W8+@str").unwrap().terms,
+            vec![
+                Term { sign: 1, kind: TermKind::Dice { count: 1, sides: 8, keep: None } },
+                Term { sign: 1, kind: TermKind::Variable("str".into()) },
+            ]
+        );
+        assert_eq!(DiceExpression::parse("-1 + d%").unwrap().terms[0].sign, -1);
+
+        for invalid in ["", "2d", "d0", "3d6kh4", "2d6++1", "1d6+", "foo", "@", "1000d6", "2d20 adv", "4d6kh3 dis", "5 vorteil"] {
+            assert!(DiceExpression::parse(invalid).is_err(), "{} sollte ungültig sein", invalid);
+        }
+    }
+
+    #[test]
+    fn test_roll_is_reproducible() {
+        let first = roll("4d6kh3+2", Some(42), &no_vars).unwrap();
+        let second = roll("4d6kh3+2", Some(42), &no_vars).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.seed, 42);
+
+        let dice: Vec<&DieResult> = first.dice().collect();
+        assert_eq!(dice.len(), 4);
+        assert_eq!(dice.iter().filter(|d| d.kept).count(), 3);
+        let lowest = dice.iter().map(|d| d.value).min().unwrap();
+        let kept_sum: u32 = dice.iter().filter(|d| d.kept).map(|d| d.value).sum();
+        let all_sum: u32 = dice.iter().map(|d| d.value).sum();
+        assert_eq!(kept_sum, all_sum - lowest);
+        assert_eq!(first.total, kept_sum as i32 + 2);
+    }
+
+    #[test]
+    fn test_roll_advantage_and_variables() {
+        let vars = |name: &str| if name == "str" { Some(3) } else { None };
+        let result = roll("1d20+@str dis", Some(7), &vars).unwrap();
+        let d20: Vec<&DieResult> = result.terms[0].dice.iter().collect();
+        assert_eq!(d20.len(), 2);
+        let lower = d20.iter().map(|d| d.value).min().unwrap();
+        assert_eq!(result.total, lower as i32 + 3);
+
+        assert!(roll("1d8+@cha", Some(1), &vars).is_err());
+
+        // Vorteil wirkt auf den ersten einzelnen Würfel, auch wenn davor andere Würfel stehen
+        let result = roll("2d6+1d20 adv", Some(7), &no_vars).unwrap();
+        assert_eq!(result.terms[0].dice.len(), 2);
+        assert!(result.terms[0].dice.iter().all(|d| d.kept));
+        assert_eq!(result.terms[1].dice.iter().filter(|d| d.kept).count(), 1);
+    }
+
+    #[test]
+    fn test_roll_overflow_is_an_error() {
+        assert!(roll("2147483647+1", Some(1), &no_vars).is_err());
+        assert!(roll("1d20-@min", Some(1), &|_| Some(i32::MIN)).is_err());
+        assert_eq!(roll("2147483647-1", Some(1), &no_vars).unwrap().total, i32::MAX - 1);
+    }
+
+    #[test]
+    fn test_dice_distribution_in_range() {
+        let mut rng = DiceRng::from_seed(123);
+        let mut seen = [false; 6];
+        for _ in 0..600 {
+            let value = rng.roll_die(6);
+            assert!((1..=6).contains(&value));
+            seen[value as usize - 1] = true;
+        }
+        assert!(seen.iter().all(|s| *s));
+    }
+}
@@ -0,0 +1,86 @@
+//! Suchanfragen für die Volltextsuche (FTS5) im Kompendium.
+//!
+//! Der Index nutzt `unicode61 remove_diacritics 2`, d.h. "Rettungswürfe" findet auch
+//! "rettungswurfe". Umschreibungen wie "ue" oder "ss" statt "ü"/"ß" kennt der Tokenizer
+//! nicht, deshalb sucht jedes Wort zusätzlich in diesen Schreibweisen.
+//! Jedes Wort wird als Präfix gesucht ("Rettungsw" findet "Rettungswürfe").
+
+/// "ae"/"oe"/"ue" -> "ä"/"ö"/"ü", außer nach Vokal oder "q" ("Feuer", "Quelle").
+fn umlauts(word: &str) -> String {
+    let chars: Vec<char> = word.chars().collect();
+    let mut result = String::with_capacity(word.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let umlaut = match (chars[i], chars.get(i + 1)) {
+            ('a', Some('e')) => Some('ä'),
+            ('o', Some('e')) => Some('ö'),
+            ('u', Some('e')) => Some('ü'),
+            _ => None,
+        };
+        let after_vowel = i > 0 && "aeiouq".contains(chars[i - 1]);
+        match umlaut {
+            Some(umlaut) if !after_vowel => {
+                result.push(umlaut);
+                i += 2;
+            }
+            _ => {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Schreibweisen eines Wortes: wie eingegeben, ß <-> ss, ae/oe/ue -> ä/ö/ü.
+pub fn word_variants(word: &str) -> Vec<String> {
+    let word = word.to_lowercase();
+    let mut variants = vec![word.replace('ß', "ss"), word.replace("ss", "ß"), umlauts(&word), word];
+    variants.sort();
+    variants.dedup();
+    variants
+}
+
+/// Baut aus einer Benutzereingabe einen FTS5-`MATCH`-Ausdruck; alle Wörter müssen vorkommen.
+/// Sonderzeichen der FTS5-Syntax werden verworfen. `None` bei leerer Eingabe.
+pub fn match_query(input: &str) -> Option<String> {
+    let groups: Vec<String> = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|word| {
+            let terms: Vec<String> = word_variants(word).iter().map(|v| format!("\"{}\"*", v)).collect();
+            if terms.len() == 1 {
+                terms[0].clone()
+            } else {
+                format!("({})", terms.join(" OR "))
+            }
+        })
+        .collect();
+    (!groups.is_empty()).then(|| groups.join(" AND "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_variants() {
+        assert_eq!(word_variants("Vorteil"), vec!["vorteil"]);
+        assert_eq!(word_variants("Größe"), vec!["grösse", "größe"]);
+        assert_eq!(word_variants("Feuer"), vec!["feuer"]);
+        assert_eq!(word_variants("Ruestung"), vec!["ruestung", "rüstung"]);
+        assert_eq!(word_variants("Schloss"), vec!["schloss", "schloß"]);
+    }
+
+    #[test]
+    fn test_match_query() {
+        assert_eq!(
+            match_query("Vorteil auf Rettungsw").as_deref(),
+            Some("\"vorteil\"* AND \"auf\"* AND \"rettungsw\"*")
+        );
+        // FTS5-Syntax aus der Eingabe wird nicht durchgereicht
+        assert_eq!(match_query("feuer* OR \"ball\"").as_deref(), Some("\"feuer\"* AND \"or\"* AND \"ball\"*"));
+        assert_eq!(match_query("Ruestung").as_deref(), Some("(\"ruestung\"* OR \"rüstung\"*)"));
+        assert_eq!(match_query("  -- ").as_deref(), None);
+    }
+}
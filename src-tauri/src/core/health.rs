@@ -0,0 +1,331 @@
+//! Schaden, Heilung, temporäre TP und Todesrettungswürfe nach den Regeln 2024.
+//!
+//! Der Zustand steckt vollständig im `HealthPool`: 0 TP mit weniger als drei
+//! Erfolgen/Fehlschlägen heißt "sterbend", drei Erfolge "stabil", drei Fehlschläge "tot".
+//! Sofortiger Tod durch massiven Schaden setzt ebenfalls drei Fehlschläge.
+
+use serde::Serialize;
+use crate::core::concentration::ConcentrationResult;
+use crate::core::dice::DiceRng;
+use crate::types::character::HealthPool;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LifeStatus {
+    Conscious,
+    Dying,
+    Stable,
+    Dead,
+}
+
+pub fn life_status(health: &HealthPool) -> LifeStatus {
+    if health.death_saves.failures >= 3 {
+        LifeStatus::Dead
+    } else if health.current > 0 {
+        LifeStatus::Conscious
+    } else if health.death_saves.successes >= 3 {
+        LifeStatus::Stable
+    } else {
+        LifeStatus::Dying
+    }
+}
+
+fn ensure_alive(health: &HealthPool) -> Result<(), String> {
+    if life_status(health) == LifeStatus::Dead {
+        return Err("Der Charakter ist tot".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DamageResult {
+    pub amount: i32,
+    pub absorbed_by_temp: i32,
+    pub hp_damage: i32,
+    pub hp_before: i32,
+    pub hp_after: i32,
+    pub temp_after: i32,
+    pub dropped_to_zero: bool,
+    /// Restschaden nach 0 TP erreicht das TP-Maximum
+    pub instant_death: bool,
+    /// Fehlschläge durch Schaden bei 0 TP (zwei bei kritischem Treffer)
+    pub death_save_failures_added: i32,
+    pub status: LifeStatus,
+    /// Konzentrationsprüfung bzw. Ende der Konzentration (wird von `db::health` ergänzt)
+    pub concentration: Option<ConcentrationResult>,
+}
+
+/// Fügt Schaden zu: erst temporäre TP, dann TP. `hp_max` ist das effektive Maximum.
+pub fn apply_damage(health: &mut HealthPool, amount: i32, critical: bool, hp_max: i32) -> Result<DamageResult, String> {
+    ensure_alive(health)?;
+    if amount < 0 {
+        return Err(format!("Ungültiger Schaden: {}", amount));
+    }
+
+    let hp_before = health.current;
+    let absorbed_by_temp = amount.min(health.temp.max(0));
+    health.temp -= absorbed_by_temp;
+    let remaining = amount - absorbed_by_temp;
+
+    let mut result = DamageResult {
+        amount,
+        absorbed_by_temp,
+        hp_damage: 0,
+        hp_before,
+        hp_after: hp_before,
+        temp_after: health.temp,
+        dropped_to_zero: false,
+        instant_death: false,
+        death_save_failures_added: 0,
+        status: life_status(health),
+        concentration: None,
+    };
+    if remaining == 0 {
+        return Ok(result);
+    }
+
+    if health.current > 0 {
+        let overflow = remaining - health.current;
+        result.hp_damage = remaining.min(health.current);
+        health.current = (health.current - remaining).max(0);
+        if health.current == 0 {
+            result.dropped_to_zero = true;
+            health.death_saves.successes = 0;
+            health.death_saves.failures = 0;
+            if overflow >= hp_max {
+                result.instant_death = true;
+                health.death_saves.failures = 3;
+            }
+        }
+    } else if remaining >= hp_max {
+        result.instant_death = true;
+        health.death_saves.failures = 3;
+    } else {
+        // Schaden bei 0 TP: Fehlschlag, und Stabilisierung endet
+        let failures = if critical { 2 } else { 1 };
+        result.death_save_failures_added = failures.min(3 - health.death_saves.failures);
+        health.death_saves.failures = (health.death_saves.failures + failures).min(3);
+        health.death_saves.successes = 0;
+    }
+
+    result.hp_after = health.current;
+    result.status = life_status(health);
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealingResult {
+    pub amount: i32,
+    pub healed: i32,
+    pub hp_before: i32,
+    pub hp_after: i32,
+    pub death_saves_cleared: bool,
+    pub status: LifeStatus,
+}
+
+/// Heilt bis zum effektiven Maximum. Heilung aus 0 TP setzt die Todesrettungswürfe zurück.
+pub fn apply_healing(health: &mut HealthPool, amount: i32, hp_max: i32) -> Result<HealingResult, String> {
+    ensure_alive(health)?;
+    if amount < 0 {
+        return Err(format!("Ungültige Heilung: {}", amount));
+    }
+
+    let hp_before = health.current;
+    health.current = (health.current + amount).min(hp_max.max(health.current));
+
+    let death_saves_cleared = hp_before <= 0 && health.current > 0;
+    if death_saves_cleared {
+        health.death_saves.successes = 0;
+        health.death_saves.failures = 0;
+    }
+
+    Ok(HealingResult {
+        amount,
+        healed: health.current - hp_before,
+        hp_before,
+        hp_after: health.current,
+        death_saves_cleared,
+        status: life_status(health),
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TempHpResult {
+    pub temp_before: i32,
+    pub temp_after: i32,
+    /// false, wenn die vorhandenen temporären TP höher waren und behalten wurden
+    pub applied: bool,
+}
+
+/// Temporäre TP stapeln nicht: ohne `replace` bleibt der höhere Wert.
+/// Mit `replace` wird der Wert gesetzt (z.B. 0 zum Entfernen).
+pub fn set_temp_hp(health: &mut HealthPool, amount: i32, replace: bool) -> Result<TempHpResult, String> {
+    if amount < 0 {
+        return Err(format!("Ungültige temporäre TP: {}", amount));
+    }
+    let temp_before = health.temp;
+    let applied = replace || amount > health.temp;
+    if applied {
+        health.temp = amount;
+    }
+    Ok(TempHpResult { temp_before, temp_after: health.temp, applied })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathSaveOutcome {
+    Success,
+    Failure,
+    /// Natürliche 20: 1 TP, wieder bei Bewusstsein
+    CriticalSuccess,
+    /// Natürliche 1: zwei Fehlschläge
+    CriticalFailure,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeathSaveResult {
+    pub roll: u32,
+    pub seed: u64,
+    pub outcome: DeathSaveOutcome,
+    pub successes: i32,
+    pub failures: i32,
+    pub status: LifeStatus,
+}
+
+/// Todesrettungswurf (W20, SG 10) für einen sterbenden Charakter.
+pub fn roll_death_save(health: &mut HealthPool, seed: u64) -> Result<DeathSaveResult, String> {
+    match life_status(health) {
+        LifeStatus::Dying => {}
+        LifeStatus::Conscious => return Err("Todesrettungswürfe nur bei 0 TP".to_string()),
+        LifeStatus::Stable => return Err("Der Charakter ist bereits stabil".to_string()),
+        LifeStatus::Dead => return Err("Der Charakter ist tot".to_string()),
+    }
+
+    let roll = DiceRng::from_seed(seed).roll_die(20);
+    let outcome = match roll {
+        20 => DeathSaveOutcome::CriticalSuccess,
+        1 => DeathSaveOutcome::CriticalFailure,
+        r if r >= 10 => DeathSaveOutcome::Success,
+        _ => DeathSaveOutcome::Failure,
+    };
+
+    let saves = &mut health.death_saves;
+    match outcome {
+        DeathSaveOutcome::CriticalSuccess => {
+            health.current = 1;
+            saves.successes = 0;
+            saves.failures = 0;
+        }
+        DeathSaveOutcome::CriticalFailure => saves.failures = (saves.failures + 2).min(3),
+        DeathSaveOutcome::Success => saves.successes = (saves.successes + 1).min(3),
+        DeathSaveOutcome::Failure => saves.failures = (saves.failures + 1).min(3),
+    }
+
+    Ok(DeathSaveResult {
+        roll,
+        seed,
+        outcome,
+        successes: health.death_saves.successes,
+        failures: health.death_saves.failures,
+        status: life_status(health),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(current: i32, temp: i32) -> HealthPool {
+        HealthPool { current, max: 30, temp, ..HealthPool::default() }
+    }
+
+    /// Sucht einen Seed, dessen W20-Wurf die Bedingung erfüllt.
+    fn seed_for(predicate: impl Fn(u32) -> bool) -> u64 {
+        (0..10_000).find(|s| predicate(DiceRng::from_seed(*s).roll_die(20))).unwrap()
+    }
+
+    #[test]
+    fn test_damage_temp_hp_first() {
+        let mut h = health(20, 5);
+        let result = apply_damage(&mut h, 8, false, 30).unwrap();
+        assert_eq!((result.absorbed_by_temp, result.hp_damage), (5, 3));
+        assert_eq!((h.current, h.temp), (17, 0));
+        assert_eq!(result.status, LifeStatus::Conscious);
+
+        let result = apply_damage(&mut h, 4, false, 30).unwrap();
+        assert_eq!(h.current, 13);
+        assert_eq!(result.absorbed_by_temp, 0);
+    }
+
+    #[test]
+    fn test_damage_to_zero_and_massive_damage() {
+        let mut h = health(10, 0);
+        let result = apply_damage(&mut h, 25, false, 30).unwrap();
+        assert!(result.dropped_to_zero && !result.instant_death);
+        assert_eq!(result.status, LifeStatus::Dying);
+
+        let mut h = health(10, 0);
+        let result = apply_damage(&mut h, 40, false, 30).unwrap();
+        assert!(result.instant_death);
+        assert_eq!(result.status, LifeStatus::Dead);
+        assert!(apply_healing(&mut h, 5, 30).is_err());
+    }
+
+    #[test]
+    fn test_damage_at_zero_hp() {
+        let mut h = health(0, 0);
+        h.death_saves.successes = 3;
+        assert_eq!(life_status(&h), LifeStatus::Stable);
+
+        let result = apply_damage(&mut h, 3, false, 30).unwrap();
+        assert_eq!(result.death_save_failures_added, 1);
+        assert_eq!((h.death_saves.successes, h.death_saves.failures), (0, 1));
+        assert_eq!(result.status, LifeStatus::Dying);
+
+        let result = apply_damage(&mut h, 3, true, 30).unwrap();
+        assert_eq!(result.death_save_failures_added, 2);
+        assert_eq!(result.status, LifeStatus::Dead);
+    }
+
+    #[test]
+    fn test_healing_and_temp_hp() {
+        let mut h = health(0, 0);
+        h.death_saves.failures = 2;
+        let result = apply_healing(&mut h, 50, 30).unwrap();
+        assert!(result.death_saves_cleared);
+        assert_eq!((h.current, result.healed), (30, 30));
+        assert_eq!(h.death_saves.failures, 0);
+
+        let mut h = health(10, 5);
+        assert!(!set_temp_hp(&mut h, 3, false).unwrap().applied);
+        assert_eq!(h.temp, 5);
+        assert!(set_temp_hp(&mut h, 8, false).unwrap().applied);
+        assert_eq!(h.temp, 8);
+        set_temp_hp(&mut h, 0, true).unwrap();
+        assert_eq!(h.temp, 0);
+    }
+
+    #[test]
+    fn test_death_saves() {
+        let mut h = health(10, 0);
+        assert!(roll_death_save(&mut h, 1).is_err());
+
+        let mut h = health(0, 0);
+        let result = roll_death_save(&mut h, seed_for(|r| r == 20)).unwrap();
+        assert_eq!(result.outcome, DeathSaveOutcome::CriticalSuccess);
+        assert_eq!((h.current, result.status), (1, LifeStatus::Conscious));
+
+        let mut h = health(0, 0);
+        let result = roll_death_save(&mut h, seed_for(|r| r == 1)).unwrap();
+        assert_eq!(result.outcome, DeathSaveOutcome::CriticalFailure);
+        assert_eq!(h.death_saves.failures, 2);
+
+        let success = seed_for(|r| (10..20).contains(&r));
+        let mut h = health(0, 0);
+        for _ in 0..3 {
+            roll_death_save(&mut h, success).unwrap();
+        }
+        assert_eq!(life_status(&h), LifeStatus::Stable);
+        assert!(roll_death_save(&mut h, success).is_err());
+    }
+}
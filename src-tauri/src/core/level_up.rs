@@ -0,0 +1,571 @@
+//! Stufenaufstieg: Vorschau und Anwendung.
+//!
+//! Die Vorschau fasst zusammen, was die nächste Stufe bringt (Merkmale, offene Auswahlen,
+//! TP-Optionen, Zauberplätze). Die Daten aus Kompendium und Stufentabelle liefert `db::level_up`
+//! als `LevelUpInput`; hier wird nur entschieden und angewendet.
+//! Aufgestiegen wird in einer bestimmten Klasse; eine neue Klasse ist ein Mehrklassen-Aufstieg
+//! (siehe `core::multiclass`).
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::core::dice::DiceRng;
+use crate::core::multiclass::{
+    add_hit_die, apply_multiclass_proficiencies, class_level, classes_after_level_up,
+    multiclass_proficiencies, normalize_classes, same_class, sync_meta_from_classes,
+    unmet_multiclass_prerequisites, MulticlassProficiencies,
+};
+use crate::types::character::Character;
+
+pub const MAX_LEVEL: i32 = 20;
+/// ID des Talents "Attributswerterhöhung" in `all_feats`
+pub const ASI_FEAT_ID: &str = "attributswerterhöhung";
+
+fn normalize(value: &str) -> String {
+    value
+        .trim()
+        .to_lowercase()
+        .replace('ä', "ae")
+        .replace('ö', "oe")
+        .replace('ü', "ue")
+        .replace('ß', "ss")
+        .replace(['_', '-', ' '], "")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChoiceOption {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Talentkategorie bei Talenten ("Allgemein", "Kampfstil", ...), sonst None
+    #[serde(default)]
+    pub feat_category: Option<String>,
+}
+
+impl ChoiceOption {
+    fn matches(&self, value: &str) -> bool {
+        let value = normalize(value);
+        normalize(&self.id) == value || normalize(&self.name) == value
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelUpFeature {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub feature_type: String,
+    pub subclass_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChoiceKind {
+    Subclass,
+    /// Attributswerterhöhung oder ein allgemeines Talent
+    AsiOrFeat,
+    EpicBoon,
+    FightingStyle,
+    FeatureOption,
+}
+
+/// Offene Auswahl. `key` ist die Merkmals-ID (bzw. "subclass") und Schlüssel in `LevelUpChoices`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingChoice {
+    pub key: String,
+    pub kind: ChoiceKind,
+    pub name: String,
+    pub options: Vec<ChoiceOption>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HpOptions {
+    pub hit_die: u32,
+    pub con_modifier: i32,
+    /// TP-Zuwachs mit Durchschnittswert (W/2 + 1 + KON, mindestens 1)
+    pub average: i32,
+    pub min: i32,
+    pub max: i32,
+    /// `HealthPool::use_rolled_hp`: es wird gewürfelt statt Durchschnitt genommen
+    pub use_rolled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlotChange {
+    pub level: i32,
+    pub before: i32,
+    pub after: i32,
+}
+
+/// Hinzunahme einer weiteren Klasse.
+#[derive(Debug, Clone, Serialize)]
+pub struct MulticlassPreview {
+    /// Nicht erfüllte Voraussetzungen (leer = Aufstieg möglich)
+    pub unmet_prerequisites: Vec<String>,
+    pub proficiencies: MulticlassProficiencies,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelUpPreview {
+    pub class_id: String,
+    /// Gesamtstufe
+    pub from_level: i32,
+    pub to_level: i32,
+    /// Stufe in der aufsteigenden Klasse (0 = neue Klasse)
+    pub class_level_before: i32,
+    pub class_level_after: i32,
+    pub multiclass: Option<MulticlassPreview>,
+    pub proficiency_bonus_before: i32,
+    pub proficiency_bonus_after: i32,
+    pub features: Vec<LevelUpFeature>,
+    pub choices: Vec<PendingChoice>,
+    pub hp: HpOptions,
+    /// Nur geänderte Grade
+    pub spell_slots: Vec<SlotChange>,
+}
+
+/// Kompendiumsdaten für die nächste Stufe, geladen von `db::level_up`.
+#[derive(Debug, Clone, Default)]
+pub struct LevelUpInput {
+    /// Klasse, in der aufgestiegen wird
+    pub class_id: String,
+    pub hit_die: u32,
+    pub con_modifier: i32,
+    pub proficiency_bonus_before: i32,
+    pub proficiency_bonus_after: i32,
+    pub spell_slots_after: Option<[i32; 9]>,
+    /// Klassen- und Unterklassenmerkmale genau der nächsten Klassenstufe
+    pub features: Vec<LevelUpFeature>,
+    /// Optionen aus `all_feature_options` je Merkmals-ID
+    pub feature_options: HashMap<String, Vec<ChoiceOption>>,
+    /// Bis zur nächsten Stufe verfügbare Unterklassen
+    pub subclasses: Vec<ChoiceOption>,
+    pub general_feats: Vec<ChoiceOption>,
+    pub epic_boons: Vec<ChoiceOption>,
+    pub fighting_styles: Vec<ChoiceOption>,
+}
+
+/// Auswahlen für `apply_level_up`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LevelUpChoices {
+    /// Klasse, in der aufgestiegen wird (Standard: Startklasse)
+    #[serde(default)]
+    pub class_id: Option<String>,
+    #[serde(default)]
+    pub subclass_id: Option<String>,
+    /// Merkmals-ID -> gewählte Option (Talent, Kampfstil oder Merkmalsoption)
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Attributserhöhungen, z.B. {"str": 2} oder {"dex": 1, "con": 1}
+    #[serde(default)]
+    pub ability_increases: HashMap<String, i32>,
+    /// Selbst gewürfelter Trefferwürfel, sonst wird mit `seed` gewürfelt
+    #[serde(default)]
+    pub hp_roll: Option<u32>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Fertigkeiten aus der Mehrklassen-Übung (Barde, Schurke, Waldläufer)
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// Musikinstrument aus der Mehrklassen-Übung (Barde)
+    #[serde(default)]
+    pub instrument: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedChoice {
+    pub key: String,
+    pub kind: ChoiceKind,
+    pub value: String,
+}
+
+/// Eintrag in der Stufenhistorie.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelUpRecord {
+    pub level: i32,
+    pub class_id: String,
+    #[serde(default)]
+    pub class_level: i32,
+    pub hp_gained: i32,
+    /// Gewürfelter Trefferwürfel (None = Durchschnitt)
+    pub hp_roll: Option<u32>,
+    pub seed: Option<u64>,
+    pub features: Vec<String>,
+    pub choices: Vec<ResolvedChoice>,
+    pub ability_increases: HashMap<String, i32>,
+    pub spell_slots: Vec<i32>,
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+/// Charakter mit aktuellen `classes` (Altdaten haben nur `class_id`).
+fn normalized(character: &Character) -> Character {
+    let mut character = character.clone();
+    normalize_classes(&mut character.meta);
+    character
+}
+
+fn has_subclass_choice(character: &Character, input: &LevelUpInput) -> bool {
+    let subclass = character.meta.classes.iter()
+        .find(|c| same_class(&c.class_id, &input.class_id))
+        .and_then(|c| c.subclass_id.as_deref());
+    subclass.is_none_or(|s| s.trim().is_empty()) && !input.subclasses.is_empty()
+}
+
+fn choice_for(feature: &LevelUpFeature, input: &LevelUpInput) -> Option<PendingChoice> {
+    let id = normalize(&feature.id);
+    let options = input.feature_options.get(&feature.id).cloned().unwrap_or_default();
+    let (kind, options) = if id.contains("attributswerterhoehung") {
+        (ChoiceKind::AsiOrFeat, input.general_feats.clone())
+    } else if id.contains("epischegabe") {
+        (ChoiceKind::EpicBoon, input.epic_boons.clone())
+    } else if id.contains("kampfstil") {
+        (ChoiceKind::FightingStyle, input.fighting_styles.iter().cloned().chain(options).collect())
+    } else if feature.feature_type == "choice" && !options.is_empty() {
+        (ChoiceKind::FeatureOption, options)
+    } else {
+        return None;
+    };
+    Some(PendingChoice { key: feature.id.clone(), kind, name: feature.name.clone(), options })
+}
+
+pub fn preview_level_up(character: &Character, input: &LevelUpInput) -> Result<LevelUpPreview, String> {
+    let character = &normalized(character);
+    let from_level = character.meta.level;
+    if from_level >= MAX_LEVEL {
+        return Err(format!("Stufe {} ist bereits die Höchststufe", MAX_LEVEL));
+    }
+    if input.hit_die == 0 {
+        return Err(format!("Kein Trefferwürfel für Klasse '{}' gefunden", input.class_id));
+    }
+
+    let mut choices = Vec::new();
+    if has_subclass_choice(character, input) {
+        choices.push(PendingChoice {
+            key: "subclass".to_string(),
+            kind: ChoiceKind::Subclass,
+            name: "Unterklasse".to_string(),
+            options: input.subclasses.clone(),
+        });
+    }
+    choices.extend(input.features.iter().filter_map(|f| choice_for(f, input)));
+
+    let class_level_before = class_level(&character.meta, &input.class_id);
+    let multiclass = (class_level_before == 0 && !character.meta.classes.is_empty()).then(|| MulticlassPreview {
+        unmet_prerequisites: unmet_multiclass_prerequisites(character, &input.class_id),
+        proficiencies: multiclass_proficiencies(&input.class_id),
+    });
+
+    let die = input.hit_die as i32;
+    let hp = HpOptions {
+        hit_die: input.hit_die,
+        con_modifier: input.con_modifier,
+        average: (die / 2 + 1 + input.con_modifier).max(1),
+        min: (1 + input.con_modifier).max(1),
+        max: (die + input.con_modifier).max(1),
+        use_rolled: character.health.use_rolled_hp.unwrap_or(false),
+    };
+
+    let spell_slots = input.spell_slots_after
+        .map(|after| {
+            (1..=9)
+                .map(|level| SlotChange { level, before: character.meta.spell_slots(level), after: after[level as usize - 1] })
+                .filter(|c| c.before != c.after)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(LevelUpPreview {
+        class_id: input.class_id.clone(),
+        from_level,
+        to_level: from_level + 1,
+        class_level_before,
+        class_level_after: class_level_before + 1,
+        multiclass,
+        proficiency_bonus_before: input.proficiency_bonus_before,
+        proficiency_bonus_after: input.proficiency_bonus_after,
+        features: input.features.clone(),
+        choices,
+        hp,
+        spell_slots,
+    })
+}
+
+/// Prüft Attributserhöhungen: genau (`exact`) bzw. höchstens `total` Punkte, Obergrenze `cap`.
+fn apply_ability_increases(character: &mut Character, increases: &HashMap<String, i32>, total: i32, exact: bool, cap: i32) -> Result<(), String> {
+    let sum: i32 = increases.values().sum();
+    if increases.values().any(|v| *v < 1) || sum > total || (exact && sum != total) {
+        return Err(if exact {
+            format!("Attributswerterhöhung: genau {} Punkte verteilen", total)
+        } else {
+            format!("Höchstens {} Punkt(e) Attributserhöhung", total)
+        });
+    }
+    for (ability, value) in increases {
+        let Some(score) = character.attributes.get_mut(ability) else {
+            return Err(format!("Unbekanntes Attribut: {}", ability));
+        };
+        if *score + value > cap {
+            return Err(format!("{} darf {} nicht übersteigen", ability, cap));
+        }
+        *score += value;
+    }
+    Ok(())
+}
+
+fn pick<'a>(choice: &'a PendingChoice, value: Option<&String>) -> Result<&'a ChoiceOption, String> {
+    let value = value.ok_or_else(|| format!("Auswahl fehlt: {}", choice.name))?;
+    choice.options.iter()
+        .find(|o| o.matches(value))
+        .ok_or_else(|| format!("Ungültige Auswahl für {}: {}", choice.name, value))
+}
+
+/// Wendet den Stufenaufstieg an. Alle offenen Auswahlen müssen getroffen sein;
+/// bei einem Fehler bleibt der Charakter unverändert.
+pub fn apply_level_up(character: &mut Character, preview: &LevelUpPreview, choices: &LevelUpChoices) -> Result<LevelUpRecord, String> {
+    let mut updated = normalized(character);
+    if preview.from_level != updated.meta.level || preview.class_level_before != class_level(&updated.meta, &preview.class_id) {
+        return Err("Die Vorschau passt nicht mehr zur aktuellen Stufe".to_string());
+    }
+    if let Some(key) = choices.options.keys().find(|k| !preview.choices.iter().any(|c| &c.key == *k)) {
+        return Err(format!("Keine offene Auswahl für '{}'", key));
+    }
+
+    // Mehrklassen: Voraussetzungen und eingeschränkte Übung
+    match &preview.multiclass {
+        Some(multiclass) if !multiclass.unmet_prerequisites.is_empty() => {
+            return Err(format!("Mehrklassen-Voraussetzungen nicht erfüllt: {}", multiclass.unmet_prerequisites.join(", ")));
+        }
+        Some(_) => {
+            apply_multiclass_proficiencies(&mut updated, &preview.class_id, &choices.skills, choices.instrument.as_deref())?;
+        }
+        None if !choices.skills.is_empty() || choices.instrument.is_some() => {
+            return Err("Fertigkeiten und Instrument gibt es nur beim Hinzunehmen einer Klasse".to_string());
+        }
+        None => {}
+    }
+    updated.meta.classes = classes_after_level_up(&updated.meta, &preview.class_id);
+
+    let mut resolved = Vec::new();
+    // Attributserhöhungen gehören zum ersten Talent der Stufe
+    let mut increases = Some(&choices.ability_increases);
+    let no_increases = HashMap::new();
+
+    for choice in &preview.choices {
+        let value = match choice.kind {
+            ChoiceKind::Subclass => choices.subclass_id.as_ref(),
+            _ => choices.options.get(&choice.key),
+        };
+        let option = pick(choice, value)?;
+        match choice.kind {
+            ChoiceKind::Subclass => {
+                if let Some(entry) = updated.meta.classes.iter_mut().find(|c| same_class(&c.class_id, &preview.class_id)) {
+                    entry.subclass_id = Some(option.id.clone());
+                }
+            }
+            ChoiceKind::AsiOrFeat | ChoiceKind::EpicBoon => {
+                let is_asi = normalize(&option.id) == normalize(ASI_FEAT_ID);
+                if !is_asi && updated.feats.iter().any(|f| normalize(f) == normalize(&option.id)) {
+                    return Err(format!("Talent bereits gewählt: {}", option.name));
+                }
+                // Epische Gaben erhöhen bis 30, Talente um höchstens 1
+                let (total, cap) = match choice.kind {
+                    ChoiceKind::EpicBoon => (1, 30),
+                    _ if is_asi => (2, 20),
+                    _ => (1, 20),
+                };
+                let increases = increases.take().unwrap_or(&no_increases);
+                apply_ability_increases(&mut updated, increases, total, is_asi, cap)?;
+                updated.feats.push(option.id.clone());
+            }
+            // Kampfstil-Talente zählen für `fighting_styles`, Klassenoptionen wie
+            // "Gesegneter Krieger" sind Merkmalsauswahlen
+            ChoiceKind::FightingStyle if option.feat_category.is_some() => {
+                updated.meta.fighting_styles.get_or_insert_with(Vec::new).push(option.id.clone());
+            }
+            ChoiceKind::FightingStyle | ChoiceKind::FeatureOption => {
+                updated.meta.feature_choices.get_or_insert_with(HashMap::new).insert(choice.key.clone(), option.id.clone());
+            }
+        }
+        resolved.push(ResolvedChoice { key: choice.key.clone(), kind: choice.kind, value: option.id.clone() });
+    }
+    if increases.is_some_and(|i| !i.is_empty()) {
+        return Err("Auf dieser Stufe gibt es keine Attributserhöhung".to_string());
+    }
+
+    // Trefferpunkte: Durchschnitt oder Wurf (eigener Wert oder per Seed)
+    let die = preview.hp.hit_die;
+    let (hp_gained, hp_roll, seed) = if preview.hp.use_rolled {
+        let (roll, seed) = match choices.hp_roll {
+            Some(roll) if (1..=die).contains(&roll) => (roll, None),
+            Some(roll) => return Err(format!("Ungültiger Trefferwürfel: {} (W{})", roll, die)),
+            None => {
+                let seed = choices.seed.unwrap_or_else(DiceRng::random_seed);
+                (DiceRng::from_seed(seed).roll_die(die), Some(seed))
+            }
+        };
+        ((roll as i32 + preview.hp.con_modifier).max(1), Some(roll), seed)
+    } else {
+        (preview.hp.average, None, None)
+    };
+    updated.health.max += hp_gained;
+    updated.health.current += hp_gained;
+    add_hit_die(&mut updated.health, die);
+    sync_meta_from_classes(&mut updated.meta);
+
+    for change in &preview.spell_slots {
+        updated.meta.set_spell_slots(change.level, change.after);
+    }
+
+    let record = LevelUpRecord {
+        level: preview.to_level,
+        class_id: preview.class_id.clone(),
+        class_level: preview.class_level_after,
+        hp_gained,
+        hp_roll,
+        seed,
+        features: preview.features.iter().map(|f| f.name.clone()).collect(),
+        choices: resolved,
+        ability_increases: choices.ability_increases.clone(),
+        spell_slots: (1..=9).map(|l| updated.meta.spell_slots(l)).collect(),
+        created_at: None,
+    };
+    *character = updated;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+
+    fn option(id: &str, name: &str) -> ChoiceOption {
+        ChoiceOption { id: id.into(), name: name.into(), description: None, feat_category: None }
+    }
+
+    fn feat(id: &str, category: &str) -> ChoiceOption {
+        ChoiceOption { feat_category: Some(category.into()), ..option(id, &id.to_uppercase()) }
+    }
+
+    fn feature(id: &str, name: &str, feature_type: &str) -> LevelUpFeature {
+        LevelUpFeature {
+            id: id.into(),
+            name: name.into(),
+            description: String::new(),
+            feature_type: feature_type.into(),
+            subclass_id: None,
+        }
+    }
+
+    fn input(features: Vec<LevelUpFeature>) -> LevelUpInput {
+        LevelUpInput {
+            class_id: "kaempfer".into(),
+            hit_die: 10,
+            con_modifier: 2,
+            proficiency_bonus_before: 3,
+            proficiency_bonus_after: 3,
+            features,
+            general_feats: vec![feat(ASI_FEAT_ID, "Allgemein"), feat("wachsam", "Allgemein")],
+            fighting_styles: vec![feat("duellieren", "Kampfstil")],
+            ..LevelUpInput::default()
+        }
+    }
+
+    #[test]
+    fn test_preview_choices_and_hp() {
+        let character = test_character();
+        let input = LevelUpInput {
+            subclasses: vec![option("kaempfer_champion", "Champion")],
+            spell_slots_after: Some([2, 0, 0, 0, 0, 0, 0, 0, 0]),
+            ..input(vec![
+                feature("kämpfer_attributswerterhoehung_l6", "ATTRIBUTSWERTERHÖHUNG", "choice"),
+                feature("kämpfer_taktik_l6", "TAKTIK", "passive"),
+            ])
+        };
+        let preview = preview_level_up(&character, &input).unwrap();
+        assert_eq!((preview.from_level, preview.to_level), (5, 6));
+        let kinds: Vec<ChoiceKind> = preview.choices.iter().map(|c| c.kind).collect();
+        assert_eq!(kinds, vec![ChoiceKind::Subclass, ChoiceKind::AsiOrFeat]);
+        assert_eq!((preview.hp.average, preview.hp.min, preview.hp.max), (8, 3, 12));
+        assert_eq!(preview.spell_slots.len(), 1);
+
+        let mut max_level = test_character();
+        max_level.meta.level = MAX_LEVEL;
+        assert!(preview_level_up(&max_level, &input).is_err());
+    }
+
+    #[test]
+    fn test_apply_asi_and_average_hp() {
+        let mut character = test_character();
+        character.meta.subclass_id = Some("kaempfer_champion".into());
+        let (hp_before, str_before) = (character.health.max, character.attributes.str);
+        let preview = preview_level_up(&character, &input(vec![
+            feature("kämpfer_attributswerterhoehung_l6", "ATTRIBUTSWERTERHÖHUNG", "choice"),
+        ])).unwrap();
+
+        // Fehlende und ungültige Auswahlen ändern nichts
+        assert!(apply_level_up(&mut character, &preview, &LevelUpChoices::default()).is_err());
+        let mut choices = LevelUpChoices::default();
+        choices.options.insert("kämpfer_attributswerterhoehung_l6".into(), "Attributswerterhöhung".into());
+        choices.ability_increases.insert("str".into(), 3);
+        assert!(apply_level_up(&mut character, &preview, &choices).is_err());
+        assert_eq!(character.meta.level, 5);
+
+        choices.ability_increases.insert("str".into(), 2);
+        let record = apply_level_up(&mut character, &preview, &choices).unwrap();
+        assert_eq!(character.meta.level, 6);
+        assert_eq!(character.attributes.str, str_before + 2);
+        assert_eq!(character.health.max, hp_before + 8);
+        assert_eq!((record.hp_gained, record.hp_roll), (8, None));
+        assert_eq!(character.feats, vec![ASI_FEAT_ID.to_string()]);
+
+        // Dieselbe Vorschau gilt nicht ein zweites Mal
+        assert!(apply_level_up(&mut character, &preview, &choices).is_err());
+    }
+
+    #[test]
+    fn test_apply_rolled_hp_and_fighting_style() {
+        let mut character = test_character();
+        character.meta.subclass_id = Some("kaempfer_champion".into());
+        character.health.use_rolled_hp = Some(true);
+        let preview = preview_level_up(&character, &input(vec![feature("kämpfer_kampfstil_l1", "KAMPFSTIL", "choice")])).unwrap();
+
+        let mut choices = LevelUpChoices { hp_roll: Some(11), ..LevelUpChoices::default() };
+        choices.options.insert("kämpfer_kampfstil_l1".into(), "duellieren".into());
+        assert!(apply_level_up(&mut character.clone(), &preview, &choices).is_err());
+
+        choices.hp_roll = None;
+        choices.seed = Some(7);
+        let record = apply_level_up(&mut character, &preview, &choices).unwrap();
+        let roll = DiceRng::from_seed(7).roll_die(10);
+        assert_eq!((record.hp_roll, record.hp_gained), (Some(roll), roll as i32 + 2));
+        assert_eq!(character.meta.fighting_styles, Some(vec!["duellieren".to_string()]));
+    }
+
+    #[test]
+    fn test_multiclass_level_up() {
+        let mut character = test_character();
+        let rogue = LevelUpInput { class_id: "schurke".into(), hit_die: 8, ..input(Vec::new()) };
+        let preview = preview_level_up(&character, &rogue).unwrap();
+        assert_eq!((preview.to_level, preview.class_level_after), (6, 1));
+        let multiclass = preview.multiclass.as_ref().unwrap();
+        assert!(multiclass.unmet_prerequisites.is_empty());
+        assert_eq!(multiclass.proficiencies.tools, vec!["diebeswerkzeug".to_string()]);
+
+        // Die Fertigkeit aus der Schurken-Übung ist Pflicht
+        assert!(apply_level_up(&mut character, &preview, &LevelUpChoices::default()).is_err());
+        let choices = LevelUpChoices { skills: vec!["heimlichkeit".into()], ..LevelUpChoices::default() };
+        apply_level_up(&mut character, &preview, &choices).unwrap();
+        assert_eq!(character.meta.level, 6);
+        assert_eq!(character.meta.class_id.as_deref(), Some("kaempfer"));
+        let levels: Vec<(&str, i32)> = character.meta.classes.iter().map(|c| (c.class_id.as_str(), c.level)).collect();
+        assert_eq!(levels, vec![("kaempfer", 5), ("schurke", 1)]);
+        assert!(character.proficiencies.skills.contains(&"heimlichkeit".to_string()));
+
+        // Paladin braucht CHA 13
+        let paladin = LevelUpInput { class_id: "paladin".into(), ..input(Vec::new()) };
+        let preview = preview_level_up(&character, &paladin).unwrap();
+        assert!(!preview.multiclass.as_ref().unwrap().unmet_prerequisites.is_empty());
+        assert!(apply_level_up(&mut character, &preview, &LevelUpChoices::default()).is_err());
+    }
+}
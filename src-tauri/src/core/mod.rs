@@ -2,13 +2,20 @@
 pub mod units;
 pub mod calculator;
 pub mod modifiers;
+pub mod armor;
 pub mod types;
-
-
-
-
-
-
-
-
-
+pub mod sheet;
+pub mod attacks;
+pub mod dice;
+pub mod rest;
+pub mod resources;
+pub mod health;
+pub mod conditions;
+pub mod concentration;
+pub mod level_up;
+pub mod multiclass;
+pub mod spell_slots;
+pub mod spell_preparation;
+pub mod casting;
+pub mod spell_parser;
+pub mod fulltext;
@@ -1,33 +1,427 @@
+use serde::Serialize;
 use crate::core::types::{Modifier, ModifierType};
 
-#[allow(dead_code)]
-pub fn apply_modifiers(base: i32, modifiers: &[Modifier]) -> i32 {
-    let mut total = base;
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ContributionKind {
+    /// Grundwert oder Teil davon (z.B. "Basis", "DEX", "Schild")
+    Base,
+    Override,
+    Add,
+    Multiply,
+}
 
-    // 1. Overrides (höchster Wert gewinnt bei mehreren Overrides)
-    let overrides: Vec<&Modifier> = modifiers
-        .iter()
-        .filter(|m| m.modifier_type == ModifierType::Override)
-        .collect();
+/// Ein einzelner Beitrag zu einem Endwert.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Contribution {
+    pub source: String,
+    pub kind: ContributionKind,
+    pub value: i32,
+    /// false, wenn der Beitrag durch einen Override verdrängt wurde
+    pub applied: bool,
+}
+
+/// Endwert samt Herleitung, z.B. für Tooltips ("10 Basis + 3 DEX + 2 Schild").
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct ModifierBreakdown {
+    pub total: i32,
+    pub contributions: Vec<Contribution>,
+}
+
+impl ModifierBreakdown {
+    /// Startet eine Herleitung, deren Grundwert aus mehreren Teilen besteht.
+    pub fn from_parts(parts: &[(&str, i32)]) -> Self {
+        let contributions: Vec<Contribution> = parts
+            .iter()
+            .map(|(source, value)| Contribution {
+                source: source.to_string(),
+                kind: ContributionKind::Base,
+                value: *value,
+                applied: true,
+            })
+            .collect();
+        Self {
+            total: contributions.iter().map(|c| c.value).sum(),
+            contributions,
+        }
+    }
+
+    /// Wie `from_parts`, lässt aber Teile mit Wert 0 (außer dem ersten) weg, damit Tooltips lesbar bleiben.
+    pub fn from_nonzero_parts(parts: &[(&str, i32)]) -> Self {
+        let parts: Vec<(&str, i32)> = parts
+            .iter()
+            .enumerate()
+            .filter(|(i, (_, value))| *i == 0 || *value != 0)
+            .map(|(_, part)| *part)
+            .collect();
+        Self::from_parts(&parts)
+    }
+
+    /// Wendet Modifikatoren in der Reihenfolge Override -> Add -> Multiply an.
+    pub fn apply(mut self, modifiers: &[Modifier]) -> Self {
+        // 1. Overrides (höchster Wert gewinnt bei mehreren Overrides)
+        let winner = modifiers
+            .iter()
+            .filter(|m| m.modifier_type == ModifierType::Override)
+            .max_by_key(|m| m.value)
+            .map(|m| m.id.clone());
 
-    if !overrides.is_empty() {
-        total = overrides.iter().map(|m| m.value).max().unwrap_or(total);
+        if winner.is_some() {
+            for c in self.contributions.iter_mut() {
+                c.applied = false;
+            }
+        }
+        for modifier in modifiers.iter().filter(|m| m.modifier_type == ModifierType::Override) {
+            let applied = winner.as_deref() == Some(modifier.id.as_str());
+            if applied {
+                self.total = modifier.value;
+            }
+            self.contributions.push(Contribution {
+                source: modifier.source.clone(),
+                kind: ContributionKind::Override,
+                value: modifier.value,
+                applied,
+            });
+        }
+
+        // 2. Add
+        for modifier in modifiers.iter().filter(|m| m.modifier_type == ModifierType::Add) {
+            self.total += modifier.value;
+            self.push(modifier, ContributionKind::Add);
+        }
+
+        // 3. Multiply
+        for modifier in modifiers
+            .iter()
+            .filter(|m| m.modifier_type == ModifierType::Multiply)
+        {
+            self.total *= modifier.value;
+            self.push(modifier, ContributionKind::Multiply);
+        }
+
+        self
+    }
+
+    fn push(&mut self, modifier: &Modifier, kind: ContributionKind) {
+        self.contributions.push(Contribution {
+            source: modifier.source.clone(),
+            kind,
+            value: modifier.value,
+            applied: true,
+        });
     }
 
-    // 2. Add
-    for modifier in modifiers.iter().filter(|m| m.modifier_type == ModifierType::Add) {
-        total += modifier.value;
+    /// Lesbare Herleitung, z.B. "10 Basis + 3 DEX + 2 Schild + 1 Ring of Protection".
+    pub fn describe(&self) -> String {
+        let mut text = String::new();
+        for c in self.contributions.iter().filter(|c| c.applied) {
+            let part = match c.kind {
+                ContributionKind::Multiply => format!("× {} {}", c.value, c.source),
+                ContributionKind::Override => format!("= {} {}", c.value, c.source),
+                _ if text.is_empty() => format!("{} {}", c.value, c.source),
+                _ if c.value < 0 => format!("- {} {}", -c.value, c.source),
+                _ => format!("+ {} {}", c.value, c.source),
+            };
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&part);
+        }
+        text
     }
+}
+
+/// Wendet Modifikatoren auf einen (ggf. zusammengesetzten) Grundwert an und liefert die vollständige
+/// Herleitung. Teile mit Wert 0 (außer dem ersten) erscheinen nicht in der Herleitung.
+pub fn apply_modifiers(parts: &[(&str, i32)], modifiers: &[Modifier]) -> ModifierBreakdown {
+    ModifierBreakdown::from_nonzero_parts(parts).apply(modifiers)
+}
 
-    // 3. Multiply
-    for modifier in modifiers
+/// Filtert alle Modifikatoren für ein Ziel (z.B. "ac", "str", "skill:athletik").
+pub fn modifiers_for(modifiers: &[Modifier], target: &str) -> Vec<Modifier> {
+    modifiers
         .iter()
-        .filter(|m| m.modifier_type == ModifierType::Multiply)
-    {
-        total *= modifier.value;
+        .filter(|m| m.target.trim().eq_ignore_ascii_case(target))
+        .cloned()
+        .collect()
+}
+
+// --- Bedingte Modifikatoren ---
+//
+// `Modifier.condition` enthält einen kleinen Ausdruck, der gegen den aktuellen
+// Charakterzustand geprüft wird, z.B.:
+//   wearing_armor == none              (Ungerüstete Verteidigung)
+//   wearing_armor != none              (Kampfstil Verteidigung)
+//   wielding == two_handed
+//   hp < half
+//   condition:raging                   (Kampfrausch aktiv)
+//   wearing_armor == none && !shield
+//
+// Schlüssel: wearing_armor (none/light/medium/heavy/any), shield, wielding
+// (none/one_handed/two_handed), hp (Zahl, "half", "max"), level, condition:<name>.
+// Operatoren: == != < <= > >=, verknüpft mit && / and, || / or, ! / not.
+
+/// Zustand des Charakters, gegen den Bedingungen geprüft werden.
+#[derive(Debug, Clone)]
+pub struct ConditionContext {
+    /// "none", "light", "medium", "heavy"
+    pub wearing_armor: String,
+    pub has_shield: bool,
+    /// "none", "one_handed", "two_handed"
+    pub wielding: String,
+    pub hp_current: i32,
+    pub hp_max: i32,
+    pub level: i32,
+    /// Aktive Zustände in Kleinbuchstaben (z.B. "raging", "poisoned")
+    pub active_conditions: Vec<String>,
+}
+
+impl Default for ConditionContext {
+    fn default() -> Self {
+        Self {
+            wearing_armor: "none".to_string(),
+            has_shield: false,
+            wielding: "none".to_string(),
+            hp_current: 0,
+            hp_max: 0,
+            level: 1,
+            active_conditions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    /// `key op value`
+    Compare { key: String, op: CompareOp, value: String },
+    /// Bloßer Schlüssel (z.B. `shield`) oder `condition:<name>`
+    Flag(String),
+    Not(Box<Condition>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+fn tokenize(input: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let two: String = chars[i..chars.len().min(i + 2)].iter().collect();
+        if ["==", "!=", "<=", ">=", "&&", "||"].contains(&two.as_str()) {
+            tokens.push(two);
+            i += 2;
+        } else if ['<', '>', '!', '(', ')'].contains(&c) {
+            tokens.push(c.to_string());
+            i += 1;
+        } else if c.is_alphanumeric() || c == '_' || c == ':' || c == '-' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || ['_', ':', '-'].contains(&chars[i])) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect::<String>().to_lowercase());
+        } else {
+            return Err(format!("Unerwartetes Zeichen '{}' in Bedingung '{}'", c, input));
+        }
     }
+    Ok(tokens)
+}
 
-    total
+struct ConditionParser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl ConditionParser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|t| t.as_str())
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Condition, String> {
+        let mut parts = vec![self.parse_and()?];
+        while matches!(self.peek(), Some("||") | Some("or")) {
+            self.pos += 1;
+            parts.push(self.parse_and()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Condition::Or(parts) })
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut parts = vec![self.parse_unary()?];
+        while matches!(self.peek(), Some("&&") | Some("and")) {
+            self.pos += 1;
+            parts.push(self.parse_unary()?);
+        }
+        Ok(if parts.len() == 1 { parts.remove(0) } else { Condition::And(parts) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Condition, String> {
+        match self.peek() {
+            Some("!") | Some("not") => {
+                self.pos += 1;
+                Ok(Condition::Not(Box::new(self.parse_unary()?)))
+            }
+            Some("(") => {
+                self.pos += 1;
+                let inner = self.parse_or()?;
+                match self.next().as_deref() {
+                    Some(")") => Ok(inner),
+                    _ => Err("Fehlende schließende Klammer".to_string()),
+                }
+            }
+            _ => self.parse_atom(),
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Condition, String> {
+        let key = self.next().ok_or("Unvollständige Bedingung")?;
+        if ["==", "!=", "<", "<=", ">", ">=", "&&", "||", ")"].contains(&key.as_str()) {
+            return Err(format!("Schlüssel erwartet, '{}' gefunden", key));
+        }
+        let op = match self.peek() {
+            Some("==") => CompareOp::Eq,
+            Some("!=") => CompareOp::Ne,
+            Some("<") => CompareOp::Lt,
+            Some("<=") => CompareOp::Le,
+            Some(">") => CompareOp::Gt,
+            Some(">=") => CompareOp::Ge,
+            _ => return Ok(Condition::Flag(key)),
+        };
+        self.pos += 1;
+        let value = self
+            .next()
+            .ok_or_else(|| format!("Wert nach '{}' erwartet", key))?;
+        Ok(Condition::Compare { key, op, value })
+    }
+}
+
+impl Condition {
+    pub fn parse(input: &str) -> Result<Condition, String> {
+        let mut parser = ConditionParser {
+            tokens: tokenize(input)?,
+            pos: 0,
+        };
+        let condition = parser.parse_or()?;
+        if let Some(rest) = parser.peek() {
+            return Err(format!("Unerwartetes '{}' in Bedingung '{}'", rest, input));
+        }
+        Ok(condition)
+    }
+
+    pub fn evaluate(&self, ctx: &ConditionContext) -> Result<bool, String> {
+        match self {
+            Condition::Not(inner) => Ok(!inner.evaluate(ctx)?),
+            Condition::And(parts) => {
+                for part in parts {
+                    if !part.evaluate(ctx)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            Condition::Or(parts) => {
+                for part in parts {
+                    if part.evaluate(ctx)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            Condition::Flag(key) => {
+                if let Some(name) = key.strip_prefix("condition:") {
+                    return Ok(ctx.active_conditions.iter().any(|c| c.eq_ignore_ascii_case(name)));
+                }
+                match key.as_str() {
+                    "shield" => Ok(ctx.has_shield),
+                    "wearing_armor" => Ok(ctx.wearing_armor != "none"),
+                    _ => Err(format!("Unbekannter Schalter '{}'", key)),
+                }
+            }
+            Condition::Compare { key, op, value } => match key.as_str() {
+                "wearing_armor" => {
+                    let matches = if value == "any" {
+                        ctx.wearing_armor != "none"
+                    } else {
+                        ctx.wearing_armor == *value
+                    };
+                    compare_text(matches, *op, key)
+                }
+                "wielding" => compare_text(ctx.wielding == *value, *op, key),
+                "shield" => compare_text(ctx.has_shield == (value == "true"), *op, key),
+                "hp" => {
+                    let wanted = match value.as_str() {
+                        "half" => ctx.hp_max / 2,
+                        "max" => ctx.hp_max,
+                        v => v.parse().map_err(|_| format!("Ungültiger HP-Wert '{}'", v))?,
+                    };
+                    Ok(compare_numbers(ctx.hp_current, *op, wanted))
+                }
+                "level" => {
+                    let wanted: i32 = value.parse().map_err(|_| format!("Ungültige Stufe '{}'", value))?;
+                    Ok(compare_numbers(ctx.level, *op, wanted))
+                }
+                _ => Err(format!("Unbekannter Schlüssel '{}'", key)),
+            },
+        }
+    }
+}
+
+fn compare_text(matches: bool, op: CompareOp, key: &str) -> Result<bool, String> {
+    match op {
+        CompareOp::Eq => Ok(matches),
+        CompareOp::Ne => Ok(!matches),
+        _ => Err(format!("'{}' unterstützt nur == und !=", key)),
+    }
+}
+
+fn compare_numbers(left: i32, op: CompareOp, right: i32) -> bool {
+    match op {
+        CompareOp::Eq => left == right,
+        CompareOp::Ne => left != right,
+        CompareOp::Lt => left < right,
+        CompareOp::Le => left <= right,
+        CompareOp::Gt => left > right,
+        CompareOp::Ge => left >= right,
+    }
+}
+
+/// Prüft, ob ein Modifikator im aktuellen Zustand gilt.
+/// Modifikatoren ohne Bedingung gelten immer, fehlerhafte Bedingungen nie.
+pub fn is_modifier_active(modifier: &Modifier, ctx: &ConditionContext) -> bool {
+    match modifier.condition.as_deref().map(str::trim) {
+        None | Some("") => true,
+        Some(expr) => Condition::parse(expr)
+            .and_then(|c| c.evaluate(ctx))
+            .unwrap_or(false),
+    }
+}
+
+/// Liefert alle Modifikatoren, deren Bedingung im aktuellen Zustand erfüllt ist.
+pub fn active_modifiers(modifiers: &[Modifier], ctx: &ConditionContext) -> Vec<Modifier> {
+    modifiers
+        .iter()
+        .filter(|m| is_modifier_active(m, ctx))
+        .cloned()
+        .collect()
 }
 
 #[cfg(test)]
@@ -35,6 +429,17 @@ mod tests {
     use super::*;
     use crate::core::types::ModifierType;
 
+    fn modifier(id: &str, source: &str, modifier_type: ModifierType, value: i32) -> Modifier {
+        Modifier {
+            id: id.into(),
+            source: source.into(),
+            target: "ac".into(),
+            modifier_type,
+            value,
+            condition: None,
+        }
+    }
+
     #[test]
     fn test_apply_modifiers() {
         let modifiers = vec![
@@ -57,18 +462,108 @@ mod tests {
         ];
 
         // Base 10, Override 19, Add 2 = 21
-        assert_eq!(apply_modifiers(10, &modifiers), 21);
+        let breakdown = apply_modifiers(&[("Basis", 10)], &modifiers);
+        assert_eq!(breakdown.total, 21);
+
+        // Basis wurde vom Override verdrängt
+        assert!(!breakdown.contributions[0].applied);
+        assert_eq!(breakdown.contributions[1].kind, ContributionKind::Override);
+        assert!(breakdown.contributions[1].applied);
+        assert_eq!(breakdown.contributions[2].kind, ContributionKind::Add);
     }
-}
 
+    #[test]
+    fn test_breakdown_describe_ac() {
+        let breakdown = ModifierBreakdown::from_parts(&[("Basis", 10), ("DEX", 3), ("Schild", 2)])
+            .apply(&[modifier("ring", "Ring of Protection", ModifierType::Add, 1)]);
 
+        assert_eq!(breakdown.total, 16);
+        assert_eq!(breakdown.describe(), "10 Basis + 3 DEX + 2 Schild + 1 Ring of Protection");
+    }
 
+    #[test]
+    fn test_breakdown_only_highest_override_applies() {
+        let breakdown = apply_modifiers(
+            &[("Basis", 12)],
+            &[
+                modifier("a", "Amulett", ModifierType::Override, 15),
+                modifier("b", "Zauber", ModifierType::Override, 17),
+                modifier("c", "Fluch", ModifierType::Add, -1),
+                modifier("d", "Doppelt", ModifierType::Multiply, 2),
+            ],
+        );
 
+        assert_eq!(breakdown.total, 32);
+        let applied: Vec<&str> = breakdown
+            .contributions
+            .iter()
+            .filter(|c| c.applied)
+            .map(|c| c.source.as_str())
+            .collect();
+        assert_eq!(applied, vec!["Zauber", "Fluch", "Doppelt"]);
+        assert_eq!(breakdown.describe(), "= 17 Zauber - 1 Fluch × 2 Doppelt");
+    }
 
+    fn ctx() -> ConditionContext {
+        ConditionContext {
+            hp_current: 20,
+            hp_max: 44,
+            level: 5,
+            ..ConditionContext::default()
+        }
+    }
 
+    #[test]
+    fn test_condition_parse() {
+        assert_eq!(
+            Condition::parse("wearing_armor == none").unwrap(),
+            Condition::Compare { key: "wearing_armor".into(), op: CompareOp::Eq, value: "none".into() }
+        );
+        assert_eq!(Condition::parse("condition:raging").unwrap(), Condition::Flag("condition:raging".into()));
+        assert!(matches!(Condition::parse("hp<half && !shield").unwrap(), Condition::And(_)));
+        assert!(Condition::parse("hp <").is_err());
+        assert!(Condition::parse("(level > 2").is_err());
+        assert!(Condition::parse("level = 2").is_err());
+    }
 
+    #[test]
+    fn test_condition_evaluate() {
+        let mut ctx = ctx();
+        let eval = |expr: &str, ctx: &ConditionContext| Condition::parse(expr).unwrap().evaluate(ctx).unwrap();
 
+        assert!(eval("wearing_armor == none", &ctx));
+        assert!(!eval("wearing_armor != none", &ctx));
+        assert!(eval("hp < half", &ctx));
+        assert!(!eval("hp >= max", &ctx));
+        assert!(eval("level >= 5 and not shield", &ctx));
+        assert!(!eval("condition:raging", &ctx));
 
+        ctx.wearing_armor = "heavy".into();
+        ctx.wielding = "two_handed".into();
+        ctx.active_conditions = vec!["raging".into()];
+        assert!(eval("wearing_armor == any", &ctx));
+        assert!(eval("wielding == two_handed", &ctx));
+        assert!(eval("condition:Raging || shield", &ctx));
 
+        assert!(Condition::parse("wielding > 2").unwrap().evaluate(&ctx).is_err());
+        assert!(Condition::parse("mood == happy").unwrap().evaluate(&ctx).is_err());
+    }
 
+    #[test]
+    fn test_active_modifiers_respects_condition() {
+        let mut defense = modifier("def", "Kampfstil Verteidigung", ModifierType::Add, 1);
+        defense.condition = Some("wearing_armor != none".into());
+        let mut broken = modifier("broken", "Kaputt", ModifierType::Add, 5);
+        broken.condition = Some("wearing_armor ==".into());
+        let always = modifier("ring", "Ring of Protection", ModifierType::Add, 1);
+        let mods = vec![defense, broken, always];
+
+        let mut ctx = ctx();
+        assert_eq!(active_modifiers(&mods, &ctx).len(), 1);
 
+        ctx.wearing_armor = "medium".into();
+        let active = active_modifiers(&mods, &ctx);
+        assert_eq!(active.len(), 2);
+        assert_eq!(apply_modifiers(&[("Basis", 16)], &active).total, 18);
+    }
+}
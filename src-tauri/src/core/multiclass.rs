@@ -0,0 +1,477 @@
+//! Mehrklassen-Regeln (PHB 2024).
+//!
+//! `CharacterMeta.classes` hält die Stufen je Klasse; `class_id`/`subclass_id` spiegeln die
+//! Startklasse und `level` ist die Gesamtstufe (daraus folgt auch der Übungsbonus).
+//! Eine neue Klasse erfordert 13 im Hauptattribut aller bisherigen und der neuen Klasse und
+//! bringt nur eingeschränkte Übung mit. Zauberplätze mehrerer Zauberklassen kommen aus der
+//! gemeinsamen Tabelle, Paktmagie bleibt getrennt. Trefferwürfel werden nach Größe gebündelt.
+
+use std::cmp::Reverse;
+use serde::Serialize;
+use crate::types::character::{Character, CharacterMeta, ClassLevel, HealthPool, HitDicePool};
+
+pub const MULTICLASS_MINIMUM: i32 = 13;
+
+/// Klassen-ID ohne Umlaute, z.B. "kämpfer" -> "kaempfer".
+pub fn class_key(class_id: &str) -> String {
+    class_id
+        .trim()
+        .to_lowercase()
+        .replace('ä', "ae")
+        .replace('ö', "oe")
+        .replace('ü', "ue")
+}
+
+pub fn same_class(a: &str, b: &str) -> bool {
+    class_key(a) == class_key(b)
+}
+
+pub fn total_level(classes: &[ClassLevel]) -> i32 {
+    classes.iter().map(|c| c.level).sum()
+}
+
+pub fn class_level(meta: &CharacterMeta, class_id: &str) -> i32 {
+    meta.classes.iter().find(|c| same_class(&c.class_id, class_id)).map(|c| c.level).unwrap_or(0)
+}
+
+/// Überträgt `classes` auf `class_id`, `subclass_id` und die Gesamtstufe.
+pub fn sync_meta_from_classes(meta: &mut CharacterMeta) {
+    if let Some(primary) = meta.classes.first() {
+        meta.class_id = Some(primary.class_id.clone());
+        meta.subclass_id = primary.subclass_id.clone();
+        meta.level = total_level(&meta.classes);
+    }
+}
+
+/// Bringt `classes` und die Einzelfelder in Einklang.
+///
+/// Alte Einklassen-Daten ohne `classes` bekommen einen Eintrag. Bei genau einer Klasse haben
+/// die Einzelfelder Vorrang (sie werden im Formular bearbeitet), bei mehreren `classes`.
+pub fn normalize_classes(meta: &mut CharacterMeta) {
+    let class_id = meta.class_id.clone().filter(|c| !c.trim().is_empty());
+    match meta.classes.len() {
+        0 => {
+            if let Some(class_id) = class_id {
+                meta.classes.push(ClassLevel {
+                    class_id,
+                    subclass_id: meta.subclass_id.clone(),
+                    level: meta.level.max(1),
+                });
+            }
+        }
+        1 => {
+            let entry = &mut meta.classes[0];
+            match class_id {
+                Some(class_id) => {
+                    entry.class_id = class_id;
+                    entry.subclass_id = meta.subclass_id.clone();
+                    entry.level = meta.level.max(1);
+                }
+                None => sync_meta_from_classes(meta),
+            }
+        }
+        _ => sync_meta_from_classes(meta),
+    }
+}
+
+/// Klassenstufen nach einem Aufstieg in `class_id` (neue Klassen werden angehängt).
+pub fn classes_after_level_up(meta: &CharacterMeta, class_id: &str) -> Vec<ClassLevel> {
+    let mut classes = meta.classes.clone();
+    match classes.iter_mut().find(|c| same_class(&c.class_id, class_id)) {
+        Some(entry) => entry.level += 1,
+        None => classes.push(ClassLevel { class_id: class_id.to_string(), subclass_id: None, level: 1 }),
+    }
+    classes
+}
+
+/// Mehrklassen-Voraussetzungen: eine der Alternativen muss vollständig erfüllt sein.
+pub fn multiclass_prerequisites(class_id: &str) -> &'static [&'static [&'static str]] {
+    match class_key(class_id).as_str() {
+        "barbar" => &[&["str"]],
+        "barde" | "zauberer" | "hexenmeister" => &[&["cha"]],
+        "kleriker" | "druide" => &[&["wis"]],
+        "kaempfer" => &[&["str"], &["dex"]],
+        "moench" | "waldlaeufer" => &[&["dex", "wis"]],
+        "paladin" => &[&["str", "cha"]],
+        "schurke" => &[&["dex"]],
+        "magier" => &[&["int"]],
+        _ => &[],
+    }
+}
+
+fn prerequisites_met(character: &Character, class_id: &str) -> bool {
+    let alternatives = multiclass_prerequisites(class_id);
+    alternatives.is_empty()
+        || alternatives.iter().any(|all| {
+            all.iter().all(|a| character.attributes.get(a).unwrap_or(0) >= MULTICLASS_MINIMUM)
+        })
+}
+
+fn describe_prerequisites(class_id: &str) -> String {
+    multiclass_prerequisites(class_id)
+        .iter()
+        .map(|all| {
+            all.iter()
+                .map(|a| format!("{} {}", a.to_uppercase(), MULTICLASS_MINIMUM))
+                .collect::<Vec<_>>()
+                .join(" und ")
+        })
+        .collect::<Vec<_>>()
+        .join(" oder ")
+}
+
+/// Nicht erfüllte Voraussetzungen für eine weitere Klasse (bisherige und neue Klasse).
+pub fn unmet_multiclass_prerequisites(character: &Character, new_class_id: &str) -> Vec<String> {
+    let mut class_ids: Vec<&str> = character.meta.classes.iter().map(|c| c.class_id.as_str()).collect();
+    if class_ids.is_empty() {
+        class_ids.extend(character.meta.class_id.as_deref());
+    }
+    class_ids.push(new_class_id);
+    class_ids
+        .into_iter()
+        .filter(|c| !prerequisites_met(character, c))
+        .map(|c| format!("{} ({})", c, describe_prerequisites(c)))
+        .collect()
+}
+
+/// Übung, die eine hinzugenommene Klasse gewährt.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MulticlassProficiencies {
+    pub armor: Vec<String>,
+    pub weapons: Vec<String>,
+    pub tools: Vec<String>,
+    /// Anzahl frei wählbarer Fertigkeiten
+    pub skill_choices: usize,
+    /// Erlaubte Fertigkeiten (leer = beliebig)
+    pub skill_options: Vec<String>,
+    /// Ein Musikinstrument nach Wahl (Barde)
+    pub instrument_choice: bool,
+}
+
+pub fn multiclass_proficiencies(class_id: &str) -> MulticlassProficiencies {
+    let list = |items: &[&str]| items.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let mut p = MulticlassProficiencies::default();
+    match class_key(class_id).as_str() {
+        "barbar" => {
+            p.armor = list(&["shield"]);
+            p.weapons = list(&["martial_weapons"]);
+        }
+        "barde" => {
+            p.armor = list(&["light"]);
+            p.skill_choices = 1;
+            p.instrument_choice = true;
+        }
+        "kleriker" => p.armor = list(&["light", "medium", "shield"]),
+        "druide" => p.armor = list(&["light", "shield"]),
+        "kaempfer" | "paladin" => {
+            p.armor = list(&["light", "medium", "shield"]);
+            p.weapons = list(&["martial_weapons"]);
+        }
+        "waldlaeufer" => {
+            p.armor = list(&["light", "medium", "shield"]);
+            p.weapons = list(&["martial_weapons"]);
+            p.skill_choices = 1;
+            p.skill_options = list(&[
+                "athletik", "heimlichkeit", "mit-tieren-umgehen", "motiv-erkennen",
+                "nachforschungen", "naturkunde", "ueberlebenskunst", "wahrnehmung",
+            ]);
+        }
+        "schurke" => {
+            p.armor = list(&["light"]);
+            p.tools = list(&["diebeswerkzeug"]);
+            p.skill_choices = 1;
+            p.skill_options = list(&[
+                "akrobatik", "athletik", "einschuechtern", "fingerfertigkeit", "heimlichkeit",
+                "motiv-erkennen", "nachforschungen", "taeuschen", "ueberzeugen", "wahrnehmung",
+            ]);
+        }
+        "hexenmeister" => p.armor = list(&["light"]),
+        _ => {}
+    }
+    p
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v.eq_ignore_ascii_case(value)) {
+        list.push(value.to_string());
+    }
+}
+
+/// Trägt die Mehrklassen-Übung ein. `skills` und `instrument` sind die Auswahlen des Spielers.
+pub fn apply_multiclass_proficiencies(
+    character: &mut Character,
+    class_id: &str,
+    skills: &[String],
+    instrument: Option<&str>,
+) -> Result<MulticlassProficiencies, String> {
+    let granted = multiclass_proficiencies(class_id);
+    if skills.len() != granted.skill_choices {
+        return Err(format!("{} Fertigkeit(en) wählen (gewählt: {})", granted.skill_choices, skills.len()));
+    }
+    if let Some(skill) = skills.iter().find(|s| {
+        !granted.skill_options.is_empty() && !granted.skill_options.iter().any(|o| o.eq_ignore_ascii_case(s.trim()))
+    }) {
+        return Err(format!("Fertigkeit nicht wählbar: {}", skill));
+    }
+    let instrument = instrument.map(str::trim).filter(|i| !i.is_empty());
+    if granted.instrument_choice && instrument.is_none() {
+        return Err("Ein Musikinstrument wählen".to_string());
+    }
+
+    let proficiencies = &mut character.proficiencies;
+    for armor in &granted.armor {
+        push_unique(&mut proficiencies.armor, armor);
+    }
+    for weapon in &granted.weapons {
+        push_unique(&mut proficiencies.weapons, weapon);
+    }
+    for tool in granted.tools.iter().map(String::as_str).chain(instrument.filter(|_| granted.instrument_choice)) {
+        push_unique(&mut proficiencies.tools, tool);
+    }
+    for skill in skills {
+        push_unique(&mut proficiencies.skills, skill.trim());
+    }
+    Ok(granted)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CasterType {
+    Full,
+    Half,
+    /// Mystischer Ritter, Arkaner Betrüger
+    Third,
+    /// Paktmagie (Hexenmeister), zählt nicht zur gemeinsamen Tabelle
+    Pact,
+    None,
+}
+
+pub fn caster_type(class_id: &str, subclass_id: Option<&str>) -> CasterType {
+    let subclass = subclass_id.map(class_key).unwrap_or_default();
+    match class_key(class_id).as_str() {
+        "barde" | "kleriker" | "druide" | "zauberer" | "magier" => CasterType::Full,
+        "paladin" | "waldlaeufer" => CasterType::Half,
+        "kaempfer" if subclass.contains("mystischer_ritter") => CasterType::Third,
+        "schurke" if subclass.contains("arkaner_betrueger") => CasterType::Third,
+        "hexenmeister" => CasterType::Pact,
+        _ => CasterType::None,
+    }
+}
+
+/// Zauberwirkerstufe für die gemeinsame Tabelle: volle Stufen, halbe (aufgerundet),
+/// Drittel (abgerundet).
+pub fn caster_level(classes: &[ClassLevel]) -> i32 {
+    classes
+        .iter()
+        .map(|c| match caster_type(&c.class_id, c.subclass_id.as_deref()) {
+            CasterType::Full => c.level,
+            CasterType::Half => (c.level + 1) / 2,
+            CasterType::Third => c.level / 3,
+            CasterType::Pact | CasterType::None => 0,
+        })
+        .sum()
+}
+
+/// Zauberplätze nach Zauberwirkerstufe (Mehrklassen-Tabelle, PHB 2024).
+const MULTICLASS_SPELL_SLOTS: [[i32; 9]; 20] = [
+    [2, 0, 0, 0, 0, 0, 0, 0, 0],
+    [3, 0, 0, 0, 0, 0, 0, 0, 0],
+    [4, 2, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 0, 0, 0, 0, 0, 0, 0],
+    [4, 3, 2, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 0, 0, 0, 0, 0, 0],
+    [4, 3, 3, 1, 0, 0, 0, 0, 0],
+    [4, 3, 3, 2, 0, 0, 0, 0, 0],
+    [4, 3, 3, 3, 1, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 0, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 0, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 0, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 0],
+    [4, 3, 3, 3, 2, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 1, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 1, 1, 1],
+    [4, 3, 3, 3, 3, 2, 2, 1, 1],
+];
+
+pub fn spell_slots_for_caster_level(caster_level: i32) -> [i32; 9] {
+    match caster_level {
+        1..=20 => MULTICLASS_SPELL_SLOTS[caster_level as usize - 1],
+        l if l > 20 => MULTICLASS_SPELL_SLOTS[19],
+        _ => [0; 9],
+    }
+}
+
+/// Gemeinsame Zauberplätze, wenn mindestens zwei Klassen Zauberplätze (ohne Paktmagie) haben.
+/// Mit nur einer Zauberklasse gilt deren eigene Tabelle (`None`).
+pub fn multiclass_spell_slots(classes: &[ClassLevel]) -> Option<[i32; 9]> {
+    let casters = classes
+        .iter()
+        .filter(|c| {
+            matches!(
+                caster_type(&c.class_id, c.subclass_id.as_deref()),
+                CasterType::Full | CasterType::Half | CasterType::Third
+            )
+        })
+        .count();
+    (casters >= 2).then(|| spell_slots_for_caster_level(caster_level(classes)))
+}
+
+fn sync_hit_dice_totals(health: &mut HealthPool) {
+    health.hit_dice_max = health.hit_dice.iter().map(|p| p.max).sum();
+    health.hit_dice_used = health.hit_dice.iter().map(|p| p.used).sum();
+}
+
+/// Baut die Trefferwürfel-Pools aus (Würfel, Klassenstufe) neu auf.
+/// Verbrauchte Würfel bleiben je Größe erhalten; Altdaten ohne Pools verbrauchen die größten zuerst.
+pub fn rebuild_hit_dice(health: &mut HealthPool, dice: &[(u32, i32)]) {
+    let mut pools: Vec<HitDicePool> = Vec::new();
+    for &(die, level) in dice.iter().filter(|(die, level)| *die > 0 && *level > 0) {
+        match pools.iter_mut().find(|p| p.die == die) {
+            Some(pool) => pool.max += level,
+            None => pools.push(HitDicePool { die, max: level, used: 0 }),
+        }
+    }
+    if pools.is_empty() {
+        return;
+    }
+    pools.sort_by_key(|p| Reverse(p.die));
+
+    if health.hit_dice.is_empty() {
+        let mut used = health.hit_dice_used.max(0);
+        for pool in &mut pools {
+            pool.used = used.min(pool.max);
+            used -= pool.used;
+        }
+    } else {
+        for pool in &mut pools {
+            let used = health.hit_dice.iter().find(|p| p.die == pool.die).map(|p| p.used).unwrap_or(0);
+            pool.used = used.clamp(0, pool.max);
+        }
+    }
+    health.hit_dice = pools;
+    sync_hit_dice_totals(health);
+}
+
+/// Ein zusätzlicher Trefferwürfel nach einem Stufenaufstieg.
+pub fn add_hit_die(health: &mut HealthPool, die: u32) {
+    if health.hit_dice.is_empty() {
+        health.hit_dice_max += 1;
+        return;
+    }
+    match health.hit_dice.iter_mut().find(|p| p.die == die) {
+        Some(pool) => pool.max += 1,
+        None => {
+            health.hit_dice.push(HitDicePool { die, max: 1, used: 0 });
+            health.hit_dice.sort_by_key(|p| Reverse(p.die));
+        }
+    }
+    sync_hit_dice_totals(health);
+}
+
+/// Gibt `count` Trefferwürfel aus, größte zuerst, und liefert ihre Größen.
+pub fn spend_hit_dice(health: &mut HealthPool, count: i32) -> Vec<u32> {
+    let mut spent = Vec::new();
+    for pool in &mut health.hit_dice {
+        while spent.len() < count.max(0) as usize && pool.used < pool.max {
+            pool.used += 1;
+            spent.push(pool.die);
+        }
+    }
+    sync_hit_dice_totals(health);
+    spent
+}
+
+/// Lange Rast: alle Trefferwürfel zurück.
+pub fn restore_hit_dice(health: &mut HealthPool) {
+    for pool in &mut health.hit_dice {
+        pool.used = 0;
+    }
+    health.hit_dice_used = 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+
+    fn class(class_id: &str, subclass_id: Option<&str>, level: i32) -> ClassLevel {
+        ClassLevel { class_id: class_id.into(), subclass_id: subclass_id.map(Into::into), level }
+    }
+
+    #[test]
+    fn test_normalize_migrates_single_class() {
+        let mut character = test_character();
+        assert!(character.meta.classes.is_empty());
+        normalize_classes(&mut character.meta);
+        assert_eq!(character.meta.classes, vec![class("kaempfer", None, 5)]);
+
+        // Einklassen: Formularwerte gewinnen
+        character.meta.level = 6;
+        normalize_classes(&mut character.meta);
+        assert_eq!(character.meta.classes[0].level, 6);
+
+        // Mehrklassen: Gesamtstufe aus den Klassen
+        character.meta.classes.push(class("magier", None, 2));
+        normalize_classes(&mut character.meta);
+        assert_eq!((character.meta.level, character.meta.class_id.as_deref()), (8, Some("kaempfer")));
+    }
+
+    #[test]
+    fn test_prerequisites_and_proficiencies() {
+        let mut character = test_character();
+        normalize_classes(&mut character.meta);
+        // STR 16, INT 10, WIS 12, CHA 8
+        assert!(unmet_multiclass_prerequisites(&character, "barbar").is_empty());
+        assert_eq!(unmet_multiclass_prerequisites(&character, "magier").len(), 1);
+        assert!(unmet_multiclass_prerequisites(&character, "paladin")[0].contains("STR 13 und CHA 13"));
+
+        character.attributes.str = 10;
+        character.attributes.dex = 13;
+        character.attributes.wis = 13;
+        assert!(unmet_multiclass_prerequisites(&character, "waldläufer").is_empty());
+
+        assert!(apply_multiclass_proficiencies(&mut character, "schurke", &[], None).is_err());
+        assert!(apply_multiclass_proficiencies(&mut character, "schurke", &["religion".into()], None).is_err());
+        apply_multiclass_proficiencies(&mut character, "schurke", &["heimlichkeit".into()], None).unwrap();
+        assert!(character.proficiencies.tools.contains(&"diebeswerkzeug".to_string()));
+        assert!(character.proficiencies.skills.contains(&"heimlichkeit".to_string()));
+        assert_eq!(character.proficiencies.armor, vec!["light".to_string()]);
+    }
+
+    #[test]
+    fn test_multiclass_spell_slots() {
+        let classes = [class("paladin", None, 3), class("zauberer", None, 3)];
+        assert_eq!(caster_level(&classes), 5);
+        assert_eq!(multiclass_spell_slots(&classes).unwrap()[..3], [4, 3, 2]);
+
+        let classes = [class("kämpfer", Some("kämpfer_mystischer_ritter"), 7), class("magier", None, 1)];
+        assert_eq!(caster_level(&classes), 3);
+
+        // Nur eine Zauberklasse bzw. Paktmagie: eigene Tabelle
+        assert!(multiclass_spell_slots(&[class("magier", None, 5), class("kaempfer", None, 2)]).is_none());
+        assert!(multiclass_spell_slots(&[class("magier", None, 5), class("hexenmeister", None, 2)]).is_none());
+    }
+
+    #[test]
+    fn test_pooled_hit_dice() {
+        let mut health = HealthPool { hit_dice_max: 6, hit_dice_used: 3, ..HealthPool::default() };
+        rebuild_hit_dice(&mut health, &[(10, 4), (6, 2)]);
+        assert_eq!(health.hit_dice, vec![
+            HitDicePool { die: 10, max: 4, used: 3 },
+            HitDicePool { die: 6, max: 2, used: 0 },
+        ]);
+
+        assert_eq!(spend_hit_dice(&mut health, 2), vec![10, 6]);
+        assert_eq!((health.hit_dice_max, health.hit_dice_used), (6, 5));
+
+        add_hit_die(&mut health, 8);
+        assert_eq!(health.hit_dice.iter().map(|p| p.die).collect::<Vec<_>>(), vec![10, 8, 6]);
+        assert_eq!(health.hit_dice_max, 7);
+
+        restore_hit_dice(&mut health);
+        assert_eq!(health.hit_dice_used, 0);
+    }
+}
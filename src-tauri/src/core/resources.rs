@@ -0,0 +1,279 @@
+//! Begrenzte Merkmals-Ressourcen (Kampfrausch, Göttliche Macht, Fokuspunkte, …).
+//!
+//! Die maximalen Anwendungen stehen als Ausdruck in `uses_per_rest`, z.B.
+//! `"PB"`, `"CHA mod (min 1)"`, `"5 * level"`, `"3"` oder der Name einer Spalte aus
+//! `core_progression_tables.class_specific_data` (`"kampfrausch"`, `"progression:fokuspunkte"`).
+//! Verbraucht wird pro Charakter in `character_feature_resources` gezählt.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UsesFactor {
+    Number(i32),
+    ProficiencyBonus,
+    Level,
+    /// Attributsmodifikator ("str" … "cha")
+    AbilityModifier(String),
+    /// Spalte aus `class_specific_data` der aktuellen Stufe
+    Progression(String),
+}
+
+/// Geparster `uses_per_rest`-Ausdruck: Summe von Produkten, optional mit Untergrenze.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsesExpression {
+    pub terms: Vec<Vec<UsesFactor>>,
+    pub min: Option<i32>,
+}
+
+/// Werte, gegen die ein Ausdruck ausgewertet wird.
+pub trait UsesContext {
+    fn proficiency_bonus(&self) -> i32;
+    fn level(&self) -> i32;
+    fn ability_modifier(&self, ability: &str) -> i32;
+    fn progression_value(&self, key: &str) -> Option<i32>;
+}
+
+fn ability_key(word: &str) -> Option<&'static str> {
+    match word {
+        "str" | "stä" | "stae" | "stärke" => Some("str"),
+        "dex" | "ges" | "geschicklichkeit" => Some("dex"),
+        "con" | "kon" | "konstitution" => Some("con"),
+        "int" | "intelligenz" => Some("int"),
+        "wis" | "wei" | "weisheit" => Some("wis"),
+        "cha" | "charisma" => Some("cha"),
+        _ => None,
+    }
+}
+
+fn parse_factor(text: &str, input: &str) -> Result<UsesFactor, String> {
+    let text = text.trim();
+    if let Ok(n) = text.parse::<i32>() {
+        return Ok(UsesFactor::Number(n));
+    }
+    if let Some(key) = text.strip_prefix("progression:") {
+        return Ok(UsesFactor::Progression(key.trim().to_string()));
+    }
+    match text {
+        "pb" | "üb" | "prof" | "übungsbonus" | "proficiency" => return Ok(UsesFactor::ProficiencyBonus),
+        "level" | "stufe" => return Ok(UsesFactor::Level),
+        _ => {}
+    }
+
+    let words: Vec<&str> = text.split(|c: char| c.is_whitespace() || c == '-' || c == '_').filter(|w| !w.is_empty()).collect();
+    if let [ability, "mod" | "modifier" | "modifikator"] | ["mod" | "modifier" | "modifikator", ability] = words.as_slice() {
+        return ability_key(ability)
+            .map(|a| UsesFactor::AbilityModifier(a.to_string()))
+            .ok_or_else(|| format!("Unbekanntes Attribut '{}' in '{}'", ability, input));
+    }
+
+    if !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Ok(UsesFactor::Progression(text.to_string()));
+    }
+    Err(format!("Ungültiger Anwendungs-Ausdruck '{}'", input))
+}
+
+impl UsesExpression {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut text = input.trim().to_lowercase();
+        if text.is_empty() {
+            return Err("Leerer Anwendungs-Ausdruck".to_string());
+        }
+
+        // "(min 1)" / "(mindestens 1)" am Ende
+        let mut min = None;
+        if let Some(open) = text.rfind('(') {
+            let inner = text[open + 1..].trim_end_matches(')').trim().to_string();
+            let value = inner
+                .strip_prefix("mindestens")
+                .or_else(|| inner.strip_prefix("min."))
+                .or_else(|| inner.strip_prefix("min"))
+                .map(str::trim)
+                .ok_or_else(|| format!("Unbekannter Zusatz '({})' in '{}'", inner, input))?;
+            min = Some(value.parse::<i32>().map_err(|_| format!("Ungültige Untergrenze in '{}'", input))?);
+            text = text[..open].trim().to_string();
+        }
+
+        let mut terms = Vec::new();
+        for term in text.split('+') {
+            let factors = term
+                .split(['*', '×'])
+                .flat_map(|f| f.split(" x "))
+                .map(|f| parse_factor(f, input))
+                .collect::<Result<Vec<_>, _>>()?;
+            terms.push(factors);
+        }
+        Ok(Self { terms, min })
+    }
+
+    pub fn evaluate(&self, context: &dyn UsesContext) -> Result<i32, String> {
+        let mut total = 0;
+        for term in &self.terms {
+            let mut product = 1;
+            for factor in term {
+                product *= match factor {
+                    UsesFactor::Number(n) => *n,
+                    UsesFactor::ProficiencyBonus => context.proficiency_bonus(),
+                    UsesFactor::Level => context.level(),
+                    UsesFactor::AbilityModifier(ability) => context.ability_modifier(ability),
+                    UsesFactor::Progression(key) => context
+                        .progression_value(key)
+                        .ok_or_else(|| format!("Spalte '{}' fehlt in der Stufentabelle", key))?,
+                };
+            }
+            total += product;
+        }
+        Ok(self.min.map_or(total, |min| total.max(min)).max(0))
+    }
+}
+
+/// Ausdruck und Rast-Typ für Merkmale, deren `uses_per_rest` im Kompendium nicht gepflegt ist.
+pub fn default_uses(feature_id: &str) -> Option<(&'static str, &'static str)> {
+    match feature_id {
+        "barbar_kampfrausch_l1" => Some(("kampfrausch", "short")),
+        "barde_bardische_inspiration_l1" => Some(("CHA mod (min 1)", "long")),
+        "druide_tiergestalt_l2" => Some(("tiergestalt", "short")),
+        "kleriker_goettliche_macht_fokussieren_l2" => Some(("goettliche_macht_fokussieren", "short")),
+        "kämpfer_durchschnaufen_l1" => Some(("durchschnaufen", "short")),
+        "magier_arkane_erholung_l1" => Some(("1", "long")),
+        "mönch_moenchsfokus_l2" => Some(("fokuspunkte", "short")),
+        "paladin_handauflegen_l1" => Some(("5 * level", "long")),
+        "paladin_goettliche_macht_fokussieren_l3" => Some(("goettliche_macht", "short")),
+        "zauberer_intuitive_zauberei_l1" => Some(("2", "long")),
+        "zauberer_quelle_der_magie_l2" => Some(("zaubereipunkte", "long")),
+        _ => None,
+    }
+}
+
+/// Ressource eines Charakters mit aufgelöstem Maximum.
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureResource {
+    pub feature_id: String,
+    pub name: String,
+    pub uses_expression: String,
+    pub uses_max: i32,
+    pub uses_spent: i32,
+    pub uses_remaining: i32,
+    /// "short" oder "long"; `None`, wenn sich die Ressource nicht durch Rast erholt
+    pub rest_type: Option<String>,
+    /// Fehler beim Auswerten des Ausdrucks (Maximum ist dann 0)
+    pub error: Option<String>,
+}
+
+impl FeatureResource {
+    pub fn new(
+        feature_id: String,
+        name: String,
+        uses_expression: String,
+        rest_type: Option<String>,
+        uses_spent: i32,
+        context: &dyn UsesContext,
+    ) -> Self {
+        let (uses_max, error) = match UsesExpression::parse(&uses_expression).and_then(|e| e.evaluate(context)) {
+            Ok(max) => (max, None),
+            Err(e) => (0, Some(e)),
+        };
+        let uses_spent = uses_spent.clamp(0, uses_max);
+        Self {
+            feature_id,
+            name,
+            uses_expression,
+            uses_max,
+            uses_spent,
+            uses_remaining: uses_max - uses_spent,
+            rest_type,
+            error,
+        }
+    }
+
+    /// Verbraucht `amount` Anwendungen.
+    pub fn spend(&mut self, amount: i32) -> Result<(), String> {
+        if amount <= 0 {
+            return Err(format!("Ungültige Anzahl: {}", amount));
+        }
+        if amount > self.uses_remaining {
+            return Err(format!(
+                "{}: nur noch {} von {} Anwendungen übrig",
+                self.name, self.uses_remaining, self.uses_max
+            ));
+        }
+        self.uses_spent += amount;
+        self.uses_remaining -= amount;
+        Ok(())
+    }
+
+    /// Stellt `amount` Anwendungen wieder her (`None` = alle).
+    pub fn restore(&mut self, amount: Option<i32>) -> Result<(), String> {
+        let amount = amount.unwrap_or(self.uses_spent);
+        if amount < 0 {
+            return Err(format!("Ungültige Anzahl: {}", amount));
+        }
+        self.uses_spent = (self.uses_spent - amount).max(0);
+        self.uses_remaining = self.uses_max - self.uses_spent;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct Ctx(HashMap<&'static str, i32>);
+
+    impl UsesContext for Ctx {
+        fn proficiency_bonus(&self) -> i32 { 3 }
+        fn level(&self) -> i32 { 5 }
+        fn ability_modifier(&self, ability: &str) -> i32 {
+            match ability { "cha" => -1, "wis" => 3, _ => 0 }
+        }
+        fn progression_value(&self, key: &str) -> Option<i32> {
+            self.0.get(key).copied()
+        }
+    }
+
+    fn ctx() -> Ctx {
+        Ctx(HashMap::from([("kampfrausch", 3), ("fokuspunkte", 5)]))
+    }
+
+    fn eval(input: &str) -> Result<i32, String> {
+        UsesExpression::parse(input)?.evaluate(&ctx())
+    }
+
+    #[test]
+    fn test_uses_expression() {
+        assert_eq!(eval("PB"), Ok(3));
+        assert_eq!(eval("ÜB"), Ok(3));
+        assert_eq!(eval("4"), Ok(4));
+        assert_eq!(eval("CHA mod (min 1)"), Ok(1));
+        assert_eq!(eval("CHA mod"), Ok(0));
+        assert_eq!(eval("WEI-Mod (mindestens 1)"), Ok(3));
+        assert_eq!(eval("5 * level"), Ok(25));
+        assert_eq!(eval("level x 5"), Ok(25));
+        assert_eq!(eval("1 + WIS mod"), Ok(4));
+        assert_eq!(eval("kampfrausch"), Ok(3));
+        assert_eq!(eval("progression:fokuspunkte"), Ok(5));
+        assert!(eval("bardenwuerfel").is_err());
+        assert!(eval("").is_err());
+        assert!(eval("CHA mod (max 1)").is_err());
+    }
+
+    #[test]
+    fn test_feature_resource_spend_restore() {
+        let mut rage = FeatureResource::new(
+            "barbar_kampfrausch_l1".into(), "KAMPFRAUSCH".into(), "kampfrausch".into(),
+            Some("short".into()), 1, &ctx(),
+        );
+        assert_eq!((rage.uses_max, rage.uses_remaining), (3, 2));
+        rage.spend(2).unwrap();
+        assert_eq!(rage.uses_remaining, 0);
+        assert!(rage.spend(1).is_err());
+        rage.restore(Some(1)).unwrap();
+        assert_eq!((rage.uses_spent, rage.uses_remaining), (2, 1));
+        rage.restore(None).unwrap();
+        assert_eq!(rage.uses_remaining, 3);
+
+        let broken = FeatureResource::new("x".into(), "X".into(), "unbekannt".into(), None, 0, &ctx());
+        assert_eq!(broken.uses_max, 0);
+        assert!(broken.error.is_some());
+    }
+}
@@ -0,0 +1,398 @@
+//! Kurze und lange Rast nach den Regeln 2024.
+//!
+//! Kurze Rast: Trefferwürfel ausgeben (Wurf + KON-Mod., mindestens 1 TP je Würfel,
+//! bei Mehrklassen-Charakteren die größten Würfel zuerst),
+//! Paktmagie-Plätze des Hexenmeisters und Merkmale mit `rest_type = 'short'` auffrischen.
+//! Lange Rast: volle TP, alle ausgegebenen Trefferwürfel zurück, alle Zauberplätze,
+//! alle Merkmale, temporäre TP verfallen, Erschöpfung sinkt um eine Stufe.
+//! Zustände mit `ends_on_rest` enden mit der passenden Rast.
+//! Eine Rast kann nur mit mindestens 1 TP begonnen werden.
+
+use serde::Serialize;
+use crate::core::conditions::{end_conditions_on_rest, reduce_exhaustion};
+use crate::core::dice::DiceRng;
+use crate::core::multiclass::{rebuild_hit_dice, restore_hit_dice, spend_hit_dice};
+use crate::types::character::Character;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RestType {
+    Short,
+    Long,
+}
+
+impl RestType {
+    /// Ob ein Merkmal mit dem `rest_type` aus `core_class_features` bei dieser Rast zurückkommt.
+    /// Was sich bei einer kurzen Rast erholt, erholt sich auch bei einer langen.
+    pub fn restores(&self, feature_rest_type: &str) -> bool {
+        match feature_rest_type.trim().to_lowercase().as_str() {
+            "short" | "kurz" => true,
+            "long" | "lang" => *self == RestType::Long,
+            _ => false,
+        }
+    }
+}
+
+/// Merkmal mit begrenzten Anwendungen, wie es aus dem Kompendium kommt.
+#[derive(Debug, Clone)]
+pub struct RestFeature {
+    pub id: String,
+    pub name: String,
+    pub rest_type: String,
+}
+
+/// Werte aus Kompendium und abgeleitetem Bogen, die eine Rast braucht.
+#[derive(Debug, Clone)]
+pub struct RestContext {
+    /// Trefferwürfel der Klasse (z.B. 10 für W10), für Altdaten ohne `HealthPool::hit_dice`
+    pub hit_die: u32,
+    pub con_modifier: i32,
+    /// Effektives TP-Maximum inkl. Modifikatoren
+    pub hp_max: i32,
+    pub features: Vec<RestFeature>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HitDieRoll {
+    pub sides: u32,
+    pub roll: u32,
+    pub con_modifier: i32,
+    pub healed: i32,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SlotRestore {
+    pub level: i32,
+    pub restored: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestSummary {
+    pub rest_type: RestType,
+    pub hp_before: i32,
+    pub hp_after: i32,
+    pub hp_healed: i32,
+    pub hit_dice_spent: i32,
+    pub hit_dice_regained: i32,
+    pub hit_dice_remaining: i32,
+    pub hit_die_rolls: Vec<HitDieRoll>,
+    /// Seed der Trefferwürfel-Würfe (nur bei kurzer Rast mit ausgegebenen Würfeln)
+    pub seed: Option<u64>,
+    pub spell_slots_restored: Vec<SlotRestore>,
+    pub pact_magic_restored: bool,
+    /// Namen der aufgefrischten Merkmale
+    pub features_reset: Vec<String>,
+    pub temp_hp_cleared: i32,
+    pub death_saves_reset: bool,
+    pub exhaustion_reduced: bool,
+    /// IDs der beendeten Zustände
+    pub conditions_ended: Vec<String>,
+}
+
+impl RestSummary {
+    fn new(rest_type: RestType, character: &Character) -> Self {
+        Self {
+            rest_type,
+            hp_before: character.health.current,
+            hp_after: character.health.current,
+            hp_healed: 0,
+            hit_dice_spent: 0,
+            hit_dice_regained: 0,
+            hit_dice_remaining: remaining_hit_dice(character),
+            hit_die_rolls: Vec::new(),
+            seed: None,
+            spell_slots_restored: Vec::new(),
+            pact_magic_restored: false,
+            features_reset: Vec::new(),
+            temp_hp_cleared: 0,
+            death_saves_reset: false,
+            exhaustion_reduced: false,
+            conditions_ended: Vec::new(),
+        }
+    }
+}
+
+pub fn remaining_hit_dice(character: &Character) -> i32 {
+    (character.health.hit_dice_max - character.health.hit_dice_used).max(0)
+}
+
+/// Hexenmeister nutzen Paktmagie – ihre Plätze kommen schon bei einer kurzen Rast zurück.
+pub fn uses_pact_magic(class_id: Option<&str>) -> bool {
+    matches!(class_id.map(|c| c.to_lowercase()).as_deref(), Some("hexenmeister") | Some("warlock"))
+}
+
+/// Nur Hexenmeister-Klassen: Ältere Charaktere führen ihre Paktplätze dann noch als normale
+/// Zauberplätze, die bei einer kurzen Rast zurückkommen. Bei Mehrklassen ist Paktmagie ein
+/// eigener Vorrat (`restore_pact_slots`), die normalen Plätze bleiben bis zur langen Rast verbraucht.
+fn pact_magic_only(character: &Character) -> bool {
+    if character.meta.classes.is_empty() {
+        return uses_pact_magic(character.meta.class_id.as_deref());
+    }
+    character.meta.classes.iter().all(|c| uses_pact_magic(Some(&c.class_id)))
+}
+
+fn ensure_can_rest(character: &Character) -> Result<(), String> {
+    if character.health.current <= 0 {
+        return Err("Eine Rast kann erst mit mindestens 1 TP begonnen werden".to_string());
+    }
+    Ok(())
+}
+
+/// Kurze Rast: gibt bis zu `hit_dice_to_spend` Trefferwürfel aus und frischt
+/// Paktmagie sowie Merkmale mit kurzer Rast auf.
+pub fn short_rest(
+    character: &mut Character,
+    hit_dice_to_spend: i32,
+    context: &RestContext,
+    seed: u64,
+) -> Result<RestSummary, String> {
+    ensure_can_rest(character)?;
+    if hit_dice_to_spend < 0 {
+        return Err(format!("Ungültige Anzahl Trefferwürfel: {}", hit_dice_to_spend));
+    }
+    if hit_dice_to_spend > remaining_hit_dice(character) {
+        return Err(format!(
+            "Nur noch {} Trefferwürfel verfügbar (angefragt: {})",
+            remaining_hit_dice(character),
+            hit_dice_to_spend
+        ));
+    }
+    if character.health.hit_dice.is_empty() {
+        if context.hit_die == 0 {
+            return Err("Trefferwürfel der Klasse unbekannt".to_string());
+        }
+        let max = character.health.hit_dice_max;
+        rebuild_hit_dice(&mut character.health, &[(context.hit_die, max)]);
+    }
+
+    let mut summary = RestSummary::new(RestType::Short, character);
+
+    if hit_dice_to_spend > 0 {
+        let mut rng = DiceRng::from_seed(seed);
+        summary.seed = Some(seed);
+        for sides in spend_hit_dice(&mut character.health, hit_dice_to_spend) {
+            let roll = rng.roll_die(sides);
+            let healed = (roll as i32 + context.con_modifier).max(1);
+            summary.hit_die_rolls.push(HitDieRoll {
+                sides,
+                roll,
+                con_modifier: context.con_modifier,
+                healed,
+            });
+        }
+        summary.hit_dice_spent = hit_dice_to_spend;
+
+        let total: i32 = summary.hit_die_rolls.iter().map(|r| r.healed).sum();
+        let hp_max = context.hp_max.max(character.health.current);
+        character.health.current = (character.health.current + total).min(hp_max);
+    }
+
+    // Ältere Hexenmeister führen ihre Paktplätze noch als normale Zauberplätze
+    if pact_magic_only(character) {
+        summary.spell_slots_restored = restore_spell_slots(character);
+        summary.pact_magic_restored = !summary.spell_slots_restored.is_empty();
+    }
+    summary.pact_magic_restored |= restore_pact_slots(character);
+
+    finish(character, &mut summary, context);
+    Ok(summary)
+}
+
+/// Lange Rast: volle TP, alle Trefferwürfel, alle Zauberplätze und Merkmale.
+pub fn long_rest(character: &mut Character, context: &RestContext) -> Result<RestSummary, String> {
+    ensure_can_rest(character)?;
+    let mut summary = RestSummary::new(RestType::Long, character);
+
+    character.health.current = character.health.current.max(context.hp_max);
+    summary.hit_dice_regained = character.health.hit_dice_used.max(0);
+    restore_hit_dice(&mut character.health);
+
+    summary.spell_slots_restored = restore_spell_slots(character);
+    summary.pact_magic_restored = pact_magic_only(character)
+        && !summary.spell_slots_restored.is_empty();
+    summary.pact_magic_restored |= restore_pact_slots(character);
+
+    summary.temp_hp_cleared = character.health.temp;
+    character.health.temp = 0;
+    summary.exhaustion_reduced = reduce_exhaustion(character);
+
+    finish(character, &mut summary, context);
+    Ok(summary)
+}
+
+fn finish(character: &mut Character, summary: &mut RestSummary, context: &RestContext) {
+    let death_saves = &mut character.health.death_saves;
+    summary.death_saves_reset = death_saves.successes != 0 || death_saves.failures != 0;
+    death_saves.successes = 0;
+    death_saves.failures = 0;
+
+    summary.conditions_ended = end_conditions_on_rest(character, summary.rest_type == RestType::Long);
+
+    summary.features_reset = context.features.iter()
+        .filter(|f| summary.rest_type.restores(&f.rest_type))
+        .map(|f| f.name.clone())
+        .collect();
+
+    summary.hp_after = character.health.current;
+    summary.hp_healed = summary.hp_after - summary.hp_before;
+    summary.hit_dice_remaining = remaining_hit_dice(character);
+}
+
+/// Setzt alle verbrauchten Zauberplätze zurück und meldet, was zurückkam.
+fn restore_spell_slots(character: &mut Character) -> Vec<SlotRestore> {
+    let mut restored = Vec::new();
+    for level in 1..=9 {
+        let used = character.meta.spell_slots_used(level);
+        if used > 0 {
+            character.meta.set_spell_slots_used(level, 0);
+            restored.push(SlotRestore { level, restored: used });
+        }
+    }
+    restored
+}
+
+/// Paktmagie-Vorrat (`core::spell_slots`) zurücksetzen; true, wenn etwas verbraucht war.
+fn restore_pact_slots(character: &mut Character) -> bool {
+    let used = character.meta.pact_slots_used;
+    character.meta.pact_slots_used = 0;
+    used > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+
+    fn context() -> RestContext {
+        RestContext {
+            hit_die: 10,
+            con_modifier: 2,
+            hp_max: 44,
+            features: vec![
+                RestFeature { id: "durchschnaufen".into(), name: "DURCHSCHNAUFEN".into(), rest_type: "short".into() },
+                RestFeature { id: "waffenmeisterung".into(), name: "WAFFENMEISTERUNG".into(), rest_type: "long".into() },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_short_rest_spends_hit_dice() {
+        let mut character = test_character();
+        character.health.current = 10;
+        character.health.hit_dice_max = 5;
+        character.health.hit_dice_used = 1;
+        character.health.temp = 3;
+        character.health.death_saves.successes = 2;
+
+        let summary = short_rest(&mut character, 2, &context(), 42).unwrap();
+        assert_eq!(summary.hit_dice_spent, 2);
+        assert_eq!(summary.hit_dice_remaining, 2);
+        assert_eq!(character.health.hit_dice_used, 3);
+        assert_eq!(summary.hit_die_rolls.len(), 2);
+        let healed: i32 = summary.hit_die_rolls.iter().map(|r| r.healed).sum();
+        assert_eq!(character.health.current, (10 + healed).min(44));
+        assert!(summary.hit_die_rolls.iter().all(|r| (1..=10).contains(&r.roll) && r.healed == r.roll as i32 + 2));
+        // Gleicher Seed, gleiches Ergebnis
+        let mut again = test_character();
+        again.health.current = 10;
+        again.health.hit_dice_max = 5;
+        again.health.hit_dice_used = 1;
+        assert_eq!(short_rest(&mut again, 2, &context(), 42).unwrap().hit_die_rolls, summary.hit_die_rolls);
+
+        // Temporäre TP bleiben bei kurzer Rast, Todesrettungswürfe nicht
+        assert_eq!(character.health.temp, 3);
+        assert!(summary.death_saves_reset);
+        assert_eq!(character.health.death_saves.successes, 0);
+        assert_eq!(summary.features_reset, vec!["DURCHSCHNAUFEN".to_string()]);
+
+        // Kein Hexenmeister: keine Paktmagie
+        assert!(!summary.pact_magic_restored);
+
+        assert!(short_rest(&mut character, 3, &context(), 1).is_err());
+        character.health.current = 0;
+        assert!(short_rest(&mut character, 0, &context(), 1).is_err());
+    }
+
+    #[test]
+    fn test_short_rest_heal_minimum_and_cap() {
+        let mut character = test_character();
+        character.health.current = 43;
+        character.health.hit_dice_max = 5;
+        let ctx = RestContext { con_modifier: -5, ..context() };
+        let summary = short_rest(&mut character, 3, &ctx, 7).unwrap();
+        assert!(summary.hit_die_rolls.iter().all(|r| r.healed >= 1));
+        assert_eq!(character.health.current, 44);
+        assert_eq!(summary.hp_healed, 1);
+    }
+
+    #[test]
+    fn test_short_rest_restores_pact_magic() {
+        let mut character = test_character();
+        character.meta.class_id = Some("hexenmeister".into());
+        character.meta.spell_slots_2 = 2;
+        character.meta.spell_slots_used_2 = 2;
+        let summary = short_rest(&mut character, 0, &context(), 1).unwrap();
+        assert!(summary.pact_magic_restored);
+        assert_eq!(summary.spell_slots_restored, vec![SlotRestore { level: 2, restored: 2 }]);
+        assert_eq!(character.meta.spell_slots_used_2, 0);
+        assert_eq!(summary.seed, None);
+
+        // Eigener Paktvorrat: normale Zauberplätze bleiben verbraucht
+        let mut fighter = test_character();
+        fighter.meta.spell_slots_used_1 = 1;
+        fighter.meta.pact_slots_used = 2;
+        let summary = short_rest(&mut fighter, 0, &context(), 1).unwrap();
+        assert!(summary.pact_magic_restored);
+        assert_eq!((fighter.meta.pact_slots_used, fighter.meta.spell_slots_used_1), (0, 1));
+    }
+
+    #[test]
+    fn test_long_rest_resets_everything() {
+        let mut character = test_character();
+        character.health.current = 5;
+        character.health.temp = 4;
+        character.health.hit_dice_max = 5;
+        character.health.hit_dice_used = 4;
+        character.health.death_saves.failures = 1;
+        character.meta.spell_slots_1 = 4;
+        character.meta.spell_slots_used_1 = 3;
+        character.meta.spell_slots_used_3 = 1;
+
+        let summary = long_rest(&mut character, &context()).unwrap();
+        assert_eq!(character.health.current, 44);
+        assert_eq!(summary.hp_healed, 39);
+        assert_eq!(summary.hit_dice_regained, 4);
+        assert_eq!(character.health.hit_dice_used, 0);
+        assert_eq!(summary.temp_hp_cleared, 4);
+        assert_eq!(character.health.temp, 0);
+        assert_eq!(character.health.death_saves.failures, 0);
+        assert_eq!(summary.spell_slots_restored.len(), 2);
+        assert_eq!(character.meta.spell_slots_used_1, 0);
+        assert_eq!(summary.features_reset.len(), 2);
+        assert!(!summary.pact_magic_restored);
+    }
+
+    #[test]
+    fn test_rest_ends_conditions_and_exhaustion() {
+        use crate::core::conditions::{exhaustion_level, set_exhaustion_level};
+        use crate::types::character::CharacterCondition;
+
+        let mut character = test_character();
+        set_exhaustion_level(&mut character, 2, None).unwrap();
+        character.conditions.push(CharacterCondition {
+            condition_id: "frightened".into(),
+            level: None,
+            source: None,
+            duration: None,
+            ends_on_rest: Some("short".into()),
+        });
+
+        let summary = short_rest(&mut character, 0, &context(), 1).unwrap();
+        assert_eq!(summary.conditions_ended, vec!["frightened".to_string()]);
+        assert!(!summary.exhaustion_reduced);
+
+        let summary = long_rest(&mut character, &context()).unwrap();
+        assert!(summary.exhaustion_reduced);
+        assert_eq!(exhaustion_level(&character.conditions), 1);
+    }
+}
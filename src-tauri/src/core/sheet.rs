@@ -0,0 +1,804 @@
+//! Abgeleitete Charakterwerte (Derived Sheet).
+//!
+//! Jede Zahl, die auf dem Charakterbogen steht, wird hier aus einem `Character`
+//! und den wenigen Kompendium-Fakten in `SheetInputs` berechnet. Das Laden der
+//! Kompendium-Daten übernimmt `db::sheet`, das Frontend zeigt nur noch an.
+//!
+//! Modifikator-Ziele (`Modifier.target`):
+//! - `str` … `cha`: Attributswerte
+//! - `save:<attr>`, `skill:<skill_id>`: einzelne Rettungswürfe / Fertigkeiten
+//! - `ac`, `initiative`, `speed` (in Fuß), `hp_max`
+//! - `passive_perception`, `passive_insight`, `passive_investigation`
+//! - `spell_save_dc`, `spell_attack`
+//! - `spell_slots:<grad>`, `pact_slots`, `pact_slot_level` (siehe `core::spell_slots`)
+//!
+//! Zustände am Charakter (`core::conditions`) wirken zusätzlich: Erschöpfung als
+//! Abzug auf W20-Tests und Bewegungsrate, andere als Nachteil oder Bewegungsrate 0.
+
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::core::armor::{ac_alternatives, armor_penalties, resolve_armor_class, AcOption, ArmorPenalties, ArmorPiece};
+use crate::core::conditions::{condition_effects, condition_names, find_condition, ConditionEffects};
+use crate::core::calculator::{
+    calculate_attribute_modifier, calculate_encumbrance, calculate_proficiency_bonus,
+};
+use crate::core::multiclass::{class_key, normalize_classes};
+use crate::core::modifiers::{active_modifiers, apply_modifiers, modifiers_for, ConditionContext, ModifierBreakdown};
+use crate::core::spell_slots::{spell_slot_target, stored_slot_maxima, SlotMaxima, PACT_SLOTS_TARGET, PACT_SLOT_LEVEL_TARGET};
+use crate::core::units::METERS_PER_FOOT;
+use crate::types::character::{Character, Modifier};
+
+pub const ABILITIES: [&str; 6] = ["str", "dex", "con", "int", "wis", "cha"];
+
+/// Standard-Bewegungsrate, falls die Spezies keine angibt (9 m = 30 ft).
+pub const DEFAULT_SPEED_FT: i32 = 30;
+
+pub struct SkillDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub ability: &'static str,
+    /// Alternative Schreibweisen, die im Frontend oder in alten Charakteren vorkommen
+    pub aliases: &'static [&'static str],
+}
+
+/// Die 18 Fertigkeiten (IDs wie in `core_skills`).
+pub const SKILLS: [SkillDef; 18] = [
+    SkillDef { id: "akrobatik", name: "Akrobatik", ability: "dex", aliases: &[] },
+    SkillDef { id: "arkane-kunde", name: "Arkane Kunde", ability: "int", aliases: &["Arkana"] },
+    SkillDef { id: "athletik", name: "Athletik", ability: "str", aliases: &[] },
+    SkillDef { id: "auftreten", name: "Auftreten", ability: "cha", aliases: &[] },
+    SkillDef { id: "einschüchterung", name: "Einschüchterung", ability: "cha", aliases: &["Einschüchtern", "einschuechtern"] },
+    SkillDef { id: "fingerfertigkeit", name: "Fingerfertigkeit", ability: "dex", aliases: &[] },
+    SkillDef { id: "geschichte", name: "Geschichte", ability: "int", aliases: &[] },
+    SkillDef { id: "heilkunde", name: "Heilkunde", ability: "wis", aliases: &[] },
+    SkillDef { id: "heimlichkeit", name: "Heimlichkeit", ability: "dex", aliases: &[] },
+    SkillDef { id: "mit-tieren-umgehen", name: "Mit Tieren umgehen", ability: "wis", aliases: &["Tierkunde"] },
+    SkillDef { id: "motiv-erkennen", name: "Motiv erkennen", ability: "wis", aliases: &[] },
+    SkillDef { id: "nachforschungen", name: "Nachforschungen", ability: "int", aliases: &["Nachforschen"] },
+    SkillDef { id: "naturkunde", name: "Naturkunde", ability: "int", aliases: &[] },
+    SkillDef { id: "religion", name: "Religion", ability: "int", aliases: &[] },
+    SkillDef { id: "täuschen", name: "Täuschen", ability: "cha", aliases: &["taeuschen"] },
+    SkillDef { id: "überlebenskunst", name: "Überlebenskunst", ability: "wis", aliases: &["ueberlebenskunst"] },
+    SkillDef { id: "überzeugen", name: "Überzeugen", ability: "cha", aliases: &["ueberzeugen"] },
+    SkillDef { id: "wahrnehmung", name: "Wahrnehmung", ability: "wis", aliases: &[] },
+];
+
+impl SkillDef {
+    /// Prüft, ob ein gespeicherter Eintrag (ID, Name oder Alias) diese Fertigkeit meint.
+    pub fn matches(&self, entry: &str) -> bool {
+        let entry = entry.trim().to_lowercase();
+        entry == self.id
+            || entry == self.name.to_lowercase()
+            || self.aliases.iter().any(|a| a.to_lowercase() == entry)
+    }
+}
+
+pub fn find_skill(entry: &str) -> Option<&'static SkillDef> {
+    SKILLS.iter().find(|s| s.matches(entry))
+}
+
+/// Zauberattribut der Klasse, falls der Charakter keines explizit gesetzt hat.
+pub fn default_spellcasting_ability(class_id: &str) -> Option<&'static str> {
+    match class_key(class_id).as_str() {
+        "magier" => Some("int"),
+        "kleriker" | "druide" | "waldlaeufer" => Some("wis"),
+        "barde" | "paladin" | "zauberer" | "hexenmeister" => Some("cha"),
+        _ => None,
+    }
+}
+
+/// Kompendium-Fakten, die nicht im `Character` selbst stehen.
+#[derive(Debug, Clone)]
+pub struct SheetInputs {
+    pub armor: Option<ArmorPiece>,
+    pub shield: Option<ArmorPiece>,
+    pub base_speed_ft: i32,
+    pub spellcasting_ability: Option<String>,
+    /// Zauberplätze aus der Klassenprogression (None = gespeicherte Werte am Charakter)
+    pub spell_slots: Option<SlotMaxima>,
+    pub carried_weight_kg: f64,
+    /// "none", "one_handed", "two_handed" (für bedingte Modifikatoren)
+    pub wielding: String,
+    /// Aktive Zustände, z.B. "raging"
+    pub active_conditions: Vec<String>,
+}
+
+impl Default for SheetInputs {
+    fn default() -> Self {
+        Self {
+            armor: None,
+            shield: None,
+            base_speed_ft: DEFAULT_SPEED_FT,
+            spellcasting_ability: None,
+            spell_slots: None,
+            carried_weight_kg: 0.0,
+            wielding: "none".to_string(),
+            active_conditions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct AbilityScore {
+    pub ability: String,
+    pub score: i32,
+    pub modifier: i32,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SavingThrow {
+    pub ability: String,
+    pub bonus: i32,
+    pub proficient: bool,
+    pub disadvantage: bool,
+    /// Misslingt automatisch (z.B. STR/GES bei Gelähmt)
+    pub auto_fail: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SkillBonus {
+    pub id: String,
+    pub name: String,
+    pub ability: String,
+    pub bonus: i32,
+    pub proficient: bool,
+    pub disadvantage: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SpellcastingStats {
+    pub ability: String,
+    pub save_dc: i32,
+    pub attack_bonus: i32,
+    /// false, wenn z.B. Rüstung ohne Ausbildung getragen wird
+    pub can_cast: bool,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SpellSlotLevel {
+    pub level: i32,
+    pub max: i32,
+    pub used: i32,
+}
+
+/// Paktmagie: eigener Vorrat, alle Plätze haben denselben Grad.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct PactSlots {
+    pub slots: i32,
+    pub slot_level: i32,
+    pub used: i32,
+}
+
+/// Hinweis auf dem Charakterbogen, z.B. "Stärke zu gering für Ritterrüstung".
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SheetWarning {
+    /// Maschinenlesbarer Code, z.B. "armor_strength", "armor_untrained"
+    pub code: String,
+    pub source: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct CarryCapacity {
+    pub capacity_kg: f64,
+    pub push_drag_lift_kg: f64,
+    pub current_weight_kg: f64,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DerivedSheet {
+    pub level: i32,
+    pub proficiency_bonus: i32,
+    pub abilities: Vec<AbilityScore>,
+    pub saving_throws: Vec<SavingThrow>,
+    pub skills: Vec<SkillBonus>,
+    pub passive_perception: i32,
+    pub passive_insight: i32,
+    pub passive_investigation: i32,
+    pub initiative: i32,
+    pub armor_class: i32,
+    /// Alle gültigen RK-Berechnungen (Rüstung, Ungerüstete Verteidigung, Magierrüstung …)
+    pub armor_class_options: Vec<AcOption>,
+    pub hp_max: i32,
+    pub speed_ft: i32,
+    pub speed_m: f64,
+    pub spellcasting: Option<SpellcastingStats>,
+    /// Grade mit mindestens einem Platz
+    pub spell_slots: Vec<SpellSlotLevel>,
+    pub pact_magic: Option<PactSlots>,
+    pub carry_capacity: CarryCapacity,
+    pub armor_penalties: ArmorPenalties,
+    pub condition_effects: ConditionEffects,
+    pub warnings: Vec<SheetWarning>,
+    /// Herleitung jedes Werts, Schlüssel = Modifikator-Ziel ("ac", "skill:athletik", ...)
+    pub breakdowns: BTreeMap<String, ModifierBreakdown>,
+}
+
+impl DerivedSheet {
+    pub fn ability_modifier(&self, ability: &str) -> i32 {
+        self.abilities
+            .iter()
+            .find(|a| a.ability.eq_ignore_ascii_case(ability))
+            .map(|a| a.modifier)
+            .unwrap_or(0)
+    }
+
+    pub fn skill_bonus(&self, skill_id: &str) -> i32 {
+        self.skills
+            .iter()
+            .find(|s| s.id == skill_id)
+            .map(|s| s.bonus)
+            .unwrap_or(0)
+    }
+}
+
+/// Sammelt während der Berechnung die Herleitung jedes Werts.
+struct Resolver<'a> {
+    modifiers: &'a [Modifier],
+    breakdowns: BTreeMap<String, ModifierBreakdown>,
+}
+
+impl Resolver<'_> {
+    /// Wendet die Modifikatoren für `target` auf den Grundwert an und merkt sich die Herleitung.
+    fn resolve(&mut self, target: &str, parts: &[(&str, i32)]) -> i32 {
+        let breakdown = apply_modifiers(parts, &modifiers_for(self.modifiers, target));
+        let total = breakdown.total;
+        self.breakdowns.insert(target.to_string(), breakdown);
+        total
+    }
+}
+
+/// Zustand für die Auswertung von `Modifier.condition`.
+pub fn condition_context(character: &Character, inputs: &SheetInputs) -> ConditionContext {
+    ConditionContext {
+        wearing_armor: inputs
+            .armor
+            .as_ref()
+            .map(|a| a.category.as_str().to_string())
+            .unwrap_or_else(|| "none".to_string()),
+        has_shield: inputs.shield.is_some(),
+        wielding: inputs.wielding.clone(),
+        hp_current: character.health.current,
+        hp_max: character.health.max,
+        level: character.meta.level,
+        active_conditions: active_conditions(character, inputs),
+    }
+}
+
+/// Zustände aus `SheetInputs` und vom Charakter (ID und deutscher Name), klein geschrieben.
+pub fn active_conditions(character: &Character, inputs: &SheetInputs) -> Vec<String> {
+    let mut names: Vec<String> = inputs.active_conditions.iter().map(|c| c.to_lowercase()).collect();
+    for name in condition_names(&character.conditions) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Berechnet alle abgeleiteten Werte eines Charakters.
+pub fn derive_sheet(character: &Character, inputs: &SheetInputs) -> DerivedSheet {
+    // Bedingte Modifikatoren nur übernehmen, wenn ihre Bedingung gerade erfüllt ist
+    let modifiers = active_modifiers(&character.modifiers, &condition_context(character, inputs));
+    let mut r = Resolver {
+        modifiers: &modifiers,
+        breakdowns: BTreeMap::new(),
+    };
+    let level = character.meta.level;
+    let proficiency_bonus = calculate_proficiency_bonus(level);
+
+    // 1. Attribute (Hintergrund-Boni sind bereits in den Basiswerten enthalten)
+    let abilities: Vec<AbilityScore> = ABILITIES
+        .iter()
+        .map(|&ability| {
+            let base = character.attributes.get(ability).unwrap_or(10);
+            let score = r.resolve(ability, &[("Basis", base)]);
+            AbilityScore {
+                ability: ability.to_string(),
+                score,
+                modifier: calculate_attribute_modifier(score),
+            }
+        })
+        .collect();
+    let ability_mod = |ability: &str| -> i32 {
+        abilities
+            .iter()
+            .find(|a| a.ability == ability)
+            .map(|a| a.modifier)
+            .unwrap_or(0)
+    };
+    let ability_score = |ability: &str| -> i32 {
+        abilities
+            .iter()
+            .find(|a| a.ability == ability)
+            .map(|a| a.score)
+            .unwrap_or(10)
+    };
+    let proficiency_part = |proficient: bool| if proficient { proficiency_bonus } else { 0 };
+    let effects = condition_effects(&character.conditions);
+    let exhaustion_part = ("Erschöpfung", -effects.d20_penalty);
+
+    // Rüstungsfolgen: Stärke-Anforderung, Heimlichkeit, fehlende Ausbildung
+    let penalties = armor_penalties(
+        inputs.armor.as_ref(),
+        inputs.shield.as_ref(),
+        ability_score("str"),
+        character.meta.class_id.as_deref(),
+        &character.proficiencies.armor,
+    );
+    let str_dex_disadvantage = |ability: &str| penalties.str_dex_disadvantage && (ability == "str" || ability == "dex");
+
+    // 2. Rettungswürfe
+    let saving_throws = ABILITIES
+        .iter()
+        .map(|&ability| {
+            let proficient = character
+                .proficiencies
+                .saving_throws
+                .iter()
+                .any(|s| s.trim().eq_ignore_ascii_case(ability));
+            let label = ability.to_uppercase();
+            SavingThrow {
+                ability: ability.to_string(),
+                bonus: r.resolve(
+                    &format!("save:{}", ability),
+                    &[(&label, ability_mod(ability)), ("Übung", proficiency_part(proficient)), exhaustion_part],
+                ),
+                proficient,
+                disadvantage: str_dex_disadvantage(ability)
+                    || effects.save_disadvantage.iter().any(|a| a == ability),
+                auto_fail: effects.save_auto_fail.iter().any(|a| a == ability),
+            }
+        })
+        .collect();
+
+    // 3. Fertigkeiten
+    let skills: Vec<SkillBonus> = SKILLS
+        .iter()
+        .map(|skill| {
+            let proficient = character.proficiencies.skills.iter().any(|s| skill.matches(s));
+            let label = skill.ability.to_uppercase();
+            SkillBonus {
+                id: skill.id.to_string(),
+                name: skill.name.to_string(),
+                ability: skill.ability.to_string(),
+                bonus: r.resolve(
+                    &format!("skill:{}", skill.id),
+                    &[(&label, ability_mod(skill.ability)), ("Übung", proficiency_part(proficient)), exhaustion_part],
+                ),
+                proficient,
+                disadvantage: str_dex_disadvantage(skill.ability)
+                    || effects.ability_check_disadvantage
+                    || (skill.id == "heimlichkeit" && penalties.stealth_disadvantage),
+            }
+        })
+        .collect();
+    let skill = |id: &str| skills.iter().find(|s| s.id == id).map(|s| s.bonus).unwrap_or(0);
+
+    // 4. Passive Werte
+    let passive_perception = r.resolve("passive_perception", &[("Basis", 10), ("Wahrnehmung", skill("wahrnehmung"))]);
+    let passive_insight = r.resolve("passive_insight", &[("Basis", 10), ("Motiv erkennen", skill("motiv-erkennen"))]);
+    let passive_investigation = r.resolve("passive_investigation", &[("Basis", 10), ("Nachforschungen", skill("nachforschungen"))]);
+
+    // 5. Initiative & RK
+    let initiative = r.resolve("initiative", &[("DEX", ability_mod("dex")), exhaustion_part]);
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
+    let alternatives = ac_alternatives(&meta.classes, &active_conditions(character, inputs));
+    let shield = inputs.shield.as_ref().filter(|_| !penalties.shield_ignored);
+    let ac = resolve_armor_class(inputs.armor.as_ref(), shield, &alternatives, &ability_mod);
+    let ac_parts: Vec<(&str, i32)> = ac
+        .best_option()
+        .parts
+        .iter()
+        .map(|(label, value)| (label.as_str(), *value))
+        .collect();
+    let armor_class = r.resolve("ac", &ac_parts);
+
+    // 6. Trefferpunkte & Bewegung
+    let hp_max = r.resolve("hp_max", &[("Basis", character.health.max)]);
+    let speed_ft = r
+        .resolve("speed", &[
+            ("Basis", inputs.base_speed_ft),
+            ("Stärke zu gering", -penalties.speed_penalty_ft),
+            ("Erschöpfung", -effects.speed_penalty_ft),
+        ])
+        .max(0);
+    let speed_ft = if effects.speed_zero { 0 } else { speed_ft };
+    let speed_m = speed_ft as f64 * METERS_PER_FOOT;
+
+    // 7. Zauberwirken
+    let spellcasting = inputs.spellcasting_ability.as_ref().map(|ability| {
+        let ability = ability.trim().to_lowercase();
+        let attr_mod = ability_mod(&ability);
+        let label = ability.to_uppercase();
+        SpellcastingStats {
+            save_dc: r.resolve("spell_save_dc", &[("Basis", 8), (&label, attr_mod), ("Übung", proficiency_bonus)]),
+            attack_bonus: r.resolve("spell_attack", &[(&label, attr_mod), ("Übung", proficiency_bonus), exhaustion_part]),
+            can_cast: !penalties.spellcasting_blocked,
+            ability,
+        }
+    });
+
+    // 8. Zauberplätze (Herleitung nur für Grade mit Plätzen oder Anpassungen)
+    let maxima = inputs.spell_slots.clone().unwrap_or_else(|| stored_slot_maxima(&character.meta));
+    let base_label = if inputs.spell_slots.is_some() { "Klasse" } else { "Gespeichert" };
+    let mut spell_slots = Vec::new();
+    for (i, &base) in maxima.slots.iter().enumerate() {
+        let level = i as i32 + 1;
+        let target = spell_slot_target(level);
+        if base == 0 && modifiers_for(&modifiers, &target).is_empty() {
+            continue;
+        }
+        let max = r.resolve(&target, &[(base_label, base)]).max(0);
+        if max > 0 {
+            spell_slots.push(SpellSlotLevel { level, max, used: character.meta.spell_slots_used(level) });
+        }
+    }
+    let pact = maxima.pact.unwrap_or_default();
+    let pact_magic = (pact.slots > 0 || !modifiers_for(&modifiers, PACT_SLOTS_TARGET).is_empty())
+        .then(|| PactSlots {
+            slots: r.resolve(PACT_SLOTS_TARGET, &[("Klasse", pact.slots)]).max(0),
+            slot_level: r.resolve(PACT_SLOT_LEVEL_TARGET, &[("Klasse", pact.slot_level)]).clamp(1, 5),
+            used: character.meta.pact_slots_used,
+        })
+        .filter(|p| p.slots > 0);
+
+    // 9. Traglast
+    let (capacity_kg, push_drag_lift_kg, status) =
+        calculate_encumbrance(ability_score("str"), inputs.carried_weight_kg);
+    let carry_capacity = CarryCapacity {
+        capacity_kg,
+        push_drag_lift_kg,
+        current_weight_kg: inputs.carried_weight_kg,
+        status: status.label().to_string(),
+    };
+
+    // 10. Hinweise
+    let mut warnings = Vec::new();
+    let armor_name = inputs.armor.as_ref().map(|a| a.name.clone()).unwrap_or_default();
+    if penalties.speed_penalty_ft > 0 {
+        warnings.push(SheetWarning {
+            code: "armor_strength".to_string(),
+            source: armor_name.clone(),
+            message: format!("Stärke zu gering: Bewegungsrate -{} ft", penalties.speed_penalty_ft),
+        });
+    }
+    if penalties.stealth_disadvantage {
+        warnings.push(SheetWarning {
+            code: "armor_stealth".to_string(),
+            source: armor_name.clone(),
+            message: "Nachteil auf Heimlichkeit".to_string(),
+        });
+    }
+    if penalties.str_dex_disadvantage {
+        warnings.push(SheetWarning {
+            code: "armor_untrained".to_string(),
+            source: armor_name,
+            message: "Keine Rüstungsausbildung: Nachteil auf W20-Tests mit STR/GES, kein Zauberwirken".to_string(),
+        });
+    }
+    if penalties.shield_ignored {
+        warnings.push(SheetWarning {
+            code: "shield_untrained".to_string(),
+            source: inputs.shield.as_ref().map(|s| s.name.clone()).unwrap_or_default(),
+            message: "Keine Schildausbildung: Schild gibt keinen RK-Bonus".to_string(),
+        });
+    }
+
+    for condition in &character.conditions {
+        let Some(def) = find_condition(&condition.condition_id) else { continue };
+        let name = match condition.level {
+            Some(level) => format!("{} {}", def.name, level),
+            None => def.name.to_string(),
+        };
+        let message = match &condition.source {
+            Some(source) => format!("{} (durch {})", def.summary, source),
+            None => def.summary.to_string(),
+        };
+        warnings.push(SheetWarning { code: format!("condition:{}", def.id), source: name, message });
+    }
+
+    DerivedSheet {
+        level,
+        proficiency_bonus,
+        abilities,
+        saving_throws,
+        skills,
+        passive_perception,
+        passive_insight,
+        passive_investigation,
+        initiative,
+        armor_class,
+        armor_class_options: ac.options,
+        hp_max,
+        speed_ft,
+        speed_m,
+        spellcasting,
+        spell_slots,
+        pact_magic,
+        carry_capacity,
+        armor_penalties: penalties,
+        condition_effects: effects,
+        warnings,
+        breakdowns: r.breakdowns,
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::core::armor::ArmorCategory;
+    use crate::core::types::ModifierType;
+    use crate::types::character::Modifier;
+
+    pub(crate) fn test_character() -> Character {
+        serde_json::from_value(serde_json::json!({
+            "id": "00000000-0000-0000-0000-000000000001",
+            "meta": { "name": "Thoradin", "level": 5, "use_metric": true, "class_id": "kaempfer" },
+            "attributes": { "str": 16, "dex": 14, "con": 14, "int": 10, "wis": 12, "cha": 8 },
+            "proficiencies": {
+                "skills": ["athletik", "Wahrnehmung"],
+                "saving_throws": ["str", "con"],
+                "weapons": [], "armor": [], "tools": [], "languages": []
+            },
+            "spellcasting": null,
+            "appearance": null
+        }))
+        .unwrap()
+    }
+
+    fn modifier(target: &str, modifier_type: ModifierType, value: i32) -> Modifier {
+        Modifier {
+            id: format!("{}-{}", target, value),
+            source: "Test".into(),
+            target: target.into(),
+            modifier_type,
+            value,
+            condition: None,
+        }
+    }
+
+    fn armor_piece(category: ArmorCategory, formula: &str) -> ArmorPiece {
+        ArmorPiece {
+            ac_formula: Some(formula.into()),
+            ..ArmorPiece::new("Test", category)
+        }
+    }
+
+    #[test]
+    fn test_derive_sheet_basics() {
+        let sheet = derive_sheet(&test_character(), &SheetInputs::default());
+
+        assert_eq!(sheet.proficiency_bonus, 3);
+        assert_eq!(sheet.ability_modifier("str"), 3);
+        assert_eq!(sheet.ability_modifier("cha"), -1);
+        assert_eq!(sheet.skills.len(), 18);
+
+        // STR-Rettungswurf geübt: 3 + 3, DEX nicht geübt: 2
+        let str_save = sheet.saving_throws.iter().find(|s| s.ability == "str").unwrap();
+        assert!(str_save.proficient);
+        assert_eq!(str_save.bonus, 6);
+        let dex_save = sheet.saving_throws.iter().find(|s| s.ability == "dex").unwrap();
+        assert_eq!(dex_save.bonus, 2);
+
+        // Fertigkeiten per ID und per Name geübt
+        assert_eq!(sheet.skill_bonus("athletik"), 6);
+        assert_eq!(sheet.skill_bonus("wahrnehmung"), 4);
+        assert_eq!(sheet.skill_bonus("heimlichkeit"), 2);
+
+        assert_eq!(sheet.passive_perception, 14);
+        assert_eq!(sheet.passive_insight, 11);
+        assert_eq!(sheet.passive_investigation, 10);
+        assert_eq!(sheet.initiative, 2);
+        assert_eq!(sheet.armor_class, 12);
+        assert_eq!(sheet.speed_ft, 30);
+        assert!((sheet.speed_m - 9.0).abs() < 1e-9);
+        assert!(sheet.spellcasting.is_none());
+        assert_eq!(sheet.carry_capacity.capacity_kg, 120.0);
+    }
+
+    #[test]
+    fn test_derive_sheet_armor_and_shield() {
+        let inputs = SheetInputs {
+            armor: Some(armor_piece(ArmorCategory::Medium, "14 + DEX (max. 2)")),
+            shield: Some(armor_piece(ArmorCategory::Shield, "+2")),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&test_character(), &inputs);
+        assert_eq!(sheet.armor_class, 18);
+    }
+
+    #[test]
+    fn test_derive_sheet_armor_penalties() {
+        let mut character = test_character();
+        character.meta.class_id = Some("magier".into());
+        let mut plate = armor_piece(ArmorCategory::Heavy, "18");
+        plate.strength_requirement = Some(17);
+        plate.stealth_disadvantage = true;
+        let inputs = SheetInputs {
+            armor: Some(plate),
+            shield: Some(armor_piece(ArmorCategory::Shield, "+2")),
+            spellcasting_ability: Some("int".into()),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+
+        assert_eq!(sheet.speed_ft, 20);
+        assert_eq!(sheet.armor_class, 18);
+        assert!(!sheet.spellcasting.unwrap().can_cast);
+        assert!(sheet.skills.iter().find(|s| s.id == "heimlichkeit").unwrap().disadvantage);
+        assert!(sheet.skills.iter().find(|s| s.id == "athletik").unwrap().disadvantage);
+        assert!(!sheet.skills.iter().find(|s| s.id == "wahrnehmung").unwrap().disadvantage);
+        assert!(sheet.saving_throws.iter().find(|s| s.ability == "dex").unwrap().disadvantage);
+        let codes: Vec<&str> = sheet.warnings.iter().map(|w| w.code.as_str()).collect();
+        assert_eq!(codes, ["armor_strength", "armor_stealth", "armor_untrained", "shield_untrained"]);
+    }
+
+    #[test]
+    fn test_derive_sheet_unarmored_defense() {
+        let mut character = test_character();
+        character.meta.class_id = Some("barbar".into());
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+
+        // 10 + DEX 2 + CON 2 schlägt 10 + DEX 2
+        assert_eq!(sheet.armor_class, 14);
+        assert_eq!(sheet.armor_class_options.len(), 2);
+        assert_eq!(sheet.breakdowns["ac"].describe(), "10 Basis + 2 DEX + 2 CON");
+    }
+
+    #[test]
+    fn test_derive_sheet_applies_modifiers() {
+        let mut character = test_character();
+        character.modifiers = vec![
+            modifier("dex", ModifierType::Add, 2),
+            modifier("ac", ModifierType::Add, 1),
+            modifier("skill:heimlichkeit", ModifierType::Add, 1),
+            modifier("speed", ModifierType::Add, 10),
+        ];
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+
+        // DEX 16 -> +3 wirkt auf RK, Initiative und DEX-Fertigkeiten
+        assert_eq!(sheet.ability_modifier("dex"), 3);
+        assert_eq!(sheet.initiative, 3);
+        assert_eq!(sheet.armor_class, 14);
+        assert_eq!(sheet.skill_bonus("heimlichkeit"), 4);
+        assert_eq!(sheet.speed_ft, 40);
+    }
+
+    #[test]
+    fn test_derive_sheet_conditional_modifiers() {
+        let mut character = test_character();
+        let mut defense = modifier("ac", ModifierType::Add, 1);
+        defense.condition = Some("wearing_armor != none".into());
+        let mut rage = modifier("skill:athletik", ModifierType::Add, 2);
+        rage.condition = Some("condition:raging".into());
+        character.modifiers = vec![defense, rage];
+
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+        assert_eq!(sheet.armor_class, 12);
+        assert_eq!(sheet.skill_bonus("athletik"), 6);
+
+        let inputs = SheetInputs {
+            armor: Some(armor_piece(ArmorCategory::Heavy, "16")),
+            active_conditions: vec!["Raging".into()],
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+        assert_eq!(sheet.armor_class, 17);
+        assert_eq!(sheet.skill_bonus("athletik"), 8);
+    }
+
+    #[test]
+    fn test_derive_sheet_conditions() {
+        use crate::types::character::CharacterCondition;
+        let condition = |id: &str, level: Option<i32>| CharacterCondition {
+            condition_id: id.into(),
+            level,
+            source: None,
+            duration: None,
+            ends_on_rest: None,
+        };
+
+        let mut character = test_character();
+        character.conditions = vec![condition("exhaustion", Some(2)), condition("poisoned", None)];
+        // Zustände erfüllen auch `condition:<name>` (deutscher Name oder ID)
+        let mut conditional = modifier("skill:athletik", ModifierType::Add, 2);
+        conditional.condition = Some("condition:vergiftet".into());
+        character.modifiers = vec![conditional];
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+
+        // Erschöpfung 2: -4 auf W20-Tests, -10 ft
+        assert_eq!(sheet.initiative, 2 - 4);
+        assert_eq!(sheet.saving_throws[0].bonus, 3 + 3 - 4);
+        assert_eq!(sheet.skill_bonus("athletik"), 3 + 3 - 4 + 2);
+        assert_eq!(sheet.speed_ft, 20);
+        assert_eq!(sheet.breakdowns["initiative"].describe(), "2 DEX - 4 Erschöpfung");
+        // Vergiftet: Nachteil auf Attributswürfe
+        assert!(sheet.skills.iter().all(|s| s.disadvantage));
+        assert!(sheet.saving_throws.iter().all(|s| !s.disadvantage));
+        assert!(sheet.warnings.iter().any(|w| w.code == "condition:poisoned"));
+
+        character.conditions = vec![condition("Gepackt", None), condition("paralyzed", None)];
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+        assert_eq!(sheet.speed_ft, 0);
+        assert!(sheet.saving_throws.iter().filter(|s| s.auto_fail).map(|s| s.ability.as_str()).eq(["str", "dex"]));
+        assert!(sheet.condition_effects.incapacitated);
+    }
+
+    #[test]
+    fn test_derive_sheet_breakdowns() {
+        let mut character = test_character();
+        character.modifiers = vec![Modifier {
+            id: "ring".into(),
+            source: "Ring of Protection".into(),
+            target: "ac".into(),
+            modifier_type: ModifierType::Add,
+            value: 1,
+            condition: None,
+        }];
+        let inputs = SheetInputs {
+            shield: Some(armor_piece(ArmorCategory::Shield, "+2")),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+
+        assert_eq!(sheet.armor_class, 15);
+        assert_eq!(sheet.breakdowns["ac"].describe(), "10 Basis + 2 DEX + 2 Schild + 1 Ring of Protection");
+        assert_eq!(sheet.breakdowns["skill:athletik"].describe(), "3 STR + 3 Übung");
+        assert_eq!(sheet.breakdowns["save:dex"].describe(), "2 DEX");
+    }
+
+    #[test]
+    fn test_derive_sheet_spellcasting() {
+        let mut character = test_character();
+        character.attributes.wis = 16;
+        let inputs = SheetInputs {
+            spellcasting_ability: Some("WIS".into()),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+        let spellcasting = sheet.spellcasting.unwrap();
+        assert_eq!(spellcasting.ability, "wis");
+        assert_eq!(spellcasting.save_dc, 14);
+        assert_eq!(spellcasting.attack_bonus, 6);
+    }
+
+    #[test]
+    fn test_derive_sheet_spell_slots() {
+        let mut character = test_character();
+        character.meta.spell_slots_used_1 = 1;
+        character.meta.pact_slots_used = 1;
+        crate::core::spell_slots::set_slot_override(&mut character.modifiers, "spell_slots:2", Some(4)).unwrap();
+        let inputs = SheetInputs {
+            spell_slots: Some(SlotMaxima {
+                slots: [4, 2, 0, 0, 0, 0, 0, 0, 0],
+                pact: Some(crate::core::spell_slots::PactMagic { slots: 2, slot_level: 3 }),
+            }),
+            ..SheetInputs::default()
+        };
+        let sheet = derive_sheet(&character, &inputs);
+        assert_eq!(sheet.spell_slots, vec![
+            SpellSlotLevel { level: 1, max: 4, used: 1 },
+            SpellSlotLevel { level: 2, max: 4, used: 0 },
+        ]);
+        assert_eq!(sheet.pact_magic, Some(PactSlots { slots: 2, slot_level: 3, used: 1 }));
+        assert!(sheet.breakdowns["spell_slots:2"].describe().contains("Manuelle Anpassung"));
+        assert!(!sheet.breakdowns.contains_key("spell_slots:3"));
+
+        // Ohne Klassendaten gelten die gespeicherten Werte
+        character.meta.spell_slots_1 = 2;
+        let sheet = derive_sheet(&character, &SheetInputs::default());
+        assert_eq!(sheet.spell_slots.iter().map(|s| (s.level, s.max)).collect::<Vec<_>>(), vec![(1, 2), (2, 4)]);
+        assert!(sheet.pact_magic.is_none());
+    }
+
+    #[test]
+    fn test_skill_aliases() {
+        assert_eq!(find_skill("Arkana").unwrap().id, "arkane-kunde");
+        assert_eq!(find_skill("Tierkunde").unwrap().id, "mit-tieren-umgehen");
+        assert_eq!(find_skill("ÜBERZEUGEN").unwrap().id, "überzeugen");
+        assert!(find_skill("Kochen").is_none());
+    }
+}
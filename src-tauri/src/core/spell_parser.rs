@@ -0,0 +1,410 @@
+//! Strukturierte Zauberdaten aus den Freitextfeldern des Kompendiums.
+//!
+//! `components`, `range`, `duration` und `casting_time` liegen als deutscher Text vor
+//! ("V, G, M (Diamantstaub im Wert von mindestens 100 GM, den der Zauber verbraucht)",
+//! "18 Meter", "Konzentration, bis zu 1 Minute", "Reaktion, die du ausführst, wenn ...").
+//! Der Parser macht daraus typisierte Werte, die `db::spell_mechanics` in eine eigene
+//! Tabelle schreibt, damit nach echten Werten gefiltert und sortiert werden kann.
+//! Nicht erkannte Texte landen als `Special` und behalten den Originaltext im Zauber.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::core::units::METERS_PER_FOOT;
+use crate::types::spell::Spell;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpellComponents {
+    pub verbal: bool,
+    pub somatic: bool,
+    pub material: bool,
+    pub material_text: Option<String>,
+    /// Mindestwert der Materialkomponente in GM
+    pub material_cost_gp: Option<f64>,
+    /// "..., die der Zauber verbraucht"
+    pub material_consumed: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeKind {
+    #[serde(rename = "self")]
+    SelfOnly,
+    Touch,
+    Distance,
+    Sight,
+    Unlimited,
+    Special,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpellRange {
+    pub kind: RangeKind,
+    pub feet: Option<i32>,
+    pub meters: Option<f64>,
+    /// Wirkungsbereich bei "Selbst (9-Meter-Kegel)"
+    pub area: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeUnit {
+    Round,
+    Minute,
+    Hour,
+    Day,
+}
+
+impl TimeUnit {
+    pub fn seconds(self) -> i64 {
+        match self {
+            TimeUnit::Round => 6,
+            TimeUnit::Minute => 60,
+            TimeUnit::Hour => 3_600,
+            TimeUnit::Day => 86_400,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TimeUnit::Round => "round",
+            TimeUnit::Minute => "minute",
+            TimeUnit::Hour => "hour",
+            TimeUnit::Day => "day",
+        }
+    }
+
+    fn parse(word: &str) -> Option<Self> {
+        let word = word.to_lowercase();
+        if word.starts_with("runde") || word.starts_with("round") {
+            Some(TimeUnit::Round)
+        } else if word.starts_with("minute") {
+            Some(TimeUnit::Minute)
+        } else if word.starts_with("stunde") || word.starts_with("hour") {
+            Some(TimeUnit::Hour)
+        } else if word.starts_with("tag") || word.starts_with("day") {
+            Some(TimeUnit::Day)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationKind {
+    Instantaneous,
+    Timed,
+    UntilDispelled,
+    Special,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpellDuration {
+    pub kind: DurationKind,
+    pub amount: Option<i32>,
+    pub unit: Option<TimeUnit>,
+    /// "bis zu": der Zauber kann früher enden
+    pub up_to: bool,
+    pub concentration: bool,
+}
+
+impl SpellDuration {
+    pub fn seconds(&self) -> Option<i64> {
+        Some(i64::from(self.amount?) * self.unit?.seconds())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CastingTimeKind {
+    Action,
+    BonusAction,
+    Reaction,
+    Time,
+    Special,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CastingTime {
+    pub kind: CastingTimeKind,
+    pub amount: Option<i32>,
+    pub unit: Option<TimeUnit>,
+    pub ritual: bool,
+    /// Auslöser bei Reaktionen und sofortigen Bonusaktionen ("wenn ...")
+    pub trigger: Option<String>,
+}
+
+impl CastingTime {
+    /// Dauer zum Sortieren: Aktionen aller Art zählen als eine Runde.
+    pub fn seconds(&self) -> Option<i64> {
+        match self.kind {
+            CastingTimeKind::Action | CastingTimeKind::BonusAction | CastingTimeKind::Reaction => Some(6),
+            CastingTimeKind::Time => Some(i64::from(self.amount?) * self.unit?.seconds()),
+            CastingTimeKind::Special => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingKind {
+    /// Höherer Zauberplatz ("Für jeden Zauberplatzgrad über dem 1. ...")
+    SlotLevel,
+    /// Zaubertricks nach Charakterstufe (5., 11., 17.)
+    CharacterLevel,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpellScaling {
+    pub kind: ScalingKind,
+    /// Grad, ab dem hochgestuft wird
+    pub base_level: Option<i32>,
+    /// Zusätzliche Würfel je Grad bzw. Stufensprung, z.B. "1W6"
+    pub dice: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpellMechanics {
+    pub components: SpellComponents,
+    pub range: SpellRange,
+    pub duration: SpellDuration,
+    pub casting_time: CastingTime,
+    pub scaling: Option<SpellScaling>,
+}
+
+/// Deutsche Zahl: "1,5" -> 1.5, "1.000" -> 1000.
+fn parse_number(text: &str) -> Option<f64> {
+    text.replace('.', "").replace(',', ".").parse().ok()
+}
+
+/// Führende Zahl mit Einheit, z.B. "10 Minuten" oder "1 Stunde oder Ritual".
+fn parse_amount_unit(text: &str) -> Option<(i32, TimeUnit)> {
+    let mut words = text.split_whitespace();
+    let amount = words.next()?.parse().ok()?;
+    let unit = TimeUnit::parse(words.next()?.trim_end_matches([',', '.']))?;
+    Some((amount, unit))
+}
+
+pub fn parse_components(components: &str, material_components: Option<&str>) -> SpellComponents {
+    let (flags, inline_text) = match components.split_once('(') {
+        Some((flags, rest)) => (flags, Some(rest.trim_end().trim_end_matches(')').trim())),
+        None => (components, None),
+    };
+    let mut parsed = SpellComponents::default();
+    for flag in flags.split(',').map(str::trim) {
+        match flag {
+            "V" => parsed.verbal = true,
+            "G" | "S" => parsed.somatic = true,
+            "M" => parsed.material = true,
+            _ => {}
+        }
+    }
+
+    let text = material_components.map(str::trim).filter(|t| !t.is_empty()).or(inline_text).filter(|t| !t.is_empty());
+    if let Some(text) = text {
+        parsed.material = true;
+        let cost = Regex::new(r"im Wert von (?:mindestens )?([\d.,]+)\s*(GM|SM|KM|EM|PM)").expect("gültiges Muster");
+        parsed.material_cost_gp = cost.captures(text).and_then(|c| {
+            let factor = match &c[2] {
+                "PM" => 10.0,
+                "EM" => 0.5,
+                "SM" => 0.1,
+                "KM" => 0.01,
+                _ => 1.0,
+            };
+            parse_number(&c[1]).map(|v| v * factor)
+        });
+        parsed.material_consumed = text.contains("verbraucht");
+        parsed.material_text = Some(text.to_string());
+    }
+    parsed
+}
+
+pub fn parse_range(range: &str) -> SpellRange {
+    let text = range.trim();
+    let lower = text.to_lowercase();
+    let special = |kind| SpellRange { kind, feet: None, meters: None, area: None };
+
+    if lower.starts_with("selbst") || lower.starts_with("self") {
+        let area = text.split_once('(').map(|(_, a)| a.trim_end_matches(')').trim().to_string());
+        return SpellRange { area, ..special(RangeKind::SelfOnly) };
+    }
+    if lower.starts_with("berührung") || lower.starts_with("touch") {
+        return special(RangeKind::Touch);
+    }
+    if lower.starts_with("sicht") || lower.starts_with("sight") {
+        return special(RangeKind::Sight);
+    }
+    if lower.starts_with("unbegrenzt") || lower.starts_with("unlimited") {
+        return special(RangeKind::Unlimited);
+    }
+
+    let distance = Regex::new(r"^([\d.,]+)\s*(Kilometer|km|Meter|m|Fuß|ft|Meilen?)\b").expect("gültiges Muster");
+    let Some(c) = distance.captures(text) else {
+        return special(RangeKind::Special);
+    };
+    let Some(value) = parse_number(&c[1]) else {
+        return special(RangeKind::Special);
+    };
+    // 1,6 km entsprechen im PHB (DE) einer Meile
+    let (feet, meters) = match &c[2] {
+        "Kilometer" | "km" => (value / 1.6 * 5_280.0, value * 1_000.0),
+        "Fuß" | "ft" => (value, value * METERS_PER_FOOT),
+        "Meile" | "Meilen" => (value * 5_280.0, value * 1_600.0),
+        _ => (value / METERS_PER_FOOT, value),
+    };
+    SpellRange { kind: RangeKind::Distance, feet: Some(feet.round() as i32), meters: Some(meters), area: None }
+}
+
+pub fn parse_duration(duration: &str, concentration: bool) -> SpellDuration {
+    let text = duration.trim();
+    let lower = text.to_lowercase();
+    let mut parsed = SpellDuration {
+        kind: DurationKind::Special,
+        amount: None,
+        unit: None,
+        up_to: false,
+        concentration: concentration || lower.starts_with("konzentration"),
+    };
+
+    if lower.starts_with("unmittelbar") || lower.starts_with("sofort") {
+        parsed.kind = DurationKind::Instantaneous;
+        return parsed;
+    }
+    if lower.starts_with("bis der zauber gebannt") {
+        parsed.kind = DurationKind::UntilDispelled;
+        return parsed;
+    }
+
+    let rest = lower.strip_prefix("konzentration,").map(str::trim).unwrap_or(&lower);
+    let rest = match rest.strip_prefix("bis zu ") {
+        Some(rest) => {
+            parsed.up_to = true;
+            rest
+        }
+        None => rest,
+    };
+    if let Some((amount, unit)) = parse_amount_unit(rest) {
+        parsed.kind = DurationKind::Timed;
+        parsed.amount = Some(amount);
+        parsed.unit = Some(unit);
+    }
+    parsed
+}
+
+pub fn parse_casting_time(casting_time: &str, ritual: bool) -> CastingTime {
+    let text = casting_time.trim();
+    let lower = text.to_lowercase();
+    let trigger = text.split_once(',').map(|(_, t)| t.trim().to_string()).filter(|t| !t.is_empty());
+    let mut parsed = CastingTime {
+        kind: CastingTimeKind::Special,
+        amount: None,
+        unit: None,
+        ritual: ritual || lower.contains("ritual"),
+        trigger: None,
+    };
+
+    if lower.starts_with("bonusaktion") || lower.starts_with("bonus action") {
+        parsed.kind = CastingTimeKind::BonusAction;
+        parsed.trigger = trigger;
+    } else if lower.starts_with("reaktion") || lower.starts_with("reaction") {
+        parsed.kind = CastingTimeKind::Reaction;
+        parsed.trigger = trigger;
+    } else if lower.starts_with("aktion") || lower.starts_with("action") {
+        parsed.kind = CastingTimeKind::Action;
+    } else if let Some((amount, unit)) = parse_amount_unit(&lower) {
+        parsed.kind = CastingTimeKind::Time;
+        parsed.amount = Some(amount);
+        parsed.unit = Some(unit);
+    }
+    parsed
+}
+
+/// Art des Hochstufens aus `higher_levels`.
+pub fn parse_scaling(spell_level: i32, higher_levels: Option<&str>) -> Option<SpellScaling> {
+    let text = higher_levels.map(str::trim).filter(|t| !t.is_empty())?;
+    let dice = Regex::new(r"\d+W\d+").expect("gültiges Muster");
+    let first_dice = |t: &str| dice.find(t).map(|m| m.as_str().to_string());
+
+    if spell_level == 0 {
+        return Some(SpellScaling { kind: ScalingKind::CharacterLevel, base_level: None, dice: first_dice(text) });
+    }
+    let base = Regex::new(r"über dem (\d+)\.").expect("gültiges Muster");
+    let base_match = base.captures(text);
+    let sentence_start = base_match.as_ref().and_then(|c| c.get(0)).map_or(0, |m| m.end());
+    let sentence = text[sentence_start..].split_once(". ").map_or(&text[sentence_start..], |(s, _)| s);
+    Some(SpellScaling {
+        kind: ScalingKind::SlotLevel,
+        base_level: base_match.and_then(|c| c[1].parse().ok()).or(Some(spell_level)),
+        dice: first_dice(sentence),
+    })
+}
+
+pub fn parse_spell(spell: &Spell) -> SpellMechanics {
+    SpellMechanics {
+        components: parse_components(&spell.components, spell.material_components.as_deref()),
+        range: parse_range(&spell.range),
+        duration: parse_duration(&spell.duration, spell.concentration),
+        casting_time: parse_casting_time(&spell.casting_time, spell.ritual),
+        scaling: parse_scaling(spell.level, spell.higher_levels.as_deref()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_components_and_range() {
+        let diamond = parse_components("V, G, M (Diamantstaub im Wert von mindestens 1.000 GM, den der Zauber verbraucht)", None);
+        assert!(diamond.verbal && diamond.somatic && diamond.material);
+        assert_eq!(diamond.material_cost_gp, Some(1000.0));
+        assert!(diamond.material_consumed);
+
+        let weapon = parse_components("G, M (eine Nahkampfwaffe im Wert von mindestens 1 SM)", None);
+        assert!(!weapon.verbal && weapon.somatic);
+        assert_eq!(weapon.material_cost_gp, Some(0.1));
+        assert!(!weapon.material_consumed);
+        assert_eq!(parse_components("V", Some("eine Feder")).material_text.as_deref(), Some("eine Feder"));
+
+        assert_eq!(parse_range("18 Meter").feet, Some(60));
+        assert_eq!(parse_range("1,5 Meter").feet, Some(5));
+        assert_eq!(parse_range("1,6 Kilometer").feet, Some(5280));
+        assert_eq!(parse_range("60 Fuß").meters, Some(18.0));
+        let cone = parse_range("Selbst (9-Meter-Kegel)");
+        assert_eq!((cone.kind, cone.area.as_deref()), (RangeKind::SelfOnly, Some("9-Meter-Kegel")));
+        assert_eq!(parse_range("Berührung").kind, RangeKind::Touch);
+        assert_eq!(parse_range("Besonders").kind, RangeKind::Special);
+    }
+
+    #[test]
+    fn test_parse_duration_and_casting_time() {
+        let concentration = parse_duration("Konzentration, bis zu 10 Minuten", false);
+        assert!(concentration.concentration && concentration.up_to);
+        assert_eq!(concentration.seconds(), Some(600));
+        assert_eq!(parse_duration("1 Runde", false).seconds(), Some(6));
+        assert_eq!(parse_duration("Unmittelbar", false).kind, DurationKind::Instantaneous);
+        assert_eq!(parse_duration("Bis der Zauber gebannt wird", false).kind, DurationKind::UntilDispelled);
+
+        let ritual = parse_casting_time("1 Minute oder Ritual", false);
+        assert_eq!((ritual.kind, ritual.seconds(), ritual.ritual), (CastingTimeKind::Time, Some(60), true));
+        let reaction = parse_casting_time("Reaktion, die du ausführst, wenn du Schaden", false);
+        assert_eq!(reaction.kind, CastingTimeKind::Reaction);
+        assert_eq!(reaction.trigger.as_deref(), Some("die du ausführst, wenn du Schaden"));
+        assert_eq!(parse_casting_time("Aktion (Mehr Wachstum) oder 8 Stunden", false).kind, CastingTimeKind::Action);
+        assert_eq!(parse_casting_time("Bonusaktion", false).kind, CastingTimeKind::BonusAction);
+    }
+
+    #[test]
+    fn test_parse_scaling() {
+        let bolt = parse_scaling(3, Some("Für jeden Zauberplatzgrad über dem 3. wird der Schaden um 1W6 erhöht.")).unwrap();
+        assert_eq!((bolt.kind, bolt.base_level, bolt.dice.as_deref()), (ScalingKind::SlotLevel, Some(3), Some("1W6")));
+
+        let cantrip = parse_scaling(0, Some("Der Schaden wird um jeweils 1W10 erhöht, wenn du die 5. (2W10) Stufe erreichst.")).unwrap();
+        assert_eq!((cantrip.kind, cantrip.dice.as_deref()), (ScalingKind::CharacterLevel, Some("1W10")));
+
+        let summon = parse_scaling(4, Some("Verwende den Zauberplatzgrad als Zaubergrad im Wertekasten.")).unwrap();
+        assert_eq!((summon.base_level, summon.dice), (Some(4), None));
+        assert!(parse_scaling(1, None).is_none());
+    }
+}
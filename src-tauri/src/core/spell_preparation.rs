@@ -0,0 +1,317 @@
+//! Zaubervorbereitung: Anzahl, Klassenliste und Grad.
+//!
+//! Wie viele Zauber vorbereitet sein dürfen, steht in der Stufentabelle
+//! (`vorbereitete_zauber`). Immer vorbereitete Zauber und Zaubertricks zählen nicht mit.
+//! Ein Zauber muss auf der Liste einer Klasse des Charakters stehen (`Spell.classes`)
+//! und diese Klasse muss Plätze seines Grades haben. Bei Mehrklassen-Charakteren
+//! addieren sich die Anzahlen, der Grad gilt je Klasse.
+
+use serde::Serialize;
+use serde_json::Value;
+use crate::core::multiclass::same_class;
+use crate::types::character::Character;
+
+/// Vorbereitbare Zauber aus `class_specific_data` ("—" = keine, fehlt = Klasse bereitet nicht vor).
+pub fn progression_prepared_count(data: &Value) -> Option<i32> {
+    match data.get("vorbereitete_zauber")? {
+        Value::Number(n) => n.as_i64().map(|n| n as i32),
+        Value::String(s) => Some(s.trim().parse().unwrap_or(0)),
+        _ => None,
+    }
+}
+
+/// Eine Klasse des Charakters mit ihren Vorbereitungs-Grenzen.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparationClass {
+    pub class_id: String,
+    /// `None`, wenn die Stufentabelle keine Anzahl kennt
+    pub limit: Option<i32>,
+    /// Höchster Grad, für den diese Klasse Plätze hat
+    pub max_spell_level: i32,
+}
+
+/// Die Kompendium-Fakten eines Zaubers, die für die Vorbereitung zählen.
+#[derive(Debug, Clone)]
+pub struct SpellInfo {
+    pub id: String,
+    pub name: String,
+    pub level: i32,
+    /// Kommagetrennte Klassennamen, z.B. "Barde, Magier"
+    pub classes: String,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum PreparationIssue {
+    /// Mehr vorbereitete Zauber als erlaubt
+    LimitExceeded { prepared: i32, limit: i32 },
+    /// Keine Klasse des Charakters bereitet Zauber vor
+    NoPreparingClass,
+    UnknownSpell { spell_id: String },
+    NotOnClassList { spell_id: String, spell_name: String },
+    LevelTooHigh { spell_id: String, spell_name: String, level: i32, max_level: i32 },
+}
+
+impl PreparationIssue {
+    pub fn message(&self) -> String {
+        match self {
+            Self::LimitExceeded { prepared, limit } => {
+                format!("Zu viele vorbereitete Zauber: {} von {}", prepared, limit)
+            }
+            Self::NoPreparingClass => "Keine Klasse des Charakters bereitet Zauber vor".to_string(),
+            Self::UnknownSpell { spell_id } => format!("Unbekannter Zauber: {}", spell_id),
+            Self::NotOnClassList { spell_name, .. } => {
+                format!("{} steht auf keiner Zauberliste der Klassen des Charakters", spell_name)
+            }
+            Self::LevelTooHigh { spell_name, level, max_level, .. } => {
+                format!("{} (Grad {}) übersteigt den höchsten Grad {}", spell_name, level, max_level)
+            }
+        }
+    }
+}
+
+/// Stand der Vorbereitung; `issues` ist leer, wenn alles passt.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparationStatus {
+    pub prepared: i32,
+    pub limit: Option<i32>,
+    pub issues: Vec<PreparationIssue>,
+    /// Lesbare Texte zu `issues`, gleiche Reihenfolge
+    pub messages: Vec<String>,
+}
+
+impl PreparationStatus {
+    fn with_issues(mut self, issues: Vec<PreparationIssue>) -> Self {
+        self.messages = issues.iter().map(PreparationIssue::message).collect();
+        self.issues = issues;
+        self
+    }
+}
+
+pub fn on_class_list(spell: &SpellInfo, class_id: &str) -> bool {
+    spell.classes.split(',').any(|name| same_class(name, class_id))
+}
+
+/// Klassen des Charakters, für die der Zauber vorbereitet werden kann.
+pub fn classes_for_spell<'a>(spell: &SpellInfo, classes: &'a [PreparationClass]) -> Vec<&'a PreparationClass> {
+    classes.iter().filter(|c| on_class_list(spell, &c.class_id)).collect()
+}
+
+pub fn total_limit(classes: &[PreparationClass]) -> Option<i32> {
+    classes.iter().filter_map(|c| c.limit).reduce(|a, b| a + b)
+}
+
+/// Prüft einen einzelnen Zauber (Klassenliste, Grad).
+pub fn check_spell(spell: &SpellInfo, classes: &[PreparationClass]) -> Option<PreparationIssue> {
+    let matching = classes_for_spell(spell, classes);
+    if matching.is_empty() {
+        return Some(PreparationIssue::NotOnClassList { spell_id: spell.id.clone(), spell_name: spell.name.clone() });
+    }
+    let max_level = matching.iter().map(|c| c.max_spell_level).max().unwrap_or(0);
+    (spell.level > max_level).then(|| PreparationIssue::LevelTooHigh {
+        spell_id: spell.id.clone(),
+        spell_name: spell.name.clone(),
+        level: spell.level,
+        max_level,
+    })
+}
+
+/// Zählt die vorbereiteten Zauber (ohne immer vorbereitete und Zaubertricks).
+pub fn prepared_count(character: &Character, spells: &[SpellInfo]) -> i32 {
+    character.spells.iter()
+        .filter(|s| s.is_prepared && !s.is_always_prepared)
+        .filter(|s| spells.iter().find(|i| i.id == s.spell_id).is_none_or(|i| i.level > 0))
+        .count() as i32
+}
+
+/// Prüft die gesamte Vorbereitung eines Charakters. `spells` enthält die Kompendium-Daten
+/// der Zauber des Charakters.
+pub fn preparation_status(character: &Character, classes: &[PreparationClass], spells: &[SpellInfo]) -> PreparationStatus {
+    let prepared = prepared_count(character, spells);
+    let limit = total_limit(classes);
+    let mut issues = Vec::new();
+
+    for entry in character.spells.iter().filter(|s| s.is_prepared && !s.is_always_prepared) {
+        match spells.iter().find(|i| i.id == entry.spell_id) {
+            Some(spell) if spell.level == 0 => {}
+            Some(spell) => issues.extend(check_spell(spell, classes)),
+            None => issues.push(PreparationIssue::UnknownSpell { spell_id: entry.spell_id.clone() }),
+        }
+    }
+    match limit {
+        Some(limit) if prepared > limit => issues.insert(0, PreparationIssue::LimitExceeded { prepared, limit }),
+        None if prepared > 0 => issues.insert(0, PreparationIssue::NoPreparingClass),
+        _ => {}
+    }
+    PreparationStatus { prepared, limit, issues: Vec::new(), messages: Vec::new() }.with_issues(issues)
+}
+
+/// Ein Zauber, den der Charakter vorbereiten darf.
+#[derive(Debug, Clone, Serialize)]
+pub struct PreparableSpell {
+    pub spell_id: String,
+    pub name: String,
+    pub level: i32,
+    /// Klassen des Charakters, auf deren Liste der Zauber steht
+    pub class_ids: Vec<String>,
+    /// `CharacterSpell.id`, falls der Zauber schon beim Charakter ist
+    pub entry_id: Option<String>,
+    pub is_prepared: bool,
+    pub is_always_prepared: bool,
+}
+
+/// Alle Zauber (ab Grad 1) aus `compendium`, die Klassenliste und Grad erfüllen.
+pub fn preparable_spells(character: &Character, classes: &[PreparationClass], compendium: &[SpellInfo]) -> Vec<PreparableSpell> {
+    compendium.iter()
+        .filter(|spell| spell.level > 0 && check_spell(spell, classes).is_none())
+        .map(|spell| {
+            let entry = character.spells.iter().find(|s| s.spell_id == spell.id);
+            PreparableSpell {
+                spell_id: spell.id.clone(),
+                name: spell.name.clone(),
+                level: spell.level,
+                class_ids: classes_for_spell(spell, classes).iter().map(|c| c.class_id.clone()).collect(),
+                entry_id: entry.map(|e| e.id.clone()),
+                is_prepared: entry.is_some_and(|e| e.is_prepared),
+                is_always_prepared: entry.is_some_and(|e| e.is_always_prepared),
+            }
+        })
+        .collect()
+}
+
+/// Setzt `is_prepared` eines Zaubers (`CharacterSpell.id`). Das Vorbereiten wird geprüft,
+/// Zurücknehmen geht immer. `issues` enthält nur Probleme, die diesen Schritt verhindern;
+/// dann bleibt der Charakter unverändert. Den Gesamtstand liefert `preparation_status`.
+pub fn set_prepared(
+    character: &mut Character,
+    entry_id: &str,
+    is_prepared: bool,
+    classes: &[PreparationClass],
+    spells: &[SpellInfo],
+) -> Result<PreparationStatus, String> {
+    let index = character.spells.iter().position(|s| s.id == entry_id)
+        .ok_or_else(|| format!("Zauber {} gehört nicht zum Charakter", entry_id))?;
+
+    let mut updated = character.clone();
+    updated.spells[index].is_prepared = is_prepared;
+    let entry = &updated.spells[index];
+
+    let status = preparation_status(&updated, classes, spells);
+    let newly_prepared = is_prepared && !character.spells[index].is_prepared && !entry.is_always_prepared;
+    if newly_prepared {
+        // Nur Probleme, die dieser Schritt verursacht; ältere Altlasten blockieren nicht
+        let spell = spells.iter().find(|i| i.id == entry.spell_id);
+        let mut blocking: Vec<PreparationIssue> = match spell {
+            None => vec![PreparationIssue::UnknownSpell { spell_id: entry.spell_id.clone() }],
+            Some(spell) if spell.level == 0 => Vec::new(),
+            Some(spell) => check_spell(spell, classes).into_iter().collect(),
+        };
+        if spell.is_some_and(|s| s.level > 0) {
+            blocking.extend(status.issues.iter()
+                .filter(|i| matches!(i, PreparationIssue::LimitExceeded { .. } | PreparationIssue::NoPreparingClass))
+                .cloned());
+        }
+        if !blocking.is_empty() {
+            return Ok(preparation_status(character, classes, spells).with_issues(blocking));
+        }
+    }
+
+    *character = updated;
+    Ok(status.with_issues(Vec::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::sheet::tests::test_character;
+    use crate::types::character::CharacterSpell;
+
+    fn spell(id: &str, level: i32, classes: &str) -> SpellInfo {
+        SpellInfo { id: id.into(), name: id.to_uppercase(), level, classes: classes.into() }
+    }
+
+    fn entry(id: &str, prepared: bool, always: bool) -> CharacterSpell {
+        CharacterSpell {
+            id: format!("row-{}", id),
+            spell_id: id.into(),
+            is_prepared: prepared,
+            is_always_prepared: always,
+            source: None,
+        }
+    }
+
+    fn paladin() -> Vec<PreparationClass> {
+        vec![PreparationClass { class_id: "paladin".into(), limit: Some(2), max_spell_level: 1 }]
+    }
+
+    fn spells() -> Vec<SpellInfo> {
+        vec![
+            spell("segnen", 1, "Kleriker, Paladin"),
+            spell("heldenmut", 1, "Barde, Paladin"),
+            spell("schild-des-glaubens", 1, "Kleriker, Paladin"),
+            spell("feuerball", 3, "Magier, Zauberer"),
+            spell("niederstreckender-schlag", 2, "Paladin"),
+        ]
+    }
+
+    #[test]
+    fn test_preparation_status() {
+        let mut character = test_character();
+        character.spells = vec![
+            entry("segnen", true, false),
+            entry("heldenmut", true, true),
+            entry("feuerball", true, false),
+            entry("niederstreckender-schlag", true, false),
+        ];
+        let status = preparation_status(&character, &paladin(), &spells());
+        assert_eq!((status.prepared, status.limit), (3, Some(2)));
+        assert_eq!(status.issues, vec![
+            PreparationIssue::LimitExceeded { prepared: 3, limit: 2 },
+            PreparationIssue::NotOnClassList { spell_id: "feuerball".into(), spell_name: "FEUERBALL".into() },
+            PreparationIssue::LevelTooHigh {
+                spell_id: "niederstreckender-schlag".into(),
+                spell_name: "NIEDERSTRECKENDER-SCHLAG".into(),
+                level: 2,
+                max_level: 1,
+            },
+        ]);
+        assert!(!status.issues[0].message().is_empty());
+
+        let candidates = preparable_spells(&character, &paladin(), &spells());
+        let ids: Vec<&str> = candidates.iter().map(|c| c.spell_id.as_str()).collect();
+        assert_eq!(ids, vec!["segnen", "heldenmut", "schild-des-glaubens"]);
+        assert!(candidates[1].is_always_prepared);
+        assert_eq!(candidates[2].entry_id, None);
+    }
+
+    #[test]
+    fn test_set_prepared_checks_limit() {
+        let mut character = test_character();
+        character.spells = vec![
+            entry("segnen", true, false),
+            entry("heldenmut", false, false),
+            entry("schild-des-glaubens", false, false),
+            entry("feuerball", false, false),
+        ];
+        let status = set_prepared(&mut character, "row-heldenmut", true, &paladin(), &spells()).unwrap();
+        assert!(status.issues.is_empty());
+        assert!(character.spells[1].is_prepared);
+
+        // Dritter Zauber sprengt die Grenze, fremde Liste wird abgelehnt
+        let status = set_prepared(&mut character, "row-schild-des-glaubens", true, &paladin(), &spells()).unwrap();
+        assert_eq!(status.issues, vec![PreparationIssue::LimitExceeded { prepared: 3, limit: 2 }]);
+        assert_eq!((status.prepared, status.messages.len()), (2, 1));
+        assert!(!character.spells[2].is_prepared);
+        let status = set_prepared(&mut character, "row-feuerball", true, &paladin(), &spells()).unwrap();
+        assert!(matches!(status.issues[0], PreparationIssue::NotOnClassList { .. }));
+
+        // Zurücknehmen geht immer, auch wenn danach noch zu viele vorbereitet sind
+        character.spells[2].is_prepared = true;
+        character.spells[3].is_prepared = true;
+        let status = set_prepared(&mut character, "row-segnen", false, &paladin(), &spells()).unwrap();
+        assert!(!character.spells[0].is_prepared);
+        assert!(status.issues.is_empty() && status.messages.is_empty());
+        assert_eq!(status.prepared, 3);
+        assert!(set_prepared(&mut character, "row-unbekannt", true, &paladin(), &spells()).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Zauberplätze aus der Klassenprogression.
+//!
+//! Die Maxima werden nicht mehr von Hand gepflegt, sondern aus
+//! `core_progression_tables.class_specific_data` abgeleitet (`zauberplaetze_N_grad`).
+//! Volle, halbe und Drittel-Zauberwirker ohne eigene Tabellenspalten (z.B. Mystischer
+//! Ritter) nutzen die Regeltabelle, mehrere Zauberklassen die Mehrklassen-Tabelle.
+//! Paktmagie ist ein eigener Vorrat: wenige Plätze eines Grades, zurück nach kurzer Rast.
+//!
+//! Manuelle Anpassungen sind Modifikatoren auf `spell_slots:<grad>`, `pact_slots`
+//! und `pact_slot_level` (siehe `core::sheet`).
+
+use serde::Serialize;
+use serde_json::Value;
+use crate::core::multiclass::{caster_type, multiclass_spell_slots, spell_slots_for_caster_level, CasterType};
+use crate::core::types::ModifierType;
+use crate::types::character::{CharacterMeta, ClassLevel, Modifier};
+
+/// Modifikator-Ziel für das Maximum eines Grades.
+pub fn spell_slot_target(level: i32) -> String {
+    format!("spell_slots:{}", level)
+}
+
+pub const PACT_SLOTS_TARGET: &str = "pact_slots";
+pub const PACT_SLOT_LEVEL_TARGET: &str = "pact_slot_level";
+
+/// Maximale Zauberplätze (Grad 1-9) aus `class_specific_data` ("—" = keine).
+pub fn progression_spell_slots(data: &Value) -> [i32; 9] {
+    let mut slots = [0; 9];
+    for (i, slot) in slots.iter_mut().enumerate() {
+        *slot = match data.get(format!("zauberplaetze_{}_grad", i + 1)) {
+            Some(Value::Number(n)) => n.as_i64().unwrap_or(0) as i32,
+            Some(Value::String(s)) => s.trim().parse().unwrap_or(0),
+            _ => 0,
+        };
+    }
+    slots
+}
+
+/// Hat die Stufentabelle überhaupt Zauberplatz-Spalten?
+pub fn has_progression_slots(data: &Value) -> bool {
+    data.get("zauberplaetze_1_grad").is_some()
+}
+
+/// Eine Klasse des Charakters samt Zauberplätzen aus ihrer Stufentabelle.
+#[derive(Debug, Clone)]
+pub struct ClassSlotInput {
+    pub class: ClassLevel,
+    /// `None`, wenn die Tabelle keine Zauberplatz-Spalten hat
+    pub progression: Option<[i32; 9]>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct PactMagic {
+    pub slots: i32,
+    pub slot_level: i32,
+}
+
+/// Maxima ohne Modifikatoren.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SlotMaxima {
+    pub slots: [i32; 9],
+    pub pact: Option<PactMagic>,
+}
+
+/// Paktmagie nach Hexenmeisterstufe (PHB 2024). Die Stufentabelle im Kompendium hat
+/// dafür keine brauchbaren Spalten (nur Plätze je Grad), daher steht sie hier.
+pub fn pact_magic(warlock_level: i32) -> Option<PactMagic> {
+    let (slots, slot_level) = match warlock_level {
+        l if l < 1 => return None,
+        1 => (1, 1),
+        2 => (2, 1),
+        3..=4 => (2, 2),
+        5..=6 => (2, 3),
+        7..=8 => (2, 4),
+        9..=10 => (2, 5),
+        11..=16 => (3, 5),
+        _ => (4, 5),
+    };
+    Some(PactMagic { slots, slot_level })
+}
+
+/// Eigene Tabelle einer einzelnen Zauberklasse, falls die Stufentabelle keine Plätze hat.
+/// Halbe Zauberwirker runden die Stufe auf, Drittel-Zauberwirker ab Stufe 3 ebenfalls.
+fn single_class_slots(class: &ClassLevel, caster: CasterType) -> [i32; 9] {
+    let caster_level = match caster {
+        CasterType::Full => class.level,
+        CasterType::Half => (class.level + 1) / 2,
+        CasterType::Third if class.level >= 3 => (class.level + 2) / 3,
+        _ => 0,
+    };
+    spell_slots_for_caster_level(caster_level)
+}
+
+/// Zauberplätze einer Klasse für sich allein, als hätte der Charakter nur diese Klasse.
+/// Eigene Klassen (Homebrew) zählen nur mit Zauberplätzen in ihrer Stufentabelle.
+pub fn class_slot_maxima(input: &ClassSlotInput) -> SlotMaxima {
+    let slots = match caster_type(&input.class.class_id, input.class.subclass_id.as_deref()) {
+        caster @ (CasterType::Full | CasterType::Half | CasterType::Third) => {
+            input.progression.unwrap_or_else(|| single_class_slots(&input.class, caster))
+        }
+        CasterType::None => input.progression.unwrap_or_default(),
+        CasterType::Pact => {
+            return SlotMaxima { slots: [0; 9], pact: pact_magic(input.class.level) };
+        }
+    };
+    SlotMaxima { slots, pact: None }
+}
+
+impl SlotMaxima {
+    /// Höchster Grad mit mindestens einem Platz (0 = keiner).
+    pub fn max_spell_level(&self) -> i32 {
+        let slots = self.slots.iter().rposition(|n| *n > 0).map_or(0, |i| i as i32 + 1);
+        slots.max(self.pact.map_or(0, |p| p.slot_level))
+    }
+}
+
+/// Leitet die Zauberplatz-Maxima aus den Klassen ab.
+pub fn compute_slot_maxima(classes: &[ClassSlotInput]) -> SlotMaxima {
+    let levels: Vec<ClassLevel> = classes.iter().map(|c| c.class.clone()).collect();
+    let mut maxima = SlotMaxima::default();
+
+    if let Some(slots) = multiclass_spell_slots(&levels) {
+        maxima.slots = slots;
+    } else if let Some(slots) = classes.iter()
+        .map(|c| class_slot_maxima(c).slots)
+        .find(|s| s.iter().any(|n| *n > 0))
+    {
+        maxima.slots = slots;
+    }
+
+    let warlock_level: i32 = levels.iter()
+        .filter(|c| caster_type(&c.class_id, c.subclass_id.as_deref()) == CasterType::Pact)
+        .map(|c| c.level)
+        .sum();
+    maxima.pact = pact_magic(warlock_level);
+    maxima
+}
+
+/// Maxima aus den Einzelfeldern von `CharacterMeta` (Charaktere ohne Klassendaten).
+pub fn stored_slot_maxima(meta: &CharacterMeta) -> SlotMaxima {
+    let mut maxima = SlotMaxima::default();
+    for (i, slot) in maxima.slots.iter_mut().enumerate() {
+        *slot = meta.spell_slots(i as i32 + 1);
+    }
+    maxima
+}
+
+pub fn is_slot_target(target: &str) -> bool {
+    target == PACT_SLOTS_TARGET
+        || target == PACT_SLOT_LEVEL_TARGET
+        || target
+            .strip_prefix("spell_slots:")
+            .and_then(|l| l.parse::<i32>().ok())
+            .is_some_and(|l| (1..=9).contains(&l))
+}
+
+/// ID der manuellen Anpassung eines Ziels (höchstens eine je Ziel).
+pub fn override_id(target: &str) -> String {
+    format!("override:{}", target)
+}
+
+/// Setzt (oder entfernt mit `None`) die manuelle Anpassung eines Zauberplatz-Maximums.
+/// Sie wird als Override-Modifikator gespeichert, damit die Herleitung sichtbar bleibt.
+pub fn set_slot_override(modifiers: &mut Vec<Modifier>, target: &str, value: Option<i32>) -> Result<(), String> {
+    if !is_slot_target(target) {
+        return Err(format!("Kein Zauberplatz-Ziel: {}", target));
+    }
+    if let Some(value) = value {
+        let valid = if target == PACT_SLOT_LEVEL_TARGET { (1..=5).contains(&value) } else { value >= 0 };
+        if !valid {
+            return Err(format!("Ungültiger Wert für {}: {}", target, value));
+        }
+    }
+
+    let id = override_id(target);
+    modifiers.retain(|m| m.id != id);
+    if let Some(value) = value {
+        modifiers.push(Modifier {
+            id,
+            source: "Manuelle Anpassung".to_string(),
+            target: target.to_string(),
+            modifier_type: ModifierType::Override,
+            value,
+            condition: None,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn class(class_id: &str, subclass_id: Option<&str>, level: i32, progression: Option<[i32; 9]>) -> ClassSlotInput {
+        ClassSlotInput {
+            class: ClassLevel { class_id: class_id.into(), subclass_id: subclass_id.map(Into::into), level },
+            progression,
+        }
+    }
+
+    #[test]
+    fn test_single_class_slots() {
+        // Tabellenwerte haben Vorrang
+        let wizard = class("magier", None, 5, Some([4, 3, 2, 0, 0, 0, 0, 0, 0]));
+        assert_eq!(compute_slot_maxima(&[wizard]).slots, [4, 3, 2, 0, 0, 0, 0, 0, 0]);
+
+        // Mystischer Ritter ohne Tabellenspalten: Stufe 7 = 4/2
+        let knight = class("kämpfer", Some("kämpfer_mystischer_ritter"), 7, None);
+        assert_eq!(compute_slot_maxima(&[knight]).slots, [4, 2, 0, 0, 0, 0, 0, 0, 0]);
+        let champion = class("kämpfer", Some("kämpfer_champion"), 7, None);
+        assert_eq!(compute_slot_maxima(&[champion]), SlotMaxima::default());
+
+        let paladin = class("paladin", None, 9, None);
+        assert_eq!(compute_slot_maxima(&[paladin]).slots, [4, 3, 2, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_multiclass_and_pact_magic() {
+        // Magier 3 + Paladin 4 = Zauberwirkerstufe 5, Tabellenwerte zählen dann nicht
+        let classes = [
+            class("magier", None, 3, Some([4, 2, 0, 0, 0, 0, 0, 0, 0])),
+            class("paladin", None, 4, Some([3, 0, 0, 0, 0, 0, 0, 0, 0])),
+            class("hexenmeister", None, 5, None),
+        ];
+        let maxima = compute_slot_maxima(&classes);
+        assert_eq!(maxima.slots, [4, 3, 2, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(maxima.pact, Some(PactMagic { slots: 2, slot_level: 3 }));
+
+        let warlock = compute_slot_maxima(&[class("hexenmeister", None, 11, Some([4, 3, 3, 0, 0, 0, 0, 0, 0]))]);
+        assert_eq!(warlock.slots, [0; 9]);
+        assert_eq!(warlock.pact, Some(PactMagic { slots: 3, slot_level: 5 }));
+    }
+
+    #[test]
+    fn test_slot_override_modifier() {
+        let mut modifiers = Vec::new();
+        set_slot_override(&mut modifiers, "spell_slots:1", Some(5)).unwrap();
+        set_slot_override(&mut modifiers, "spell_slots:1", Some(6)).unwrap();
+        assert_eq!(modifiers.len(), 1);
+        assert_eq!((modifiers[0].modifier_type.clone(), modifiers[0].value), (ModifierType::Override, 6));
+
+        assert!(set_slot_override(&mut modifiers, "spell_slots:10", Some(1)).is_err());
+        assert!(set_slot_override(&mut modifiers, PACT_SLOT_LEVEL_TARGET, Some(6)).is_err());
+
+        set_slot_override(&mut modifiers, "spell_slots:1", None).unwrap();
+        assert!(modifiers.is_empty());
+    }
+}
@@ -1,5 +1,8 @@
 pub const STR_CAPACITY_FACTOR_KG: f64 = 7.5;
 pub const STR_MAX_LIFT_FACTOR_KG: f64 = 15.0;
 
+/// PHB (DE) rechnet 1,5 m pro 5 ft, also 0,3 m pro Fuß.
+pub const METERS_PER_FOOT: f64 = 0.3;
+
 // MagicContainer code removed as it was unused and flagged by compiler.
 // See git history if restore is needed.
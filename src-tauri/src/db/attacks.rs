@@ -0,0 +1,171 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use crate::core::attacks::{derive_attacks, EquippedWeapon, WeaponAttack};
+use crate::core::sheet::derive_sheet;
+use crate::db::sheet::{bonus_from_name, load_sheet_inputs};
+use crate::error::AppResult;
+use crate::types::character::{Character, CharacterItem};
+use crate::types::weapons::{Weapon, WeaponMastery, WeaponProperty};
+
+/// Computes to-hit and damage for every equipped weapon of a character.
+pub fn compute_attacks(conn: &Connection, character: &Character) -> AppResult<Vec<WeaponAttack>> {
+    let inputs = load_sheet_inputs(conn, character)?;
+    let sheet = derive_sheet(character, &inputs);
+    let weapons = load_equipped_weapons(conn, character)?;
+    Ok(derive_attacks(character, &inputs, &sheet, &weapons))
+}
+
+/// Loads all equipped weapons, including magic weapons that reference a base weapon
+/// via `custom_data.base_item_id`.
+pub fn load_equipped_weapons(conn: &Connection, character: &Character) -> AppResult<Vec<EquippedWeapon>> {
+    let mut weapons = Vec::new();
+    for item in character.inventory.iter().filter(|i| i.is_equipped) {
+        let equipped = match load_weapon(conn, &item.item_id)? {
+            Some(weapon) => EquippedWeapon::new(weapon),
+            None => {
+                let Some((name, attack, damage)) = load_magic_weapon(conn, &item.item_id)? else { continue };
+                let Some(base_id) = custom_str(item, "base_item_id") else { continue };
+                let Some(weapon) = load_weapon(conn, &base_id)? else { continue };
+                EquippedWeapon {
+                    display_name: Some(name.clone()),
+                    magic_attack_bonus: attack,
+                    magic_damage_bonus: damage,
+                    magic_source: Some(name),
+                    ..EquippedWeapon::new(weapon)
+                }
+            }
+        };
+        weapons.push(apply_item_flags(equipped, item));
+    }
+    Ok(weapons)
+}
+
+/// Loads a weapon with its properties and mastery from the unified views.
+pub fn load_weapon(conn: &Connection, weapon_id: &str) -> AppResult<Option<Weapon>> {
+    let weapon = conn.query_row(
+        "SELECT w.id, w.name, w.category, w.category_label, w.weapon_subtype, w.mastery_id,
+                w.damage_dice, w.damage_type, w.weight_kg, w.cost_gp, w.data, w.source,
+                wm.id, wm.name, wm.description
+         FROM all_weapons_unified w
+         LEFT JOIN weapon_masteries wm ON wm.id = w.mastery_id
+         WHERE w.id = ?",
+        params![weapon_id],
+        |row| {
+            let data: String = row.get(10)?;
+            let mastery_id: Option<String> = row.get(12)?;
+            let mastery = match mastery_id {
+                Some(id) => Some(WeaponMastery { id, name: row.get(13)?, description: row.get(14)? }),
+                None => None,
+            };
+            Ok(Weapon {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                category: row.get(2)?,
+                category_label: row.get(3)?,
+                weapon_subtype: row.get(4)?,
+                mastery_id: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                damage_dice: row.get(6)?,
+                damage_type: row.get(7)?,
+                weight_kg: row.get(8)?,
+                cost_gp: row.get(9)?,
+                properties: Vec::new(),
+                mastery,
+                data: serde_json::from_str(&data).unwrap_or(Value::Null),
+                source: row.get(11)?,
+            })
+        },
+    ).optional()?;
+
+    let Some(mut weapon) = weapon else { return Ok(None) };
+
+    let mut stmt = conn.prepare(
+        "SELECT wp.id, wp.name, wp.description, wp.has_parameter, wp.parameter_type, wpm.parameter_value
+         FROM weapon_property_mappings_unified wpm
+         JOIN weapon_properties wp ON wp.id = wpm.property_id
+         WHERE wpm.weapon_id = ?",
+    )?;
+    let rows = stmt.query_map(params![weapon_id], |row| {
+        let parameter_value: Option<String> = row.get(5)?;
+        Ok(WeaponProperty {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            has_parameter: row.get::<_, Option<bool>>(3)?.unwrap_or(false),
+            parameter_type: row.get(4)?,
+            parameter_value: parameter_value.and_then(|s| serde_json::from_str(&s).ok()),
+        })
+    })?;
+    // Eigenschaften sind teils deutsch und englisch gemappt; die Variante mit Parameter gewinnt
+    for property in rows {
+        let property = property?;
+        let existing = weapon.properties.iter_mut().find(|p| {
+            p.id == property.id || p.name.to_lowercase() == property.name.to_lowercase()
+        });
+        match existing {
+            Some(p) if p.parameter_value.is_none() => *p = property,
+            Some(_) => {}
+            None => weapon.properties.push(property),
+        }
+    }
+
+    Ok(Some(weapon))
+}
+
+/// Magische Waffe: (Name, Angriffsbonus, Schadensbonus). Die Boni stehen in
+/// `core_mag_weapons`, in `data.bonuses` oder nur im Namen ("WAFFE +1").
+fn load_magic_weapon(conn: &Connection, item_id: &str) -> AppResult<Option<(String, i32, i32)>> {
+    let row: Option<(String, Option<i32>, Option<i32>)> = conn.query_row(
+        "SELECT b.name,
+                COALESCE(w.attack_bonus, json_extract(b.data, '$.bonuses.attack_roll')),
+                COALESCE(w.damage_bonus, json_extract(b.data, '$.bonuses.damage_roll'))
+         FROM all_mag_items_base b
+         LEFT JOIN all_mag_weapons w ON w.item_base_id = b.id
+         WHERE b.id = ?",
+        params![item_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional()?;
+
+    Ok(row.map(|(name, attack, damage)| {
+        let attack = attack.or_else(|| bonus_from_name(&name)).unwrap_or(0);
+        let damage = damage.unwrap_or(attack);
+        (name, attack, damage)
+    }))
+}
+
+/// Griff, Nebenhand und Inventar-Boni aus `custom_data`.
+///
+/// Schlüssel: `two_handed` und `offhand`/`hand: "offhand"` (setzt die Waffentabelle). Boni wie im
+/// Frontend (`getInventoryItemMagicBonus`): `magic_bonus`/`bonus`/`enhancement_bonus` für Angriff und
+/// Schaden, dazu `attack_bonus`/`to_hit_bonus`/`hit_bonus` bzw. `damage_bonus`/`dmg_bonus`/`damage_mod`.
+/// Es zählt jeweils der erste vorhandene Schlüssel; Zahlen dürfen auch als Text ("+1") vorliegen.
+fn apply_item_flags(mut equipped: EquippedWeapon, item: &CharacterItem) -> EquippedWeapon {
+    let Some(data) = item.custom_data.as_ref() else { return equipped };
+    let flag = |key: &str| data.get(key).and_then(Value::as_bool).unwrap_or(false);
+
+    equipped.two_handed_grip = flag("two_handed");
+    equipped.offhand = flag("offhand") || data.get("hand").and_then(Value::as_str) == Some("offhand");
+
+    let shared = custom_number(data, &["magic_bonus", "bonus", "enhancement_bonus"]).unwrap_or(0);
+    equipped.magic_attack_bonus += shared + custom_number(data, &["attack_bonus", "to_hit_bonus", "hit_bonus"]).unwrap_or(0);
+    equipped.magic_damage_bonus += shared + custom_number(data, &["damage_bonus", "dmg_bonus", "damage_mod"]).unwrap_or(0);
+    equipped
+}
+
+/// Erster Schlüssel mit Zahlenwert; Text wie "+1" oder " 2 " wird wie `parseNumber` im Frontend gelesen.
+fn custom_number(data: &Value, keys: &[&str]) -> Option<i32> {
+    keys.iter().find_map(|key| {
+        let value = match data.get(*key)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => {
+                let s = s.trim();
+                s.strip_prefix('+').unwrap_or(s).parse::<f64>().ok()
+            }
+            _ => None,
+        };
+        value.filter(|v| v.is_finite()).map(|v| v as i32)
+    })
+}
+
+fn custom_str(item: &CharacterItem, key: &str) -> Option<String> {
+    item.custom_data.as_ref()?.get(key).and_then(Value::as_str).map(str::to_string)
+}
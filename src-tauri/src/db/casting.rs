@@ -0,0 +1,28 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use rusqlite::Connection;
+use crate::core::casting::{self, CastRequest, CastResult};
+use crate::db::characters::{load_character, save_character};
+use crate::db::sheet::compute_sheet;
+use crate::db::spell_preparation::load_preparation_classes;
+use crate::db::spells::load_spell;
+use crate::error::{AppError, AppResult};
+
+/// Casts a spell: consumes a spell or Pact slot (none for cantrips and rituals),
+/// starts concentration if needed and persists the character.
+pub fn cast_spell(conn: &Connection, character_id: &str, spell_id: &str, request: &CastRequest) -> AppResult<CastResult> {
+    let mut character = load_character(conn, character_id)?;
+    let spell = load_spell(conn, spell_id)?
+        .ok_or_else(|| AppError::InvalidInput(format!("Zauber '{}' nicht gefunden", spell_id)))?;
+    let sheet = compute_sheet(conn, &character)?;
+    // Klassen ohne `vorbereitete_zauber` in der Stufentabelle kennen ihre Zauber nur
+    let requires_preparation = load_preparation_classes(conn, &character)?.iter().any(|c| c.limit.is_some());
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64);
+
+    let result = casting::cast_spell(&mut character, &spell, &sheet, request, requires_preparation, started_at)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    Ok(result)
+}
@@ -0,0 +1,312 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+use crate::core::multiclass::{normalize_classes, rebuild_hit_dice};
+use crate::types::character::{Character, Concentration};
+use crate::db::queries;
+use crate::db::row_sync::atomically;
+use crate::error::{AppError, AppResult};
+
+/// `storage_version` of rows that keep the whole character as JSON in `data`.
+/// They are read through the compatibility path until [`convert_legacy_characters`] ran.
+pub const LEGACY_STORAGE_VERSION: i32 = 1;
+
+/// `storage_version` of rows stored in columns and child tables; `data` only holds the extras.
+/// Rows with version 2 keep name, level and classes in `data` and are converted like legacy rows.
+pub const NORMALIZED_STORAGE_VERSION: i32 = 3;
+
+/// Parts of `Character` stored in child tables or dedicated columns, not in `data`.
+/// `meta.class_id`/`meta.subclass_id` are rebuilt from the first entry of `character_classes`.
+const NORMALIZED_FIELDS: &[&str] = &[
+    "id",
+    "meta.name",
+    "meta.level",
+    "meta.class_id",
+    "meta.subclass_id",
+    "meta.classes",
+    "attributes",
+    "proficiencies",
+    "modifiers",
+    "feats",
+    "inventory",
+    "spells",
+    "conditions",
+    "concentration",
+    "health.death_saves",
+];
+
+/// Integer fields with a column in `characters`: JSON path in `Character` -> column.
+fn stat_columns() -> Vec<(String, String)> {
+    let mut columns: Vec<(String, String)> = [
+        ("attributes.str", "attr_str"),
+        ("attributes.dex", "attr_dex"),
+        ("attributes.con", "attr_con"),
+        ("attributes.int", "attr_int"),
+        ("attributes.wis", "attr_wis"),
+        ("attributes.cha", "attr_cha"),
+        ("health.current", "hp_current"),
+        ("health.max", "hp_max"),
+        ("health.temp", "hp_temp"),
+        ("health.hit_dice_max", "hit_dice_max"),
+        ("health.hit_dice_used", "hit_dice_used"),
+        ("health.death_saves.successes", "death_saves_successes"),
+        ("health.death_saves.failures", "death_saves_failures"),
+        ("meta.currency_copper", "currency_cp"),
+        ("meta.currency_silver", "currency_sp"),
+        ("meta.currency_electrum", "currency_ep"),
+        ("meta.currency_gold", "currency_gp"),
+        ("meta.currency_platinum", "currency_pp"),
+        ("meta.pact_slots_used", "pact_slots_used"),
+    ]
+    .iter()
+    .map(|(path, column)| (path.to_string(), column.to_string()))
+    .collect();
+    for level in 1..=9 {
+        for column in [format!("spell_slots_{}", level), format!("spell_slots_used_{}", level)] {
+            columns.push((format!("meta.{}", column), column));
+        }
+    }
+    columns
+}
+
+fn object(value: &mut Value) -> &mut Map<String, Value> {
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    match value {
+        Value::Object(map) => map,
+        _ => unreachable!(),
+    }
+}
+
+/// Sets a dotted path ("health.current"), creating missing objects on the way.
+fn set_path(value: &mut Value, path: &str, new_value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => set_path(object(value).entry(head).or_insert(Value::Null), rest, new_value),
+        None => {
+            object(value).insert(path.to_string(), new_value);
+        }
+    }
+}
+
+fn remove_path(value: &mut Value, path: &str) {
+    let Value::Object(map) = value else { return };
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            if let Some(child) = map.get_mut(head) {
+                remove_path(child, rest);
+            }
+        }
+        None => {
+            map.remove(path);
+        }
+    }
+}
+
+/// Everything without a normalized home (meta, appearance, spellcasting, hit dice pools, ...).
+fn character_extras(character: &Character) -> AppResult<Value> {
+    let mut value = serde_json::to_value(character)?;
+    for path in NORMALIZED_FIELDS {
+        remove_path(&mut value, path);
+    }
+    for (path, _) in stat_columns() {
+        remove_path(&mut value, &path);
+    }
+    Ok(value)
+}
+
+/// Loads a character by ID.
+///
+/// Normalized rows are assembled from the `characters` columns, the child tables and the
+/// extras in `data`. Older blobs without per-class levels are migrated to `meta.classes`.
+pub fn load_character(conn: &Connection, id: &str) -> AppResult<Character> {
+    let (data, storage_version): (String, i32) = conn
+        .query_row(
+            "SELECT data, storage_version FROM characters WHERE id = ?",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?
+        .ok_or_else(|| AppError::CharacterNotFound(id.to_string()))?;
+
+    let mut character = if storage_version == LEGACY_STORAGE_VERSION {
+        load_legacy_character(conn, id, &data)?
+    } else {
+        load_normalized_character(conn, id, &data, storage_version)?
+    };
+    normalize_classes(&mut character.meta);
+    if character.meta.classes.len() > 1 && character.health.hit_dice.is_empty() {
+        rebuild_hit_dice_pools(conn, &mut character)?;
+    }
+    Ok(character)
+}
+
+fn load_normalized_character(conn: &Connection, id: &str, extras: &str, storage_version: i32) -> AppResult<Character> {
+    let mut value: Value = serde_json::from_str(extras)?;
+    // Ältere normalisierte Zeilen haben Name, Stufe und Klassen noch in `data`
+    if storage_version >= NORMALIZED_STORAGE_VERSION {
+        let (name, level): (Option<String>, Option<i32>) =
+            conn.query_row("SELECT name, level FROM characters WHERE id = ?", params![id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        set_path(&mut value, "meta.name", Value::String(name.unwrap_or_default()));
+        set_path(&mut value, "meta.level", level.unwrap_or(1).into());
+        set_path(&mut value, "meta.classes", serde_json::to_value(crate::db::stats::load_classes(conn, id)?)?);
+    }
+    let stats = stat_columns();
+    let sql = format!(
+        "SELECT {}, concentration_spell_id, concentration_spell_name, concentration_slot_level,
+                concentration_duration, concentration_started_at
+         FROM characters WHERE id = ?",
+        stats.iter().map(|(_, column)| column.as_str()).collect::<Vec<_>>().join(", ")
+    );
+    let (numbers, concentration) = conn.query_row(&sql, params![id], |row| {
+        let numbers = (0..stats.len()).map(|i| row.get::<_, i64>(i)).collect::<Result<Vec<_>, _>>()?;
+        let n = stats.len();
+        let concentration = match row.get::<_, Option<String>>(n)? {
+            Some(spell_id) => Some(Concentration {
+                spell_name: row.get::<_, Option<String>>(n + 1)?.unwrap_or_else(|| spell_id.clone()),
+                spell_id,
+                slot_level: row.get(n + 2)?,
+                duration: row.get(n + 3)?,
+                started_at: row.get(n + 4)?,
+            }),
+            None => None,
+        };
+        Ok((numbers, concentration))
+    })?;
+
+    set_path(&mut value, "id", Value::String(id.to_string()));
+    for ((path, _), number) in stats.iter().zip(numbers) {
+        set_path(&mut value, path, number.into());
+    }
+    set_path(&mut value, "concentration", serde_json::to_value(concentration)?);
+
+    let (proficiencies, feats) = crate::db::features::load_features(conn, id)?;
+    set_path(&mut value, "proficiencies", serde_json::to_value(proficiencies)?);
+    set_path(&mut value, "feats", serde_json::to_value(feats)?);
+    set_path(&mut value, "modifiers", serde_json::to_value(crate::db::modifiers::load_modifiers(conn, id)?)?);
+    set_path(&mut value, "inventory", serde_json::to_value(crate::db::inventory::load_inventory(conn, id)?)?);
+    set_path(&mut value, "spells", serde_json::to_value(crate::db::spells::load_character_spells(conn, id)?)?);
+    set_path(&mut value, "conditions", serde_json::to_value(crate::db::conditions::load_conditions(conn, id)?)?);
+
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Compatibility read path for rows that still hold the whole character as JSON.
+/// Only the inventory comes from `character_inventory`. To be removed together with
+/// [`LEGACY_STORAGE_VERSION`] once no database contains such rows.
+fn load_legacy_character(conn: &Connection, id: &str, data: &str) -> AppResult<Character> {
+    let mut character: Character = serde_json::from_str(data)?;
+    // Die Zeilen-ID gilt, auch wenn das JSON eine andere enthält
+    if let Ok(uuid) = Uuid::parse_str(id) {
+        character.id = uuid;
+    }
+    character.inventory = crate::db::inventory::load_inventory(conn, id)?;
+    Ok(character)
+}
+
+/// Loads all characters, most recently updated first.
+pub fn list_characters(conn: &Connection) -> AppResult<Vec<Character>> {
+    let mut stmt = conn.prepare(queries::SELECT_ALL_CHARACTER_IDS)?;
+    let ids = stmt.query_map([], |row| row.get::<_, String>(0))?.collect::<Result<Vec<_>, _>>()?;
+    ids.iter().map(|id| load_character(conn, id)).collect()
+}
+
+/// One-shot converter: moves legacy rows and rows with name, level and classes still in `data`
+/// to the normalized format and shrinks `data` to the extras. `updated_at` is kept. Rows whose
+/// JSON cannot be read stay as they are (reported by the schema validation). Returns the number
+/// of converted characters.
+pub fn convert_legacy_characters(conn: &Connection) -> AppResult<usize> {
+    let mut stmt = conn.prepare("SELECT id, updated_at FROM characters WHERE storage_version < ?")?;
+    let rows = stmt
+        .query_map(params![NORMALIZED_STORAGE_VERSION], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut converted = 0;
+    for (id, updated_at) in rows {
+        let character = match load_character(conn, &id) {
+            Ok(character) => character,
+            Err(e) => {
+                println!("Charakter {} bleibt im alten Format: {}", id, e);
+                continue;
+            }
+        };
+        atomically(conn, "convert_character", || {
+            save_character(conn, &character)?;
+            conn.execute("UPDATE characters SET updated_at = ? WHERE id = ?", params![updated_at, id])?;
+            Ok(())
+        })?;
+        converted += 1;
+    }
+    Ok(converted)
+}
+
+/// Splits the hit dice into one pool per die size from the character's class levels.
+pub fn rebuild_hit_dice_pools(conn: &Connection, character: &mut Character) -> AppResult<()> {
+    let mut dice = Vec::new();
+    for class in &character.meta.classes {
+        dice.push((crate::db::rest::class_hit_die(conn, &class.class_id)?, class.level));
+    }
+    rebuild_hit_dice(&mut character.health, &dice);
+    Ok(())
+}
+
+/// Syncs all normalized tables with the character struct (row-level diffs).
+fn sync_normalized(conn: &Connection, character: &Character) -> AppResult<()> {
+    // Sync normalized inventory
+    crate::db::inventory::sync_inventory(conn, character)?;
+    // Sync normalized spells
+    crate::db::spells::sync_spells(conn, character)?;
+    // Sync normalized stats (attributes & health)
+    crate::db::stats::sync_stats(conn, character)?;
+    // Sync normalized features & proficiencies
+    crate::db::features::sync_features(conn, character)?;
+    // Sync normalized modifiers
+    crate::db::modifiers::sync_modifiers(conn, character)?;
+    // Sync normalized conditions
+    crate::db::conditions::sync_conditions(conn, character)?;
+    Ok(())
+}
+
+/// Persists a character: syncs all normalized columns and tables and stores the extras in `data`.
+/// Legacy rows are converted on the way.
+///
+/// Everything happens in one savepoint, so a failure leaves the stored character untouched.
+/// Only changed rows are written; unchanged rows keep their ids and `created_at`.
+pub fn save_character(conn: &Connection, character: &Character) -> AppResult<()> {
+    atomically(conn, "save_character", || {
+        let id = character.id.to_string();
+        let data = serde_json::to_string(&character_extras(character)?)?;
+
+        let rows_affected = conn.execute(queries::UPDATE_CHARACTER, params![data, NORMALIZED_STORAGE_VERSION, id])?;
+        if rows_affected == 0 {
+            return Err(AppError::CharacterNotFound(id));
+        }
+        sync_normalized(conn, character)
+    })
+}
+
+/// Inserts a new character and its normalized rows in one savepoint.
+pub fn insert_character(conn: &Connection, character: &Character) -> AppResult<()> {
+    atomically(conn, "insert_character", || {
+        let data = serde_json::to_string(&character_extras(character)?)?;
+        conn.execute(queries::INSERT_CHARACTER, params![character.id.to_string(), data, NORMALIZED_STORAGE_VERSION])?;
+        sync_normalized(conn, character)
+    })
+}
+
+/// Compendium class ids for a character's class id.
+///
+/// Characters may store ASCII ids ("kaempfer") while `core_classes` uses
+/// umlauts ("kämpfer"); both spellings are returned.
+pub fn class_id_candidates(class_id: &str) -> Vec<String> {
+    let lower = class_id.trim().to_lowercase();
+    let umlauts = lower.replace("ae", "ä").replace("oe", "ö").replace("ue", "ü");
+    let ascii = lower.replace('ä', "ae").replace('ö', "oe").replace('ü', "ue");
+    let mut ids = vec![lower];
+    for id in [umlauts, ascii] {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
@@ -0,0 +1,127 @@
+use rusqlite::{Connection, DatabaseName, OpenFlags, OptionalExtension};
+use std::path::Path;
+use crate::db::migrations;
+use crate::db::row_sync::atomically;
+use crate::error::{AppError, AppResult};
+
+/// Marks a user database into which the legacy data has already been copied.
+const LEGACY_IMPORT_SETTING: &str = "legacy_user_data_imported";
+
+/// `file:` URI that opens `path` read-only; characters with a meaning in URIs are escaped.
+fn read_only_uri(path: &Path) -> String {
+    let escaped: String = path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .chars()
+        .map(|c| match c {
+            '%' => "%25".to_string(),
+            '?' => "%3f".to_string(),
+            '#' => "%23".to_string(),
+            c => c.to_string(),
+        })
+        .collect();
+    format!("file:{}?mode=ro", escaped)
+}
+
+/// Brings a writable compendium (development checkout) up to date before it is attached.
+/// The bundled compendium is migrated when it is built; if it is read-only nothing happens.
+pub fn migrate_compendium(path: &Path) -> AppResult<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    let pending = migrations::pending_migrations(&conn, migrations::MIGRATIONS).map_err(AppError::Other)?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    if conn.is_readonly(DatabaseName::Main)? {
+        println!(
+            "Warnung: Kompendium {} ist schreibgeschützt, {} Migration(en) fehlen",
+            path.to_string_lossy(),
+            pending.len()
+        );
+        return Ok(());
+    }
+    migrations::run_migrations(&conn).map_err(AppError::Other)
+}
+
+/// Attaches the compendium read-only as `compendium` and creates the views and triggers that
+/// combine it with the user tables as TEMP objects. Needed on every connection.
+///
+/// Unqualified table names resolve to the user database first, so `core_*` tables are read
+/// from the compendium and `custom_*` tables from the user database.
+pub fn attach_compendium(conn: &Connection, path: &Path) -> AppResult<()> {
+    conn.execute("ATTACH DATABASE ?1 AS compendium", [read_only_uri(path)])?;
+
+    // Die Definitionen stammen aus dem Kompendium, damit sie zu dessen Tabellen passen
+    let objects: Vec<(String, String, String)> = conn
+        .prepare("SELECT type, name, sql FROM compendium.sqlite_master WHERE type IN ('view', 'trigger') AND sql IS NOT NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+    for (kind, name, sql) in objects {
+        let temp_sql = match kind.as_str() {
+            "view" => sql.replacen("CREATE VIEW", "CREATE TEMP VIEW", 1),
+            _ if migrations::CROSS_SCHEMA_TRIGGERS.contains(&name.as_str()) => {
+                sql.replacen("CREATE TRIGGER", "CREATE TEMP TRIGGER", 1)
+            }
+            _ => continue,
+        };
+        conn.execute_batch(&temp_sql)?;
+    }
+    Ok(())
+}
+
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> AppResult<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info(\"{}\")", schema, table))?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(1))?.collect::<Result<_, _>>()?;
+    Ok(columns)
+}
+
+/// Copies characters, homebrew and settings that older versions stored in the compendium file
+/// into the user database; returns the number of characters copied.
+///
+/// Runs once per installation: the caller records the import in the location settings. The
+/// marker in `settings` covers user databases filled before that record existed.
+pub fn import_legacy_user_data(conn: &Connection) -> AppResult<usize> {
+    let imported: Option<String> = conn
+        .query_row("SELECT value FROM main.settings WHERE key = ?", [LEGACY_IMPORT_SETTING], |row| row.get(0))
+        .optional()?;
+    if imported.is_some() {
+        return Ok(0);
+    }
+
+    atomically(conn, "legacy_import", || {
+        let tables: Vec<String> = conn
+            .prepare("SELECT name FROM compendium.sqlite_master WHERE type = 'table'")?
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<_, _>>()?;
+
+        let mut characters = 0;
+        // spell_mechanics wird beim Start ohnehin neu aufgebaut
+        for table in tables.iter().filter(|t| migrations::is_user_table(t) && *t != "spell_mechanics") {
+            let legacy_columns = table_columns(conn, "compendium", table)?;
+            let columns: Vec<String> = table_columns(conn, "main", table)?
+                .into_iter()
+                .filter(|c| legacy_columns.contains(c))
+                .map(|c| format!("\"{}\"", c))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+            let copied = conn.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO main.\"{table}\" ({columns}) SELECT {columns} FROM compendium.\"{table}\"",
+                    table = table,
+                    columns = columns.join(", ")
+                ),
+                [],
+            )?;
+            if table == "characters" {
+                characters = copied;
+            }
+        }
+
+        conn.execute(
+            "INSERT OR REPLACE INTO main.settings (key, value) VALUES (?, '1')",
+            [LEGACY_IMPORT_SETTING],
+        )?;
+        Ok(characters)
+    })
+}
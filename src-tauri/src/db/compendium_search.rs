@@ -0,0 +1,509 @@
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::core::multiclass::same_class;
+use crate::error::{AppError, AppResult};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompendiumEntity {
+    Spells,
+    Weapons,
+    Armor,
+    Items,
+    MagicItems,
+    Feats,
+    Backgrounds,
+}
+
+/// Typed filters. A filter that doesn't apply to the searched entity is rejected.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct CompendiumFilters {
+    /// Spells: exact levels (0 = cantrip)
+    pub spell_levels: Vec<i32>,
+    /// Spells: school prefix, e.g. "Hervorrufung"
+    pub schools: Vec<String>,
+    /// Spells: class ID or name, e.g. "waldläufer"
+    pub class: Option<String>,
+    pub concentration: Option<bool>,
+    pub ritual: Option<bool>,
+    /// Weapons (`simple_melee`, ...), armor, items, magic items and feats
+    pub categories: Vec<String>,
+    /// Weapons: property ID, e.g. "finesse"
+    pub weapon_property: Option<String>,
+    /// Magic items: e.g. "selten"
+    pub rarities: Vec<String>,
+    pub requires_attunement: Option<bool>,
+    pub cost_min: Option<f64>,
+    pub cost_max: Option<f64>,
+    pub weight_min: Option<f64>,
+    pub weight_max: Option<f64>,
+    /// "core", "override" or "homebrew"
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CompendiumSort {
+    pub field: String,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CompendiumPage {
+    /// Zero-based page index
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default = "default_page_size")]
+    pub page_size: usize,
+}
+
+fn default_page_size() -> usize {
+    DEFAULT_PAGE_SIZE
+}
+
+impl Default for CompendiumPage {
+    fn default() -> Self {
+        Self { page: 0, page_size: DEFAULT_PAGE_SIZE }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompendiumSearchResult {
+    pub entity: CompendiumEntity,
+    /// Number of matches over all pages
+    pub total: i64,
+    pub page: usize,
+    pub page_size: usize,
+    /// One JSON object per row with the view's columns (`data` parsed)
+    pub items: Vec<Value>,
+}
+
+const RARITY_ORDER: &str = "CASE e.rarity WHEN 'gewöhnlich' THEN 0 WHEN 'ungewöhnlich' THEN 1 WHEN 'selten' THEN 2
+    WHEN 'sehr selten' THEN 3 WHEN 'legendär' THEN 4 WHEN 'artefakt' THEN 5 ELSE 6 END";
+
+/// View, joins, columns and sortable fields of an entity.
+struct EntitySpec {
+    from: &'static str,
+    columns: &'static str,
+    default_sort: &'static str,
+    sorts: &'static [(&'static str, &'static str)],
+}
+
+impl CompendiumEntity {
+    fn spec(self) -> EntitySpec {
+        match self {
+            CompendiumEntity::Spells => EntitySpec {
+                from: "all_spells e LEFT JOIN spell_mechanics m ON m.spell_id = e.id",
+                columns: "e.id, e.name, e.level, e.school, e.casting_time, e.range, e.components, e.duration,
+                          e.concentration, e.ritual, e.classes, e.source",
+                default_sort: "e.level",
+                sorts: &[
+                    ("name", "e.name"),
+                    ("level", "e.level"),
+                    ("school", "e.school"),
+                    ("range", "m.range_ft"),
+                    ("duration", "m.duration_seconds"),
+                    ("casting_time", "m.casting_time_seconds"),
+                    ("material_cost", "m.material_cost_gp"),
+                ],
+            },
+            CompendiumEntity::Weapons => EntitySpec {
+                from: "all_weapons_unified e",
+                columns: "e.id, e.name, e.category, e.category_label, e.weapon_subtype, e.mastery_id,
+                          e.damage_dice, e.damage_type, e.weight_kg, e.cost_gp, e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[("name", "e.name"), ("category", "e.category"), ("cost", "e.cost_gp"), ("weight", "e.weight_kg")],
+            },
+            CompendiumEntity::Armor => EntitySpec {
+                from: "all_armors e",
+                columns: "e.id, e.name, e.category, e.category_label, e.base_ac, e.ac_bonus, e.ac_formula,
+                          e.strength_requirement, e.stealth_disadvantage, e.weight_kg, e.cost_gp, e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[
+                    ("name", "e.name"),
+                    ("category", "e.category"),
+                    ("ac", "COALESCE(e.base_ac, e.ac_bonus)"),
+                    ("cost", "e.cost_gp"),
+                    ("weight", "e.weight_kg"),
+                ],
+            },
+            CompendiumEntity::Items => EntitySpec {
+                from: "all_items e",
+                columns: "e.id, e.name, e.description, e.category, e.cost_gp, e.weight_kg, e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[("name", "e.name"), ("category", "e.category"), ("cost", "e.cost_gp"), ("weight", "e.weight_kg")],
+            },
+            CompendiumEntity::MagicItems => EntitySpec {
+                from: "all_mag_items_base e",
+                columns: "e.id, e.name, e.rarity, e.category, e.requires_attunement, e.source_book, e.source_page,
+                          e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[("name", "e.name"), ("category", "e.category"), ("rarity", RARITY_ORDER)],
+            },
+            CompendiumEntity::Feats => EntitySpec {
+                from: "all_feats e",
+                columns: "e.id, e.name, e.category, e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[("name", "e.name"), ("category", "e.category")],
+            },
+            CompendiumEntity::Backgrounds => EntitySpec {
+                from: "all_backgrounds e",
+                columns: "e.id, e.name, e.data, e.source",
+                default_sort: "e.name",
+                sorts: &[("name", "e.name")],
+            },
+        }
+    }
+
+    fn has_cost_and_weight(self) -> bool {
+        matches!(self, CompendiumEntity::Weapons | CompendiumEntity::Armor | CompendiumEntity::Items)
+    }
+
+    fn has_category(self) -> bool {
+        !matches!(self, CompendiumEntity::Spells | CompendiumEntity::Backgrounds)
+    }
+}
+
+/// `LIKE` in SQLite ignores case only for ASCII, so umlauts are matched in all spellings
+/// (names are stored as "FEUERBALL", classes as "Waldläufer").
+fn case_variants(term: &str) -> Vec<String> {
+    let lower = term.to_lowercase();
+    let capitalized: String = lower.chars().take(1).flat_map(char::to_uppercase).chain(lower.chars().skip(1)).collect();
+    let mut variants = vec![term.to_string(), lower, term.to_uppercase(), capitalized];
+    variants.sort();
+    variants.dedup();
+    variants
+}
+
+/// Escapes the `LIKE` wildcards `%` and `_` in user input; used with `ESCAPE '\'`.
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
+
+/// Query builder: collects `WHERE` conditions with their parameters.
+#[derive(Default)]
+struct Conditions {
+    sql: Vec<String>,
+    values: Vec<SqlValue>,
+}
+
+impl Conditions {
+    fn push(&mut self, sql: impl Into<String>, values: impl IntoIterator<Item = SqlValue>) {
+        self.sql.push(sql.into());
+        self.values.extend(values);
+    }
+
+    /// `expr LIKE pattern` for all case variants of `term`. Wildcards in `term` match literally.
+    fn like(&mut self, expr: &str, term: &str, pattern: impl Fn(&str) -> String) {
+        let variants = case_variants(term);
+        let sql = vec![format!("{} LIKE ? ESCAPE '\\'", expr); variants.len()].join(" OR ");
+        self.push(format!("({})", sql), variants.iter().map(|v| SqlValue::Text(pattern(&escape_like(v)))));
+    }
+
+    fn one_of<T: Into<SqlValue> + Clone>(&mut self, expr: &str, values: &[T]) {
+        if values.is_empty() {
+            return;
+        }
+        let sql = format!("{} IN ({})", expr, vec!["?"; values.len()].join(", "));
+        self.push(sql, values.iter().cloned().map(Into::into));
+    }
+
+    fn where_clause(&self) -> String {
+        if self.sql.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", self.sql.join(" AND "))
+        }
+    }
+}
+
+fn unsupported(entity: CompendiumEntity, filter: &str) -> AppError {
+    AppError::InvalidInput(format!("Filter '{}' gibt es für {:?} nicht", filter, entity))
+}
+
+/// Display name of a class as used in `all_spells.classes` ("Waldläufer").
+fn class_name(conn: &Connection, class: &str) -> AppResult<String> {
+    let mut stmt = conn.prepare("SELECT id, name FROM all_classes")?;
+    let classes = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(classes
+        .into_iter()
+        .find(|(id, name)| same_class(id, class) || same_class(name, class))
+        .map_or_else(|| class.to_string(), |(_, name)| name))
+}
+
+fn build_conditions(
+    conn: &Connection,
+    entity: CompendiumEntity,
+    query: Option<&str>,
+    filters: &CompendiumFilters,
+) -> AppResult<Conditions> {
+    let mut c = Conditions::default();
+    let is_spell = entity == CompendiumEntity::Spells;
+
+    if let Some(query) = query.map(str::trim).filter(|q| !q.is_empty()) {
+        c.like("e.name", query, |v| format!("%{}%", v));
+    }
+    if let Some(source) = &filters.source {
+        c.push("e.source = ?", [SqlValue::Text(source.clone())]);
+    }
+
+    let spell_filters = [
+        ("spell_levels", !filters.spell_levels.is_empty()),
+        ("schools", !filters.schools.is_empty()),
+        ("class", filters.class.is_some()),
+        ("concentration", filters.concentration.is_some()),
+        ("ritual", filters.ritual.is_some()),
+    ];
+    if let Some((name, _)) = spell_filters.iter().find(|(_, set)| *set && !is_spell) {
+        return Err(unsupported(entity, name));
+    }
+    c.one_of("e.level", &filters.spell_levels);
+    if !filters.schools.is_empty() {
+        let sql = vec!["e.school LIKE ? ESCAPE '\\'"; filters.schools.len()].join(" OR ");
+        c.push(format!("({})", sql), filters.schools.iter().map(|s| SqlValue::Text(format!("{}%", escape_like(s)))));
+    }
+    if let Some(class) = &filters.class {
+        c.like("e.classes", &class_name(conn, class)?, |v| format!("%{}%", v));
+    }
+    if let Some(concentration) = filters.concentration {
+        c.push("e.concentration = ?", [SqlValue::Integer(concentration.into())]);
+    }
+    if let Some(ritual) = filters.ritual {
+        c.push("e.ritual = ?", [SqlValue::Integer(ritual.into())]);
+    }
+
+    if !filters.categories.is_empty() {
+        if !entity.has_category() {
+            return Err(unsupported(entity, "categories"));
+        }
+        c.one_of("e.category", &filters.categories);
+    }
+    if let Some(property) = &filters.weapon_property {
+        if entity != CompendiumEntity::Weapons {
+            return Err(unsupported(entity, "weapon_property"));
+        }
+        c.push(
+            "EXISTS (SELECT 1 FROM weapon_property_mappings_unified p WHERE p.weapon_id = e.id AND p.property_id = ?)",
+            [SqlValue::Text(property.clone())],
+        );
+    }
+
+    if entity != CompendiumEntity::MagicItems {
+        if !filters.rarities.is_empty() {
+            return Err(unsupported(entity, "rarities"));
+        }
+        if filters.requires_attunement.is_some() {
+            return Err(unsupported(entity, "requires_attunement"));
+        }
+    }
+    c.one_of("e.rarity", &filters.rarities);
+    if let Some(attunement) = filters.requires_attunement {
+        c.push("e.requires_attunement = ?", [SqlValue::Integer(attunement.into())]);
+    }
+
+    let ranges = [
+        ("cost_min", filters.cost_min, "e.cost_gp >= ?"),
+        ("cost_max", filters.cost_max, "e.cost_gp <= ?"),
+        ("weight_min", filters.weight_min, "e.weight_kg >= ?"),
+        ("weight_max", filters.weight_max, "e.weight_kg <= ?"),
+    ];
+    for (name, value, sql) in ranges {
+        if let Some(value) = value {
+            if !entity.has_cost_and_weight() {
+                return Err(unsupported(entity, name));
+            }
+            c.push(sql, [SqlValue::Real(value)]);
+        }
+    }
+    Ok(c)
+}
+
+fn row_to_json(row: &rusqlite::Row, names: &[String]) -> rusqlite::Result<Value> {
+    let mut object = Map::new();
+    for (i, name) in names.iter().enumerate() {
+        let value = match row.get_ref(i)? {
+            ValueRef::Null => Value::Null,
+            ValueRef::Integer(n) => Value::from(n),
+            ValueRef::Real(f) => Value::from(f),
+            ValueRef::Text(t) => {
+                let text = String::from_utf8_lossy(t);
+                if name == "data" {
+                    serde_json::from_str(&text).unwrap_or(Value::Null)
+                } else {
+                    Value::from(text.into_owned())
+                }
+            }
+            ValueRef::Blob(_) => Value::Null,
+        };
+        object.insert(name.clone(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+/// Searches one compendium entity by name with typed filters, sorting and paging.
+///
+/// # Errors
+/// Returns `AppError::InvalidInput` for an unknown sort field, a filter that doesn't apply
+/// to the entity or a page beyond the addressable range
+pub fn search_compendium(
+    conn: &Connection,
+    entity: CompendiumEntity,
+    query: Option<&str>,
+    filters: &CompendiumFilters,
+    sort: Option<&CompendiumSort>,
+    page: &CompendiumPage,
+) -> AppResult<CompendiumSearchResult> {
+    let spec = entity.spec();
+    let conditions = build_conditions(conn, entity, query, filters)?;
+    let where_clause = conditions.where_clause();
+
+    let order = match sort {
+        Some(sort) => {
+            let expr = spec.sorts.iter()
+                .find(|(field, _)| *field == sort.field)
+                .map(|(_, expr)| *expr)
+                .ok_or_else(|| AppError::InvalidInput(format!("Sortierung nach '{}' gibt es für {:?} nicht", sort.field, entity)))?;
+            // Ohne Wert (z.B. Reichweite "Besonders") immer ans Ende
+            format!("({}) IS NULL, {} {}", expr, expr, if sort.descending { "DESC" } else { "ASC" })
+        }
+        None => spec.default_sort.to_string(),
+    };
+
+    let total: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM {} {}", spec.from, where_clause),
+        params_from_iter(conditions.values.iter()),
+        |row| row.get(0),
+    )?;
+
+    let page_size = page.page_size.clamp(1, MAX_PAGE_SIZE);
+    let offset = page.page
+        .checked_mul(page_size)
+        .filter(|offset| i64::try_from(*offset).is_ok())
+        .ok_or_else(|| AppError::InvalidInput(format!("Seite {} liegt außerhalb des gültigen Bereichs", page.page)))?;
+    let sql = format!(
+        "SELECT {} FROM {} {} ORDER BY {}, e.name, e.id LIMIT {} OFFSET {}",
+        spec.columns, spec.from, where_clause, order, page_size, offset
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let names: Vec<String> = stmt.column_names().into_iter().map(str::to_string).collect();
+    let items = stmt
+        .query_map(params_from_iter(conditions.values.iter()), |row| row_to_json(row, &names))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(CompendiumSearchResult { entity, total, page: page.page, page_size, items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE all_spells (
+                id TEXT PRIMARY KEY, name TEXT, level INTEGER, school TEXT, casting_time TEXT, range TEXT,
+                components TEXT, duration TEXT, concentration INTEGER, ritual INTEGER, classes TEXT, source TEXT
+             );
+             CREATE TABLE spell_mechanics (
+                spell_id TEXT PRIMARY KEY, range_ft INTEGER, duration_seconds INTEGER,
+                casting_time_seconds INTEGER, material_cost_gp REAL
+             );
+             CREATE TABLE all_classes (id TEXT PRIMARY KEY, name TEXT);
+             INSERT INTO all_classes VALUES ('magier', 'Magier'), ('waldlaeufer', 'Waldläufer');
+             INSERT INTO all_spells (id, name, level, school, concentration, ritual, classes, source) VALUES
+                ('feuerpfeil', 'Feuerpfeil', 0, 'Hervorrufung', 0, 0, 'Magier', 'core'),
+                ('feuerball', 'Feuerball', 3, 'Hervorrufung', 0, 0, 'Magier', 'core'),
+                ('fluss_50', 'Fluss 50%', 2, 'Verwandlung', 1, 0, 'Waldläufer', 'homebrew'),
+                ('fluss_500', 'Fluss 500', 2, 'Verwandlung', 1, 0, 'Waldläufer', 'homebrew'),
+                ('schild', 'Schild', 1, 'Bannmagie', 0, 0, 'Magier', 'core');
+             INSERT INTO spell_mechanics VALUES
+                ('feuerpfeil', 120, 0, 6, NULL), ('feuerball', 150, 0, 6, NULL), ('schild', 0, 6, 0, NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn ids(result: &CompendiumSearchResult) -> Vec<&str> {
+        result.items.iter().map(|item| item["id"].as_str().unwrap()).collect()
+    }
+
+    fn search(
+        conn: &Connection,
+        query: Option<&str>,
+        filters: &CompendiumFilters,
+        sort: Option<&CompendiumSort>,
+        page: &CompendiumPage,
+    ) -> AppResult<CompendiumSearchResult> {
+        search_compendium(conn, CompendiumEntity::Spells, query, filters, sort, page)
+    }
+
+    #[test]
+    fn test_filters() {
+        let conn = test_db();
+        let page = CompendiumPage::default();
+
+        let filters = CompendiumFilters { class: Some("waldläufer".into()), concentration: Some(true), ..Default::default() };
+        assert_eq!(ids(&search(&conn, None, &filters, None, &page).unwrap()), vec!["fluss_50", "fluss_500"]);
+
+        let filters = CompendiumFilters { schools: vec!["hervor".into()], spell_levels: vec![3], ..Default::default() };
+        assert_eq!(ids(&search(&conn, None, &filters, None, &page).unwrap()), vec!["feuerball"]);
+
+        // Filter anderer Kategorien werden abgelehnt, bevor die Abfrage läuft
+        let filters = CompendiumFilters { rarities: vec!["selten".into()], ..Default::default() };
+        assert!(matches!(search(&conn, None, &filters, None, &page), Err(AppError::InvalidInput(_))));
+        let filters = CompendiumFilters { cost_max: Some(10.0), ..Default::default() };
+        assert!(matches!(search(&conn, None, &filters, None, &page), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_like_wildcards_match_literally() {
+        let conn = test_db();
+        let page = CompendiumPage::default();
+        let none = CompendiumFilters::default();
+
+        assert_eq!(ids(&search(&conn, Some("50%"), &none, None, &page).unwrap()), vec!["fluss_50"]);
+        assert!(search(&conn, Some("_"), &none, None, &page).unwrap().items.is_empty());
+        assert_eq!(search(&conn, Some("FEUER"), &none, None, &page).unwrap().total, 2);
+    }
+
+    #[test]
+    fn test_sorting() {
+        let conn = test_db();
+        let page = CompendiumPage::default();
+        let none = CompendiumFilters::default();
+
+        let by_range = CompendiumSort { field: "range".into(), descending: true };
+        // Ohne Wert (keine Mechanik-Zeile) ans Ende, danach nach Name
+        assert_eq!(
+            ids(&search(&conn, None, &none, Some(&by_range), &page).unwrap()),
+            vec!["feuerball", "feuerpfeil", "schild", "fluss_50", "fluss_500"]
+        );
+
+        let unknown = CompendiumSort { field: "rarity".into(), descending: false };
+        assert!(matches!(search(&conn, None, &none, Some(&unknown), &page), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_paging() {
+        let conn = test_db();
+        let none = CompendiumFilters::default();
+
+        let second = search(&conn, None, &none, None, &CompendiumPage { page: 1, page_size: 2 }).unwrap();
+        assert_eq!(second.total, 5);
+        assert_eq!(ids(&second), vec!["fluss_50", "fluss_500"]);
+
+        let past_end = search(&conn, None, &none, None, &CompendiumPage { page: 9, page_size: 2 }).unwrap();
+        assert!(past_end.items.is_empty());
+
+        let overflow = search(&conn, None, &none, None, &CompendiumPage { page: usize::MAX, page_size: 50 });
+        assert!(matches!(overflow, Err(AppError::InvalidInput(_))));
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use rusqlite::Connection;
+use crate::core::concentration::{self, ConcentrationChange, ConcentrationResult};
+use crate::db::characters::{load_character, save_character};
+use crate::db::spells::load_spell;
+use crate::error::{AppError, AppResult};
+
+/// Starts concentrating on a spell, ending any previous concentration, and persists the character.
+pub fn start_concentration(
+    conn: &Connection,
+    character_id: &str,
+    spell_id: &str,
+    slot_level: Option<i32>,
+) -> AppResult<ConcentrationChange> {
+    let mut character = load_character(conn, character_id)?;
+    let spell = load_spell(conn, spell_id)?
+        .ok_or_else(|| AppError::InvalidInput(format!("Zauber '{}' nicht gefunden", spell_id)))?;
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64);
+
+    let change = concentration::start_concentration(&mut character, &spell, slot_level, started_at)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    Ok(change)
+}
+
+/// Ends the current concentration (if any) and persists the character.
+pub fn end_concentration(conn: &Connection, character_id: &str) -> AppResult<Option<ConcentrationResult>> {
+    let mut character = load_character(conn, character_id)?;
+    let ended = concentration::end_concentration(&mut character);
+    if ended.is_some() {
+        save_character(conn, &character)?;
+    }
+    Ok(ended)
+}
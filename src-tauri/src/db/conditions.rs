@@ -0,0 +1,82 @@
+use rusqlite::types::Value;
+use rusqlite::{params, Connection};
+use crate::core::concentration::enforce_concentration;
+use crate::core::conditions;
+use crate::types::character::{Character, CharacterCondition};
+use crate::db::characters::{load_character, save_character};
+use crate::db::row_sync::{sync_rows, RowTable, SyncRow};
+use crate::error::{AppError, AppResult};
+
+const CONDITIONS_TABLE: RowTable = RowTable {
+    table: "character_conditions",
+    key: &["condition_id"],
+    columns: &["level", "source", "duration", "ends_on_rest"],
+};
+
+/// Synchronizes character conditions with the database (matched by condition id).
+pub fn sync_conditions(conn: &Connection, character: &Character) -> AppResult<()> {
+    let text = |value: &Option<String>| value.clone().map_or(Value::Null, Value::Text);
+    let rows: Vec<SyncRow> = character.conditions.iter().map(|condition| {
+        let ends_on_rest = condition.ends_on_rest.as_deref()
+            .filter(|r| matches!(*r, "short" | "long"));
+        SyncRow::new(
+            vec![Value::Text(condition.condition_id.clone())],
+            vec![
+                condition.level.map_or(Value::Null, |l| Value::Integer(l.into())),
+                text(&condition.source),
+                text(&condition.duration),
+                ends_on_rest.map_or(Value::Null, |r| Value::Text(r.to_string())),
+            ],
+        )
+    }).collect();
+
+    sync_rows(conn, &CONDITIONS_TABLE, &character.id.to_string(), &rows)
+}
+
+/// Loads a character's active conditions in the order they were added.
+pub fn load_conditions(conn: &Connection, character_id: &str) -> AppResult<Vec<CharacterCondition>> {
+    let mut stmt = conn.prepare(
+        "SELECT condition_id, level, source, duration, ends_on_rest
+         FROM character_conditions
+         WHERE character_id = ?
+         ORDER BY created_at, rowid"
+    )?;
+    let conditions = stmt.query_map(params![character_id], |row| {
+        Ok(CharacterCondition {
+            condition_id: row.get(0)?,
+            level: row.get(1)?,
+            source: row.get(2)?,
+            duration: row.get(3)?,
+            ends_on_rest: row.get(4)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(conditions)
+}
+
+/// Adds (or updates) a condition and persists the character.
+/// Incapacitating conditions end concentration.
+pub fn add_condition(conn: &Connection, character_id: &str, condition: CharacterCondition) -> AppResult<Vec<CharacterCondition>> {
+    let mut character = load_character(conn, character_id)?;
+    conditions::add_condition(&mut character, condition).map_err(AppError::InvalidInput)?;
+    enforce_concentration(&mut character);
+    save_character(conn, &character)?;
+    Ok(character.conditions)
+}
+
+/// Removes a condition and persists the character.
+pub fn remove_condition(conn: &Connection, character_id: &str, condition_id: &str) -> AppResult<Vec<CharacterCondition>> {
+    let mut character = load_character(conn, character_id)?;
+    if conditions::remove_condition(&mut character, condition_id) {
+        save_character(conn, &character)?;
+    }
+    Ok(character.conditions)
+}
+
+/// Sets the exhaustion level (0 removes it, 6 is death) and persists the character.
+pub fn set_exhaustion(conn: &Connection, character_id: &str, level: i32, source: Option<String>) -> AppResult<Vec<CharacterCondition>> {
+    let mut character = load_character(conn, character_id)?;
+    conditions::set_exhaustion_level(&mut character, level, source).map_err(AppError::InvalidInput)?;
+    enforce_concentration(&mut character);
+    save_character(conn, &character)?;
+    Ok(character.conditions)
+}
@@ -1,76 +1,74 @@
+use rusqlite::types::Value;
 use rusqlite::{params, Connection};
-use crate::types::character::Character;
+use crate::db::row_sync::{sync_rows, RowTable, SyncRow};
+use crate::types::character::{Character, CharacterProficiencies};
 use crate::error::AppResult;
-use uuid::Uuid;
+
+const PROFICIENCIES_TABLE: RowTable = RowTable {
+    table: "character_proficiencies",
+    key: &["type", "ref_id"],
+    columns: &[],
+};
+
+const FEATURES_TABLE: RowTable = RowTable {
+    table: "character_features",
+    key: &["feature_id"],
+    columns: &[],
+};
 
 /// Synchronizes character proficiencies and features with the database.
+/// Rows are matched by type and reference, so existing rows keep their id.
 pub fn sync_features(conn: &Connection, character: &Character) -> AppResult<()> {
     let char_id = character.id.to_string();
-
-    // 1. Clear existing proficiencies and features
-    conn.execute(
-        "DELETE FROM character_proficiencies WHERE character_id = ?",
-        params![char_id],
-    )?;
-    conn.execute(
-        "DELETE FROM character_features WHERE character_id = ?",
-        params![char_id],
-    )?;
-
-    // 2. Sync Proficiencies
     let profs = &character.proficiencies;
-    
-    // Skills
-    for skill in &profs.skills {
-        insert_proficiency(conn, &char_id, "skill", skill)?;
-    }
-    // Saving Throws
-    for st in &profs.saving_throws {
-        insert_proficiency(conn, &char_id, "saving_throw", st)?;
-    }
-    // Weapons
-    for weapon in &profs.weapons {
-        insert_proficiency(conn, &char_id, "weapon", weapon)?;
-    }
-    // Armor
-    for armor in &profs.armor {
-        insert_proficiency(conn, &char_id, "armor", armor)?;
-    }
-    // Tools
-    for tool in &profs.tools {
-        insert_proficiency(conn, &char_id, "tool", tool)?;
-    }
-    // Languages
-    for lang in &profs.languages {
-        insert_proficiency(conn, &char_id, "language", lang)?;
-    }
 
-    // 3. Sync Features/Feats
-    for feat_id in &character.feats {
-        conn.execute(
-            "INSERT INTO character_features (id, character_id, feature_id, created_at, updated_at)
-             VALUES (?, ?, ?, (unixepoch()), (unixepoch()))",
-            params![
-                Uuid::new_v4().to_string(),
-                char_id,
-                feat_id
-            ],
-        )?;
-    }
+    // 1. Proficiencies
+    let groups = [
+        ("skill", &profs.skills),
+        ("saving_throw", &profs.saving_throws),
+        ("weapon", &profs.weapons),
+        ("armor", &profs.armor),
+        ("tool", &profs.tools),
+        ("language", &profs.languages),
+    ];
+    let rows: Vec<SyncRow> = groups.iter()
+        .flat_map(|(p_type, refs)| refs.iter().map(move |ref_id| {
+            SyncRow::new(vec![Value::Text(p_type.to_string()), Value::Text(ref_id.clone())], Vec::new())
+        }))
+        .collect();
+    sync_rows(conn, &PROFICIENCIES_TABLE, &char_id, &rows)?;
 
-    Ok(())
+    // 2. Features/Feats (a feat taken twice keeps two rows)
+    let rows: Vec<SyncRow> = character.feats.iter()
+        .map(|feat_id| SyncRow::new(vec![Value::Text(feat_id.clone())], Vec::new()))
+        .collect();
+    sync_rows(conn, &FEATURES_TABLE, &char_id, &rows)
 }
 
-fn insert_proficiency(conn: &Connection, char_id: &str, p_type: &str, ref_id: &str) -> AppResult<()> {
-    conn.execute(
-        "INSERT INTO character_proficiencies (id, character_id, type, ref_id, created_at, updated_at)
-         VALUES (?, ?, ?, ?, (unixepoch()), (unixepoch()))",
-        params![
-            Uuid::new_v4().to_string(),
-            char_id,
-            p_type,
-            ref_id
-        ],
+/// Loads proficiencies and feats of a character in the order they were added.
+pub fn load_features(conn: &Connection, character_id: &str) -> AppResult<(CharacterProficiencies, Vec<String>)> {
+    let mut proficiencies = CharacterProficiencies::default();
+    let mut stmt = conn.prepare(
+        "SELECT type, ref_id FROM character_proficiencies WHERE character_id = ? ORDER BY created_at, rowid"
+    )?;
+    let rows = stmt.query_map(params![character_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for row in rows {
+        let (p_type, ref_id) = row?;
+        let list = match p_type.as_str() {
+            "skill" => &mut proficiencies.skills,
+            "saving_throw" => &mut proficiencies.saving_throws,
+            "weapon" => &mut proficiencies.weapons,
+            "armor" => &mut proficiencies.armor,
+            "tool" => &mut proficiencies.tools,
+            "language" => &mut proficiencies.languages,
+            _ => continue,
+        };
+        list.push(ref_id);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT feature_id FROM character_features WHERE character_id = ? ORDER BY created_at, rowid"
     )?;
-    Ok(())
+    let feats = stmt.query_map(params![character_id], |row| row.get(0))?.collect::<Result<Vec<String>, _>>()?;
+    Ok((proficiencies, feats))
 }
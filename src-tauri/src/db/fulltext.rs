@@ -0,0 +1,259 @@
+use rusqlite::types::ValueRef;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::core::fulltext::match_query;
+use crate::error::AppResult;
+
+pub const DEFAULT_FULLTEXT_LIMIT: usize = 50;
+
+/// `settings` key holding the fingerprint of the indexed compendium text.
+const FINGERPRINT_SETTING: &str = "fulltext_compendium_fingerprint";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FulltextEntity {
+    Spell,
+    Feat,
+    ClassFeature,
+    MagicItem,
+    Item,
+}
+
+impl FulltextEntity {
+    pub const ALL: [FulltextEntity; 5] = [
+        FulltextEntity::Spell,
+        FulltextEntity::Feat,
+        FulltextEntity::ClassFeature,
+        FulltextEntity::MagicItem,
+        FulltextEntity::Item,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FulltextEntity::Spell => "spell",
+            FulltextEntity::Feat => "feat",
+            FulltextEntity::ClassFeature => "class_feature",
+            FulltextEntity::MagicItem => "magic_item",
+            FulltextEntity::Item => "item",
+        }
+    }
+
+    /// View that decides which rows are visible (overridden core rows are not).
+    fn view(self) -> &'static str {
+        match self {
+            FulltextEntity::Spell => "all_spells",
+            FulltextEntity::Feat => "all_feats",
+            FulltextEntity::ClassFeature => "all_class_features",
+            FulltextEntity::MagicItem => "all_mag_items_base",
+            FulltextEntity::Item => "all_items",
+        }
+    }
+
+    /// Indexed tables and the SQL expression for the searchable body (`{row}` = NEW/OLD/table).
+    fn sources(self) -> [(&'static str, &'static str); 2] {
+        const JSON_DESCRIPTION: &str =
+            "CASE WHEN json_valid({row}.data) THEN json_extract({row}.data, '$.description') END";
+        const MAGIC_ITEM_DESCRIPTION: &str =
+            "COALESCE(CASE WHEN json_valid({row}.data) THEN json_extract({row}.data, '$.description') END,
+                      CASE WHEN json_valid({row}.facts_json) THEN json_extract({row}.facts_json, '$.description') END)";
+        const SPELL_BODY: &str = "{row}.description || COALESCE(' ' || {row}.higher_levels, '')";
+        match self {
+            FulltextEntity::Spell => [("core_spells", SPELL_BODY), ("custom_spells", SPELL_BODY)],
+            FulltextEntity::Feat => [("core_feats", JSON_DESCRIPTION), ("custom_feats", JSON_DESCRIPTION)],
+            FulltextEntity::ClassFeature => [
+                ("core_class_features", "{row}.description"),
+                ("custom_class_features", "{row}.description"),
+            ],
+            FulltextEntity::MagicItem => [
+                ("core_mag_items_base", MAGIC_ITEM_DESCRIPTION),
+                ("custom_mag_items_base", MAGIC_ITEM_DESCRIPTION),
+            ],
+            FulltextEntity::Item => [("core_items", "{row}.description"), ("custom_items", "{row}.description")],
+        }
+    }
+}
+
+/// Creates the FTS5 index over names and descriptions, the triggers that keep it in sync
+/// with the custom tables, and fills it if it is out of date (e.g. after a compendium update).
+/// The index lives in the user database; triggers can only watch tables of that database.
+pub fn ensure_fulltext_index(conn: &Connection) -> AppResult<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS compendium_fts USING fts5(
+            entity UNINDEXED,
+            entity_id UNINDEXED,
+            source_table UNINDEXED,
+            name,
+            body,
+            tokenize = 'unicode61 remove_diacritics 2'
+        );",
+    )?;
+
+    let mut expected: i64 = 0;
+    for entity in FulltextEntity::ALL {
+        for (table, body) in entity.sources() {
+            let insert = |row: &str| {
+                format!(
+                    "INSERT INTO compendium_fts (entity, entity_id, source_table, name, body)
+                     VALUES ('{entity}', {row}.id, '{table}', {row}.name, {body});",
+                    entity = entity.as_str(),
+                    table = table,
+                    row = row,
+                    body = body.replace("{row}", row),
+                )
+            };
+            expected += conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))?;
+            if !in_user_database(conn, table)? {
+                continue;
+            }
+
+            let delete = format!("DELETE FROM compendium_fts WHERE source_table = '{}' AND entity_id = OLD.id;", table);
+            conn.execute_batch(&format!(
+                "CREATE TRIGGER IF NOT EXISTS fts_{table}_insert AFTER INSERT ON {table} BEGIN {insert_new} END;
+                 CREATE TRIGGER IF NOT EXISTS fts_{table}_update AFTER UPDATE ON {table} BEGIN {delete} {insert_new} END;
+                 CREATE TRIGGER IF NOT EXISTS fts_{table}_delete AFTER DELETE ON {table} BEGIN {delete} END;",
+                table = table,
+                insert_new = insert("NEW"),
+                delete = delete,
+            ))?;
+        }
+    }
+
+    // Neue Benutzerdatenbanken oder ausgetauschte Kompendien neu indexieren; der Fingerabdruck
+    // erkennt auch geänderte Texte bei gleicher Zeilenzahl
+    let indexed: i64 = conn.query_row("SELECT COUNT(*) FROM compendium_fts", [], |row| row.get(0))?;
+    let fingerprint = compendium_fingerprint(conn)?;
+    let stored: Option<String> = conn
+        .query_row("SELECT value FROM main.settings WHERE key = ?", [FINGERPRINT_SETTING], |row| row.get(0))
+        .optional()?;
+    if indexed != expected || stored.as_deref() != Some(fingerprint.as_str()) {
+        rebuild_fulltext_index(conn)?;
+        conn.execute(
+            "INSERT OR REPLACE INTO main.settings (key, value) VALUES (?, ?)",
+            params![FINGERPRINT_SETTING, fingerprint],
+        )?;
+    }
+    Ok(())
+}
+
+fn in_user_database(conn: &Connection, table: &str) -> AppResult<bool> {
+    Ok(conn.query_row(
+        "SELECT COUNT(*) > 0 FROM main.sqlite_master WHERE type = 'table' AND name = ?",
+        [table],
+        |row| row.get(0),
+    )?)
+}
+
+/// Fingerprint over id, name and body of all indexed compendium rows. Custom tables are
+/// left out, their triggers keep the index current.
+fn compendium_fingerprint(conn: &Connection) -> AppResult<String> {
+    let mut hasher = DefaultHasher::new();
+    for entity in FulltextEntity::ALL {
+        for (table, body) in entity.sources() {
+            if in_user_database(conn, table)? {
+                continue;
+            }
+            table.hash(&mut hasher);
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, name, {} FROM {table} ORDER BY id",
+                body.replace("{row}", table),
+                table = table,
+            ))?;
+            let mut rows = stmt.query([])?;
+            while let Some(row) = rows.next()? {
+                for i in 0..3 {
+                    match row.get_ref(i)? {
+                        ValueRef::Null => 0u8.hash(&mut hasher),
+                        ValueRef::Integer(n) => n.hash(&mut hasher),
+                        ValueRef::Real(f) => f.to_bits().hash(&mut hasher),
+                        ValueRef::Text(t) | ValueRef::Blob(t) => t.hash(&mut hasher),
+                    }
+                }
+            }
+        }
+    }
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+/// Re-indexes all compendium tables.
+pub fn rebuild_fulltext_index(conn: &Connection) -> AppResult<()> {
+    conn.execute("DELETE FROM compendium_fts", [])?;
+    for entity in FulltextEntity::ALL {
+        for (table, body) in entity.sources() {
+            conn.execute(
+                &format!(
+                    "INSERT INTO compendium_fts (entity, entity_id, source_table, name, body)
+                     SELECT '{}', id, '{}', name, {} FROM {table}",
+                    entity.as_str(),
+                    table,
+                    body.replace("{row}", table),
+                    table = table,
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+pub struct FulltextHit {
+    pub entity: FulltextEntity,
+    pub id: String,
+    pub name: String,
+    /// Name with matches wrapped in `<mark>`
+    pub name_highlighted: String,
+    /// Excerpt of the description around the matches, matches wrapped in `<mark>`
+    pub snippet: String,
+    /// BM25 rank, lower is better
+    pub rank: f64,
+}
+
+fn parse_entity(value: &str) -> FulltextEntity {
+    FulltextEntity::ALL.into_iter().find(|e| e.as_str() == value).unwrap_or(FulltextEntity::Item)
+}
+
+/// Ranked full-text search over names (weighted higher) and descriptions.
+pub fn fulltext_search(
+    conn: &Connection,
+    query: &str,
+    entities: &[FulltextEntity],
+    limit: usize,
+) -> AppResult<Vec<FulltextHit>> {
+    let Some(match_expr) = match_query(query) else {
+        return Ok(Vec::new());
+    };
+    let entities = if entities.is_empty() { &FulltextEntity::ALL[..] } else { entities };
+    let visible: Vec<String> = entities
+        .iter()
+        .map(|e| format!("(entity = '{}' AND entity_id IN (SELECT id FROM {}))", e.as_str(), e.view()))
+        .collect();
+
+    let sql = format!(
+        "SELECT entity, entity_id, name,
+                highlight(compendium_fts, 3, '<mark>', '</mark>'),
+                snippet(compendium_fts, 4, '<mark>', '</mark>', '…', 16),
+                bm25(compendium_fts, 0.0, 0.0, 0.0, 10.0, 1.0) AS rank
+         FROM compendium_fts
+         WHERE compendium_fts MATCH ?1 AND ({})
+         ORDER BY rank
+         LIMIT ?2",
+        visible.join(" OR ")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let params: Vec<rusqlite::types::Value> = vec![match_expr.into(), (limit.clamp(1, 500) as i64).into()];
+    let hits = stmt
+        .query_map(params_from_iter(params), |row| {
+            Ok(FulltextHit {
+                entity: parse_entity(&row.get::<_, String>(0)?),
+                id: row.get(1)?,
+                name: row.get(2)?,
+                name_highlighted: row.get(3)?,
+                snippet: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                rank: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(hits)
+}
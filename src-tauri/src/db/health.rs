@@ -0,0 +1,58 @@
+use rusqlite::Connection;
+use crate::core::concentration::concentration_after_damage;
+use crate::core::dice::DiceRng;
+use crate::core::health::{self, DamageResult, DeathSaveResult, HealingResult, TempHpResult};
+use crate::db::characters::{load_character, save_character};
+use crate::db::sheet::compute_sheet;
+use crate::error::{AppError, AppResult};
+
+/// Applies damage (temp HP first), checks concentration and persists the new health state.
+pub fn apply_damage(
+    conn: &Connection,
+    character_id: &str,
+    amount: i32,
+    critical: bool,
+    seed: Option<u64>,
+) -> AppResult<DamageResult> {
+    let mut character = load_character(conn, character_id)?;
+    let sheet = compute_sheet(conn, &character)?;
+    let mut result = health::apply_damage(&mut character.health, amount, critical, sheet.hp_max)
+        .map_err(AppError::InvalidInput)?;
+
+    // Auch von temporären TP abgefangener Schaden erfordert eine Konzentrationsprüfung
+    let con_save = sheet.saving_throws.iter().find(|s| s.ability == "con").map(|s| s.bonus).unwrap_or(0);
+    let seed = seed.unwrap_or_else(DiceRng::random_seed);
+    result.concentration = concentration_after_damage(&mut character, amount, con_save, seed);
+
+    save_character(conn, &character)?;
+    Ok(result)
+}
+
+/// Heals up to the effective HP maximum and persists the new health state.
+pub fn apply_healing(conn: &Connection, character_id: &str, amount: i32) -> AppResult<HealingResult> {
+    let mut character = load_character(conn, character_id)?;
+    let hp_max = compute_sheet(conn, &character)?.hp_max;
+    let result = health::apply_healing(&mut character.health, amount, hp_max)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    Ok(result)
+}
+
+/// Grants temporary HP (higher value wins unless `replace`).
+pub fn set_temp_hp(conn: &Connection, character_id: &str, amount: i32, replace: bool) -> AppResult<TempHpResult> {
+    let mut character = load_character(conn, character_id)?;
+    let result = health::set_temp_hp(&mut character.health, amount, replace)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    Ok(result)
+}
+
+/// Rolls a death saving throw for a dying character.
+pub fn roll_death_save(conn: &Connection, character_id: &str, seed: Option<u64>) -> AppResult<DeathSaveResult> {
+    let mut character = load_character(conn, character_id)?;
+    let seed = seed.unwrap_or_else(DiceRng::random_seed);
+    let result = health::roll_death_save(&mut character.health, seed)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    Ok(result)
+}
@@ -1,9 +1,12 @@
 use rusqlite::{params, Connection};
-use crate::types::character::{Character, CharacterInventoryEntry};
+use crate::types::character::{Character, CharacterInventoryEntry, CharacterItem};
 use crate::error::AppResult;
 use serde::Serialize;
 use std::collections::HashMap;
 use serde_json::Value;
+use rusqlite::types::Value as SqlValue;
+use crate::db::characters::{load_character, save_character};
+use crate::db::row_sync::{bool_value, sync_rows, RowTable, SyncRow};
 
 /// Helper to fetch all inventory entries for a character from SQL
 pub fn get_all_inventory_entries(conn: &Connection, character_id: &str) -> AppResult<Vec<CharacterInventoryEntry>> {
@@ -12,7 +15,8 @@ pub fn get_all_inventory_entries(conn: &Connection, character_id: &str) -> AppRe
                 container_id, custom_name, custom_description, data, created_at, updated_at,
                 location, source, is_starting_equipment
          FROM character_inventory 
-         WHERE character_id = ?"
+         WHERE character_id = ?
+         ORDER BY created_at, rowid"
     )?;
     
     let iter = stmt.query_map(params![character_id], |row| {
@@ -45,6 +49,23 @@ pub fn get_all_inventory_entries(conn: &Connection, character_id: &str) -> AppRe
     Ok(items)
 }
 
+/// Loads the inventory of a character as `Character::inventory` entries.
+pub fn load_inventory(conn: &Connection, character_id: &str) -> AppResult<Vec<CharacterItem>> {
+    let entries = get_all_inventory_entries(conn, character_id)?;
+    Ok(entries.into_iter().map(|entry| CharacterItem {
+        id: entry.id,
+        item_id: entry.item_id,
+        item_type: Some(entry.item_type),
+        quantity: entry.quantity,
+        is_equipped: entry.is_equipped,
+        is_attuned: entry.is_attuned,
+        location: entry.location,
+        source: entry.source,
+        is_starting_equipment: entry.is_starting_equipment,
+        custom_data: entry.data,
+    }).collect())
+}
+
 #[derive(Debug, Serialize)]
 pub struct StartingEquipmentOption {
     pub label: String,
@@ -106,16 +127,11 @@ pub fn get_class_starting_equipment_options(
 }
 
 /// Synchronizes the character_inventory table with the inventory field in a Character struct.
+/// Rows are matched by item entry id, so unchanged entries keep their `created_at`.
 pub fn sync_inventory(conn: &Connection, character: &Character) -> AppResult<()> {
     let char_id = character.id.to_string();
 
-    // 1. Clear existing inventory for this character
-    conn.execute(
-        "DELETE FROM character_inventory WHERE character_id = ?",
-        params![char_id],
-    )?;
-
-    // 1b. Update currency in characters table
+    // 1. Update currency in characters table
     conn.execute(
         "UPDATE characters SET 
             currency_cp = ?, 
@@ -135,33 +151,34 @@ pub fn sync_inventory(conn: &Connection, character: &Character) -> AppResult<()>
         ],
     )?;
 
-    // 2. Insert items from the character struct
+    // 2. Diff items against the character struct
+    let mut rows = Vec::new();
     for item in &character.inventory {
         let item_type = detect_item_type(conn, &item.item_id)?;
-        
-        conn.execute(
-            "INSERT INTO character_inventory (
-                id, character_id, item_id, item_type, quantity, is_equipped, is_attuned, location, source, is_starting_equipment, data
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-            params![
-                item.id,
-                char_id,
-                item.item_id,
-                item_type,
-                item.quantity,
-                if item.is_equipped { 1 } else { 0 },
-                if item.is_attuned { 1 } else { 0 },
-                item.location.as_deref().unwrap_or("Body"),
-                item.source.as_deref().unwrap_or("manual"),
-                if item.is_starting_equipment { 1 } else { 0 },
-                serde_json::to_string(&item.custom_data).unwrap_or_else(|_| "{}".to_string())
+        rows.push(SyncRow::new(
+            vec![SqlValue::Text(item.id.clone())],
+            vec![
+                SqlValue::Text(item.item_id.clone()),
+                SqlValue::Text(item_type),
+                SqlValue::Integer(item.quantity.into()),
+                bool_value(item.is_equipped),
+                bool_value(item.is_attuned),
+                SqlValue::Text(item.location.clone().unwrap_or_else(|| "Body".to_string())),
+                SqlValue::Text(item.source.clone().unwrap_or_else(|| "manual".to_string())),
+                bool_value(item.is_starting_equipment),
+                SqlValue::Text(serde_json::to_string(&item.custom_data).unwrap_or_else(|_| "{}".to_string())),
             ],
-        )?;
+        ));
     }
-
-    Ok(())
+    sync_rows(conn, &INVENTORY_TABLE, &char_id, &rows)
 }
 
+const INVENTORY_TABLE: RowTable = RowTable {
+    table: "character_inventory",
+    key: &["id"],
+    columns: &["item_id", "item_type", "quantity", "is_equipped", "is_attuned", "location", "source", "is_starting_equipment", "data"],
+};
+
 /// Helper: attempts to detect if item_id refers to an Equipment Package (core_equipment)
 fn is_equipment_package(conn: &Connection, item_id: &str) -> AppResult<bool> {
     let mut stmt = conn.prepare("SELECT 1 FROM core_equipment WHERE id = ?")?;
@@ -187,9 +204,7 @@ pub fn clear_starting_equipment(
     // The user requirement says: "reset corresponding gold field in character_meta".
     
     // We need to read the character to get current gold and the tracked amount.
-    let mut stmt = conn.prepare("SELECT data FROM characters WHERE id = ?")?;
-    let data_str: String = stmt.query_row(params![character_id], |row| row.get(0))?;
-    let mut character: Character = serde_json::from_str(&data_str)?;
+    let mut character = load_character(conn, character_id)?;
     
     let mut gold_to_remove = 0;
     
@@ -216,12 +231,8 @@ pub fn clear_starting_equipment(
          let current_gp = character.meta.currency_gold.unwrap_or(0);
          character.meta.currency_gold = Some((current_gp - gold_to_remove).max(0));
          
-         // Save updated char back
-         let new_data = serde_json::to_string(&character)?;
-         conn.execute("UPDATE characters SET data = ? WHERE id = ?", params![new_data, character_id])?;
-         
-         // Also update flattened columns
-         conn.execute("UPDATE characters SET currency_gp = ? WHERE id = ?", params![character.meta.currency_gold.unwrap_or(0), character_id])?;
+         // Save updated char back (currency columns included)
+         save_character(conn, &character)?;
     }
 
     Ok(())
@@ -330,40 +341,16 @@ pub fn apply_background_starting_equipment(
     }
 
     // 3. Add Gold (Track granted)
+    // clear_starting_equipment relies on meta.background_gold_granted to know what to remove.
+    let mut character = load_character(conn, character_id)?;
     if gold > 0 {
-         println!("DEBUG: Adding gold: {}", gold);
-         // ... (rest of logic)
-         conn.execute(
-            "UPDATE characters SET currency_gp = currency_gp + ? WHERE id = ?",
-            params![gold, character_id],
-        )?;
-        
-        // Update meta in JSON too to be safe/consistent? 
-        // clear_starting_equipment relies on reading meta.background_gold_granted to know what to remove.
-        // So we MUST update background_gold_granted.
-        let mut stmt = conn.prepare("SELECT data FROM characters WHERE id = ?")?;
-        let data_str: String = stmt.query_row(params![character_id], |row| row.get(0))?;
-        let mut character: Character = serde_json::from_str(&data_str)?;
-
-        character.meta.background_gold_granted = Some(gold);
-        character.meta.background_equipment_applied = Some(true);
-        // Also ensure currency_gold matches the flat column roughly or let loop above handle it?
-        // best to keep them in sync.
+        println!("DEBUG: Adding gold: {}", gold);
         let current_gp = character.meta.currency_gold.unwrap_or(0);
         character.meta.currency_gold = Some(current_gp + gold);
-
-        let new_data = serde_json::to_string(&character)?;
-        conn.execute("UPDATE characters SET data = ? WHERE id = ?", params![new_data, character_id])?;
-    } else {
-        // Just flag applied
-        let mut stmt = conn.prepare("SELECT data FROM characters WHERE id = ?")?;
-        let data_str: String = stmt.query_row(params![character_id], |row| row.get(0))?;
-        let mut character: Character = serde_json::from_str(&data_str)?;
-        character.meta.background_gold_granted = Some(0);
-        character.meta.background_equipment_applied = Some(true);
-        let new_data = serde_json::to_string(&character)?;
-        conn.execute("UPDATE characters SET data = ? WHERE id = ?", params![new_data, character_id])?;
     }
+    character.meta.background_gold_granted = Some(gold.max(0));
+    character.meta.background_equipment_applied = Some(true);
+    save_character(conn, &character)?;
 
     Ok(())
 }
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use rusqlite::{params, params_from_iter, Connection};
+use uuid::Uuid;
+use crate::core::calculator::calculate_proficiency_bonus;
+use crate::core::level_up::{
+    self, ChoiceKind, ChoiceOption, LevelUpChoices, LevelUpFeature, LevelUpInput, LevelUpPreview,
+    LevelUpRecord,
+};
+use crate::core::multiclass::{classes_after_level_up, normalize_classes, same_class};
+use crate::db::characters::{class_id_candidates, load_character, rebuild_hit_dice_pools, save_character};
+use crate::db::rest::class_hit_die;
+use crate::db::sheet::compute_sheet;
+use crate::db::spell_slots::load_slot_maxima;
+use crate::error::{AppError, AppResult};
+use crate::types::character::Character;
+
+/// Klassenmerkmale genau einer Stufe (ohne fremde Unterklassen). Overrides ersetzen Kernmerkmale.
+fn load_level_features(conn: &Connection, class_ids: &[String], level: i32, subclass_id: Option<&str>) -> AppResult<Vec<LevelUpFeature>> {
+    let sql = format!(
+        "SELECT id, name, description, feature_type, subclass_id FROM all_class_features
+         WHERE class_id IN ({}) AND level = ? AND (subclass_id IS NULL OR subclass_id = ?)
+         ORDER BY CASE source WHEN 'core' THEN 0 ELSE 1 END, subclass_id IS NOT NULL, name",
+        vec!["?"; class_ids.len()].join(", ")
+    );
+    let mut values: Vec<rusqlite::types::Value> = class_ids.iter().map(|id| id.clone().into()).collect();
+    values.push(i64::from(level).into());
+    values.push(subclass_id.unwrap_or_default().to_string().into());
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(LevelUpFeature {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            feature_type: row.get(3)?,
+            subclass_id: row.get(4)?,
+        })
+    })?;
+
+    let mut features: Vec<LevelUpFeature> = Vec::new();
+    for row in rows {
+        let feature = row?;
+        features.retain(|f| f.id != feature.id);
+        features.push(feature);
+    }
+    Ok(features)
+}
+
+fn load_feature_options(conn: &Connection, feature_id: &str) -> AppResult<Vec<ChoiceOption>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, option_name, option_description FROM all_feature_options
+         WHERE feature_id = ?
+         ORDER BY display_order, option_name",
+    )?;
+    let rows = stmt.query_map(params![feature_id], |row| {
+        Ok(ChoiceOption {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            feat_category: None,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn load_feats(conn: &Connection, category: &str) -> AppResult<Vec<ChoiceOption>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, json_extract(data, '$.description'), category FROM all_feats
+         WHERE category = ?
+         ORDER BY name",
+    )?;
+    let rows = stmt.query_map(params![category], |row| {
+        Ok(ChoiceOption {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            feat_category: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+fn load_subclasses(conn: &Connection, class_ids: &[String], max_level: i32) -> AppResult<Vec<ChoiceOption>> {
+    let sql = format!(
+        "SELECT id, name, description FROM all_subclasses
+         WHERE class_id IN ({}) AND level <= ?
+         ORDER BY name",
+        vec!["?"; class_ids.len()].join(", ")
+    );
+    let mut values: Vec<rusqlite::types::Value> = class_ids.iter().map(|id| id.clone().into()).collect();
+    values.push(i64::from(max_level).into());
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(ChoiceOption {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            description: row.get(2)?,
+            feat_category: None,
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Lädt alles, was `core::level_up` für die nächste Stufe in `class_id` braucht
+/// (ohne Angabe: Startklasse).
+pub fn load_level_up_input(conn: &Connection, character: &Character, class_id: Option<&str>) -> AppResult<LevelUpInput> {
+    let mut character = character.clone();
+    normalize_classes(&mut character.meta);
+    let class_id = class_id.map(str::to_string)
+        .or_else(|| character.meta.class_id.clone())
+        .filter(|c| !c.trim().is_empty())
+        .ok_or_else(|| AppError::InvalidInput("Der Charakter hat keine Klasse".to_string()))?;
+    let class_ids = class_id_candidates(&class_id);
+    let entry = character.meta.classes.iter().find(|c| same_class(&c.class_id, &class_id));
+    let next_class_level = entry.map_or(0, |c| c.level) + 1;
+    let level = character.meta.level;
+
+    let subclass_id = entry.and_then(|c| c.subclass_id.as_deref()).filter(|s| !s.trim().is_empty());
+    let features = load_level_features(conn, &class_ids, next_class_level, subclass_id)?;
+    let mut feature_options = HashMap::new();
+    for feature in features.iter().filter(|f| f.feature_type == "choice") {
+        feature_options.insert(feature.id.clone(), load_feature_options(conn, &feature.id)?);
+    }
+    let subclasses = match subclass_id {
+        Some(_) => Vec::new(),
+        None => load_subclasses(conn, &class_ids, next_class_level)?,
+    };
+
+    // Der Übungsbonus hängt an der Gesamtstufe, nicht an der Klassenstufe
+    Ok(LevelUpInput {
+        hit_die: class_hit_die(conn, &class_id)?,
+        con_modifier: compute_sheet(conn, &character)?.ability_modifier("con"),
+        proficiency_bonus_before: calculate_proficiency_bonus(level),
+        proficiency_bonus_after: calculate_proficiency_bonus(level + 1),
+        spell_slots_after: Some(load_slot_maxima(conn, &classes_after_level_up(&character.meta, &class_id))?.slots),
+        class_id,
+        features,
+        feature_options,
+        subclasses,
+        general_feats: load_feats(conn, "Allgemein")?,
+        epic_boons: load_feats(conn, "Epische Gabe")?,
+        fighting_styles: load_feats(conn, "Kampfstil")?,
+    })
+}
+
+/// Shows what the next level in `class_id` (default: starting class) grants without changing the character.
+pub fn preview_level_up(conn: &Connection, character_id: &str, class_id: Option<&str>) -> AppResult<LevelUpPreview> {
+    let character = load_character(conn, character_id)?;
+    let input = load_level_up_input(conn, &character, class_id)?;
+    level_up::preview_level_up(&character, &input).map_err(AppError::InvalidInput)
+}
+
+/// Applies the next level with the given choices, persists the character and records the level-up.
+pub fn apply_level_up(conn: &Connection, character_id: &str, choices: &LevelUpChoices) -> AppResult<LevelUpRecord> {
+    let mut character = load_character(conn, character_id)?;
+    let input = load_level_up_input(conn, &character, choices.class_id.as_deref())?;
+    let preview = level_up::preview_level_up(&character, &input).map_err(AppError::InvalidInput)?;
+    // Zweite Klasse: Trefferwürfel ab jetzt je Würfelgröße getrennt führen
+    if preview.multiclass.is_some() && character.health.hit_dice.is_empty() {
+        rebuild_hit_dice_pools(conn, &mut character)?;
+    }
+    let mut record = level_up::apply_level_up(&mut character, &preview, choices).map_err(AppError::InvalidInput)?;
+
+    // Neu gewählte Unterklasse: deren Merkmale der neuen Stufe kommen hinzu
+    if preview.choices.iter().any(|c| c.kind == ChoiceKind::Subclass) {
+        let class_ids = class_id_candidates(&preview.class_id);
+        let subclass_id = character.meta.classes.iter()
+            .find(|c| same_class(&c.class_id, &preview.class_id))
+            .and_then(|c| c.subclass_id.as_deref());
+        let subclass_features = load_level_features(conn, &class_ids, preview.class_level_after, subclass_id)?;
+        record.features.extend(subclass_features.into_iter().filter(|f| f.subclass_id.is_some()).map(|f| f.name));
+    }
+
+    save_character(conn, &character)?;
+
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs() as i64);
+    record.created_at = created_at;
+    conn.execute(
+        "INSERT INTO character_level_history (id, character_id, level, class_id, hp_gained, data, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        params![
+            Uuid::new_v4().to_string(),
+            character_id,
+            record.level,
+            record.class_id,
+            record.hp_gained,
+            serde_json::to_string(&record)?,
+            created_at,
+        ],
+    )?;
+    Ok(record)
+}
+
+/// Loads all recorded level-ups of a character, oldest first.
+pub fn get_level_history(conn: &Connection, character_id: &str) -> AppResult<Vec<LevelUpRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT data, created_at FROM character_level_history
+         WHERE character_id = ?
+         ORDER BY level, created_at",
+    )?;
+    let rows = stmt.query_map(params![character_id], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+    })?;
+
+    let mut history = Vec::new();
+    for row in rows {
+        let (data, created_at) = row?;
+        let mut record: LevelUpRecord = serde_json::from_str(&data)?;
+        record.created_at = created_at;
+        history.push(record);
+    }
+    Ok(history)
+}
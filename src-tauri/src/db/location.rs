@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use crate::error::{AppError, AppResult};
+
+/// Bundled compendium with the core rules data, attached read-only.
+pub const COMPENDIUM_FILE: &str = "dnd-nexus.db";
+/// Per-user database with characters, homebrew and settings.
+pub const USER_DATABASE_FILE: &str = "dnd-nexus-user.db";
+/// Environment variable overriding the user database path.
+pub const USER_DATABASE_ENV: &str = "DND_NEXUS_USER_DB";
+/// Environment variable overriding the compendium path.
+pub const COMPENDIUM_ENV: &str = "DND_NEXUS_COMPENDIUM";
+/// The location setting is needed before the database is open, so it lives in its own file
+/// in the app config dir instead of the `settings` table.
+const LOCATION_SETTINGS_FILE: &str = "database.json";
+
+/// Where the user database path came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationSource {
+    Environment,
+    Setting,
+    Default,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseLocation {
+    pub user_database: PathBuf,
+    pub source: LocationSource,
+    pub compendium: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LocationSettings {
+    user_database: Option<PathBuf>,
+    /// Altbestand aus dem Kompendium wurde bereits übernommen (gilt für die Installation,
+    /// nicht für eine einzelne Benutzerdatenbank)
+    #[serde(default)]
+    legacy_user_data_imported: bool,
+}
+
+fn env_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+fn settings_path(app: &AppHandle) -> AppResult<PathBuf> {
+    Ok(app.path().app_config_dir()?.join(LOCATION_SETTINGS_FILE))
+}
+
+fn read_settings(app: &AppHandle) -> AppResult<LocationSettings> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(LocationSettings::default());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn write_settings(app: &AppHandle, settings: &LocationSettings) -> AppResult<()> {
+    let settings_file = settings_path(app)?;
+    if let Some(dir) = settings_file.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(settings_file, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+/// Stores the user database path for the next start; `None` goes back to the default.
+pub fn set_user_database_setting(app: &AppHandle, path: Option<PathBuf>) -> AppResult<()> {
+    let mut settings = read_settings(app)?;
+    settings.user_database = path;
+    write_settings(app, &settings)
+}
+
+/// Whether characters from older versions have already been copied out of the compendium.
+/// Recorded per installation, so switching to a new user database does not import them again.
+pub fn legacy_user_data_imported(app: &AppHandle) -> AppResult<bool> {
+    Ok(read_settings(app)?.legacy_user_data_imported)
+}
+
+pub fn set_legacy_user_data_imported(app: &AppHandle) -> AppResult<()> {
+    let mut settings = read_settings(app)?;
+    settings.legacy_user_data_imported = true;
+    write_settings(app, &settings)
+}
+
+/// Resolves both database paths. The user database comes from `DND_NEXUS_USER_DB`, the
+/// location setting or the app data dir, in that order.
+pub fn resolve_location(app: &AppHandle) -> AppResult<DatabaseLocation> {
+    let (user_database, source) = if let Some(path) = env_path(USER_DATABASE_ENV) {
+        (path, LocationSource::Environment)
+    } else if let Some(path) = read_settings(app)?.user_database {
+        (path, LocationSource::Setting)
+    } else {
+        (app.path().app_data_dir()?.join(USER_DATABASE_FILE), LocationSource::Default)
+    };
+    Ok(DatabaseLocation { user_database, source, compendium: find_compendium(app)? })
+}
+
+/// Finds the bundled compendium: `DND_NEXUS_COMPENDIUM`, the resource dir (release builds),
+/// then the directories above the executable and the working directory (development).
+fn find_compendium(app: &AppHandle) -> AppResult<PathBuf> {
+    if let Some(path) = env_path(COMPENDIUM_ENV) {
+        return Ok(path);
+    }
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Ok(resource_dir) = app.path().resource_dir() {
+        candidates.push(resource_dir.join(COMPENDIUM_FILE));
+        candidates.push(resource_dir.join("resources").join(COMPENDIUM_FILE));
+        // Relative Pfade im Bundle (Windows)
+        candidates.push(resource_dir.join("_up_").join(COMPENDIUM_FILE));
+    }
+    if let Ok(exe) = std::env::current_exe() {
+        // src-tauri/target/... -> Projekt-Root
+        candidates.extend(exe.ancestors().skip(1).take(5).map(|dir| dir.join(COMPENDIUM_FILE)));
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        candidates.extend(cwd.ancestors().take(2).map(|dir| dir.join(COMPENDIUM_FILE)));
+    }
+
+    candidates.iter().find(|path| path.exists()).cloned().ok_or_else(|| {
+        let searched: Vec<String> = candidates.iter().map(|p| p.to_string_lossy().to_string()).collect();
+        AppError::Other(format!(
+            "Kompendium ({}) nicht gefunden!\nGesucht in:\n  {}\n\nPfad lässt sich mit {} festlegen.",
+            COMPENDIUM_FILE,
+            searched.join("\n  "),
+            COMPENDIUM_ENV
+        ))
+    })
+}
@@ -1,6 +1,5 @@
 use rusqlite::Connection;
-use std::sync::Mutex;
-use tauri::{AppHandle, Manager};
+use tauri::AppHandle;
 use crate::commands::logging;
 
 pub mod migrations;
@@ -12,109 +11,99 @@ pub mod spells;
 pub mod stats;
 pub mod features;
 pub mod modifiers;
+pub mod characters;
+pub mod sheet;
+pub mod attacks;
+pub mod rest;
+pub mod resources;
+pub mod health;
+pub mod conditions;
+pub mod concentration;
+pub mod level_up;
+pub mod spell_slots;
+pub mod spell_preparation;
+pub mod casting;
+pub mod spell_mechanics;
+pub mod compendium_search;
+pub mod fulltext;
+pub mod row_sync;
+pub mod location;
+pub mod compendium;
+pub mod pool;
 
-pub struct Database(pub Mutex<Connection>);
+pub use pool::Database;
 
-pub fn init_database(_app: &AppHandle) -> Result<Database, String> {
-    // NUR Root-Datenbank verwenden - KEINE Runtime-Datenbank mehr
-    // Prüfe verschiedene mögliche Pfade zur Root-Datenbank
-    let mut project_db_paths: Vec<std::path::PathBuf> = Vec::new();
-
-    // Pfad 0: Tauri Resource Directory (Wichtig für Produktion/Release)
-    if let Ok(resource_dir) = _app.path().resource_dir() {
-        // Variante A: Direkt im resource_dir
-        let db_path = resource_dir.join("dnd-nexus.db");
-        if db_path.exists() {
-            project_db_paths.push(db_path);
-        }
-        
-        // Variante B: Im resources Unterordner
-        let db_path_res = resource_dir.join("resources").join("dnd-nexus.db");
-        if db_path_res.exists() {
-            project_db_paths.push(db_path_res);
-        }
-
-        // Variante C: Relative Pfade im Bundle (Windows)
-        let db_path_bundle = resource_dir.join("_up_").join("dnd-nexus.db");
-        if db_path_bundle.exists() {
-            project_db_paths.push(db_path_bundle);
-        }
-    }
-    
-    // Pfad 1: Relativ zum Executable (src-tauri/target/... -> ../dnd-nexus.db)
-    if let Ok(exe) = std::env::current_exe() {
-        if let Some(exe_dir) = exe.parent() {
-            // Versuche Parent-Verzeichnisse (bis zum Projekt-Root)
-            let mut current = exe_dir.to_path_buf();
-            for _ in 0..5 {
-                let db_path = current.join("dnd-nexus.db");
-                if db_path.exists() {
-                    project_db_paths.push(db_path);
-                }
-                if let Some(parent) = current.parent() {
-                    current = parent.to_path_buf();
-                } else {
-                    break;
-                }
-            }
-        }
-    }
-    
-    // Pfad 2: Aktuelles Arbeitsverzeichnis
-    if let Ok(cwd) = std::env::current_dir() {
-        let db_path = cwd.join("dnd-nexus.db");
-        if db_path.exists() {
-            project_db_paths.push(db_path);
-        }
-        // Auch Parent-Verzeichnisse prüfen
-        if let Some(parent) = cwd.parent() {
-            let db_path = parent.join("dnd-nexus.db");
-            if db_path.exists() {
-                project_db_paths.push(db_path);
-            }
-        }
+pub fn init_database(app: &AppHandle) -> Result<Database, String> {
+    let location = location::resolve_location(app).map_err(|e| format!("Datenbank-Pfade konnten nicht ermittelt werden: {}", e))?;
+    let user_db_str = location.user_database.to_string_lossy().to_string();
+    let compendium_str = location.compendium.to_string_lossy().to_string();
+    for log_msg in [
+        format!("Verwende Benutzerdatenbank: {} ({:?})", user_db_str, location.source),
+        format!("Verwende Kompendium: {}", compendium_str),
+    ] {
+        println!("{}", log_msg);
+        let _ = logging::write_log(app.clone(), log_msg);
     }
-    
-    // Pfad 3: Hardcoded Projekt-Pfad (für Development)
-    #[cfg(debug_assertions)]
+
+    if location.user_database.exists()
+        && std::fs::canonicalize(&location.user_database).ok() == std::fs::canonicalize(&location.compendium).ok()
     {
-        let hardcoded_path = std::path::PathBuf::from("/daten/projects/dnd_nexus-sheet_engine/dnd-nexus.db");
-        if hardcoded_path.exists() {
-            project_db_paths.push(hardcoded_path);
-        }
+        return Err(format!(
+            "Benutzerdatenbank und Kompendium sind dieselbe Datei ({}). Bitte einen anderen Pfad über {} oder die Einstellungen wählen.",
+            user_db_str,
+            location::USER_DATABASE_ENV
+        ));
     }
-    
-    // Finde die erste existierende Root-Datenbank
-    let db_path = project_db_paths.iter()
-        .find(|p| p.exists())
-        .ok_or_else(|| {
-            let searched_paths: Vec<String> = project_db_paths.iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect();
-            format!(
-                "Root-Datenbank (dnd-nexus.db) nicht gefunden!\n\
-                Gesucht in:\n  {}\n\n\
-                Bitte stelle sicher, dass die Datenbank im Projekt-Root existiert.",
-                searched_paths.join("\n  ")
-            )
-        })?;
-    
-    println!("Verwende Root-Datenbank: {:?}", db_path);
-    
-    let db_path_str = db_path.to_string_lossy().to_string();
-    let log_msg = format!("Verwende Datenbank: {}", db_path_str);
-    println!("{}", log_msg);
-    let _ = logging::write_log(_app.clone(), log_msg);
-    
+
+    // Entwicklungs-Kompendium auf Stand bringen (das ausgelieferte ist bereits migriert)
+    compendium::migrate_compendium(&location.compendium)
+        .map_err(|e| format!("Kompendium-Migration fehlgeschlagen ({}): {}", compendium_str, e))?;
+
+    if let Some(dir) = location.user_database.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Konnte Verzeichnis {:?} nicht anlegen: {}", dir, e))?;
+    }
+
     // Verbindung herstellen
-    let conn = Connection::open(&db_path).map_err(|e| {
-        let err_msg = format!("Konnte Datenbank nicht öffnen ({}): {}", db_path_str, e);
-        let _ = logging::write_log(_app.clone(), err_msg.clone());
+    let conn = Connection::open(&location.user_database).map_err(|e| {
+        let err_msg = format!("Konnte Datenbank nicht öffnen ({}): {}", user_db_str, e);
+        let _ = logging::write_log(app.clone(), err_msg.clone());
         err_msg
     })?;
-    
-    // Migrations ausführen (stellt Tabellenstruktur sicher)
-    migrations::run_migrations(&conn).map_err(|e| format!("Datenbank-Migration fehlgeschlagen: {}", e))?;
+
+    // Migrations ausführen (stellt Tabellenstruktur sicher), danach erst das Kompendium anhängen
+    migrations::run_user_migrations(&conn).map_err(|e| format!("Datenbank-Migration fehlgeschlagen: {}", e))?;
+    compendium::attach_compendium(&conn, &location.compendium)
+        .map_err(|e| format!("Kompendium konnte nicht angehängt werden ({}): {}", compendium_str, e))?;
+
+    // Charaktere, Homebrew und Einstellungen, die frühere Versionen ins Kompendium geschrieben haben
+    // Nur einmal je Installation, sonst kämen gelöschte Charaktere in einer neuen Datenbank zurück
+    let legacy_imported = location::legacy_user_data_imported(app)
+        .map_err(|e| format!("Datenbank-Einstellungen konnten nicht gelesen werden: {}", e))?;
+    let imported = if legacy_imported {
+        0
+    } else {
+        let imported = compendium::import_legacy_user_data(&conn)
+            .map_err(|e| format!("Bestehende Charaktere konnten nicht übernommen werden: {}", e))?;
+        location::set_legacy_user_data_imported(app)
+            .map_err(|e| format!("Datenbank-Einstellungen konnten nicht gespeichert werden: {}", e))?;
+        imported
+    };
+    if imported > 0 {
+        println!("{} Charaktere aus {} übernommen", imported, compendium_str);
+    }
+
+    // Volltextsuche (FTS5) über Namen und Beschreibungen, per Trigger aktuell gehalten
+    fulltext::ensure_fulltext_index(&conn).map_err(|e| format!("Volltextindex konnte nicht angelegt werden: {}", e))?;
+
+    // Strukturierte Zauberdaten aus dem Freitext neu aufbauen, falls sich Kompendium, Homebrew oder Parser geändert haben
+    spell_mechanics::rebuild_spell_mechanics(&conn).map_err(|e| format!("Zauberdaten konnten nicht aufbereitet werden: {}", e))?;
+
+    // Altbestand (vollständiges JSON in characters.data) in die normalisierten Tabellen überführen
+    let converted = characters::convert_legacy_characters(&conn)
+        .map_err(|e| format!("Charaktere konnten nicht konvertiert werden: {}", e))?;
+    if converted > 0 {
+        println!("{} Charaktere ins normalisierte Speicherformat überführt", converted);
+    }
     
     // Prüfe Datenbank-Inhalt für Debugging
     let weapons_count: i32 = conn.prepare("SELECT COUNT(*) FROM all_weapons_unified")
@@ -132,6 +121,8 @@ pub fn init_database(_app: &AppHandle) -> Result<Database, String> {
     println!("=== DATENBANK-INHALT: Waffen={}, Rüstungen={}, Magische Gegenstände={}, Charaktere={} ===", 
              weapons_count, armor_count, magic_count, chars_count);
     
-    Ok(Database(Mutex::new(conn)))
+    // WAL: Lesezugriffe laufen über eigene Verbindungen und blockieren Speichervorgänge nicht
+    Database::new(conn, &location.user_database, &location.compendium, pool::READ_POOL_SIZE)
+        .map_err(|e| format!("Lesezugriffe konnten nicht eingerichtet werden: {}", e))
 }
 
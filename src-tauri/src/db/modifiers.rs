@@ -1,43 +1,61 @@
+use rusqlite::types::Value;
 use rusqlite::{params, Connection};
-use crate::types::character::Character;
+use crate::db::row_sync::{sync_rows, RowTable, SyncRow};
+use crate::core::types::ModifierType;
+use crate::types::character::{Character, Modifier};
 use crate::error::AppResult;
-// use uuid::Uuid; - Removed unused import
 
-/// Synchronizes character modifiers with the database.
-pub fn sync_modifiers(conn: &Connection, character: &Character) -> AppResult<()> {
-    let char_id = character.id.to_string();
-
-    // 1. Clear existing modifiers
-    conn.execute(
-        "DELETE FROM character_modifiers WHERE character_id = ?",
-        params![char_id],
-    )?;
+const MODIFIERS_TABLE: RowTable = RowTable {
+    table: "character_modifiers",
+    key: &["id"],
+    columns: &["source", "target", "modifier_type", "value", "condition"],
+};
 
-    // 2. Sync Modifiers
-    for modifier in &character.modifiers {
+/// Synchronizes character modifiers with the database (matched by modifier id).
+pub fn sync_modifiers(conn: &Connection, character: &Character) -> AppResult<()> {
+    let rows: Vec<SyncRow> = character.modifiers.iter().map(|modifier| {
         let modifier_type = match modifier.modifier_type {
-            crate::core::types::ModifierType::Override => "Override",
-            crate::core::types::ModifierType::Add => "Add",
-            crate::core::types::ModifierType::Multiply => "Multiply",
+            ModifierType::Override => "Override",
+            ModifierType::Add => "Add",
+            ModifierType::Multiply => "Multiply",
         };
-
-        conn.execute(
-            "INSERT INTO character_modifiers (
-                id, character_id, source, target, 
-                modifier_type, value, condition, 
-                created_at, updated_at
-             ) VALUES (?, ?, ?, ?, ?, ?, ?, (unixepoch()), (unixepoch()))",
-            params![
-                modifier.id,
-                char_id,
-                modifier.source,
-                modifier.target,
-                modifier_type,
-                modifier.value,
-                modifier.condition,
+        SyncRow::new(
+            vec![Value::Text(modifier.id.clone())],
+            vec![
+                Value::Text(modifier.source.clone()),
+                Value::Text(modifier.target.clone()),
+                Value::Text(modifier_type.to_string()),
+                Value::Integer(modifier.value.into()),
+                modifier.condition.clone().map_or(Value::Null, Value::Text),
             ],
-        )?;
-    }
+        )
+    }).collect();
 
-    Ok(())
+    sync_rows(conn, &MODIFIERS_TABLE, &character.id.to_string(), &rows)
+}
+
+/// Loads a character's modifiers in the order they were added.
+pub fn load_modifiers(conn: &Connection, character_id: &str) -> AppResult<Vec<Modifier>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source, target, modifier_type, value, condition
+         FROM character_modifiers
+         WHERE character_id = ?
+         ORDER BY created_at, rowid"
+    )?;
+    let modifiers = stmt.query_map(params![character_id], |row| {
+        let modifier_type = match row.get::<_, String>(3)?.as_str() {
+            "Override" => ModifierType::Override,
+            "Multiply" => ModifierType::Multiply,
+            _ => ModifierType::Add,
+        };
+        Ok(Modifier {
+            id: row.get(0)?,
+            source: row.get(1)?,
+            target: row.get(2)?,
+            modifier_type,
+            value: row.get(4)?,
+            condition: row.get(5)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(modifiers)
 }
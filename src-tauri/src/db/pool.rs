@@ -0,0 +1,80 @@
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use crate::db::compendium::attach_compendium;
+use crate::error::{map_lock_error, AppResult};
+
+/// Number of read-only connections kept open.
+pub const READ_POOL_SIZE: usize = 4;
+
+/// Shared database state: one writer connection for all mutations and a pool of read-only
+/// connections for everything else. The user database runs in WAL mode, so readers see the
+/// last committed state and neither side waits for the other.
+pub struct Database {
+    writer: Mutex<Connection>,
+    readers: Mutex<Vec<Connection>>,
+    reader_returned: Condvar,
+}
+
+/// A read-only connection borrowed from the pool; goes back to the pool when dropped.
+pub struct ReadConnection<'a> {
+    conn: Option<Connection>,
+    db: &'a Database,
+}
+
+impl Deref for ReadConnection<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection is only taken on drop")
+    }
+}
+
+impl Drop for ReadConnection<'_> {
+    fn drop(&mut self) {
+        if let (Some(conn), Ok(mut readers)) = (self.conn.take(), self.db.readers.lock()) {
+            readers.push(conn);
+            self.db.reader_returned.notify_one();
+        }
+    }
+}
+
+impl Database {
+    /// Switches the user database to WAL and opens `pool_size` read-only connections with
+    /// the compendium attached. `writer` must already be migrated and have the compendium attached.
+    pub fn new(writer: Connection, user_database: &Path, compendium: &Path, pool_size: usize) -> AppResult<Self> {
+        writer.pragma_update_and_check(Some(DatabaseName::Main), "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        writer.pragma_update(Some(DatabaseName::Main), "synchronous", "NORMAL")?;
+
+        let readers = (0..pool_size.max(1))
+            .map(|_| open_reader(user_database, compendium))
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self { writer: Mutex::new(writer), readers: Mutex::new(readers), reader_returned: Condvar::new() })
+    }
+
+    /// Borrows a read-only connection, waiting while all of them are in use.
+    pub fn read(&self) -> AppResult<ReadConnection<'_>> {
+        let mut readers = map_lock_error(self.readers.lock())?;
+        loop {
+            if let Some(conn) = readers.pop() {
+                return Ok(ReadConnection { conn: Some(conn), db: self });
+            }
+            readers = map_lock_error(self.reader_returned.wait(readers))?;
+        }
+    }
+
+    /// Exclusive access to the writer connection. Hold it only for the mutation itself.
+    pub fn write(&self) -> AppResult<MutexGuard<'_, Connection>> {
+        map_lock_error(self.writer.lock())
+    }
+}
+
+fn open_reader(user_database: &Path, compendium: &Path) -> AppResult<Connection> {
+    let conn = Connection::open_with_flags(
+        user_database,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_URI | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    attach_compendium(&conn, compendium)?;
+    Ok(conn)
+}
@@ -0,0 +1,168 @@
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde_json::Value;
+use uuid::Uuid;
+use crate::core::multiclass::normalize_classes;
+use crate::core::resources::{default_uses, FeatureResource, UsesContext};
+use crate::core::sheet::DerivedSheet;
+use crate::db::characters::class_id_candidates;
+use crate::db::sheet::compute_sheet;
+use crate::error::{AppError, AppResult};
+use crate::types::character::{Character, ClassLevel};
+
+/// Bogenwerte plus Stufe und Stufentabelle der Klasse als Grundlage für `uses_per_rest`.
+struct SheetUsesContext<'a> {
+    sheet: &'a DerivedSheet,
+    /// Stufe in der Klasse, der das Merkmal gehört (nicht die Gesamtstufe)
+    level: i32,
+    progression: Value,
+}
+
+impl UsesContext for SheetUsesContext<'_> {
+    fn proficiency_bonus(&self) -> i32 {
+        self.sheet.proficiency_bonus
+    }
+
+    fn level(&self) -> i32 {
+        self.level
+    }
+
+    fn ability_modifier(&self, ability: &str) -> i32 {
+        self.sheet.ability_modifier(ability)
+    }
+
+    fn progression_value(&self, key: &str) -> Option<i32> {
+        match self.progression.get(key)? {
+            Value::Number(n) => n.as_i64().map(|v| v as i32),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Loads all limited-use features of a character with resolved maximum and current uses.
+///
+/// Features qualify if they have a `uses_per_rest` expression in the compendium or a
+/// built-in default (see `core::resources::default_uses`). Multiclass characters get the
+/// features of every class up to that class's own level.
+pub fn load_feature_resources(conn: &Connection, character: &Character) -> AppResult<Vec<FeatureResource>> {
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
+    if meta.classes.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let sheet = compute_sheet(conn, character)?;
+    let mut resources = Vec::new();
+    for class in &meta.classes {
+        load_class_resources(conn, character, &sheet, class, &mut resources)?;
+    }
+    Ok(resources)
+}
+
+fn load_class_resources(
+    conn: &Connection,
+    character: &Character,
+    sheet: &DerivedSheet,
+    class: &ClassLevel,
+    resources: &mut Vec<FeatureResource>,
+) -> AppResult<()> {
+    let class_ids = class_id_candidates(&class.class_id);
+    let placeholders = vec!["?"; class_ids.len()].join(", ");
+
+    let progression_sql = format!(
+        "SELECT class_specific_data FROM core_progression_tables WHERE class_id IN ({}) AND level = ? LIMIT 1",
+        placeholders
+    );
+    let mut values: Vec<rusqlite::types::Value> = class_ids.iter().map(|id| id.clone().into()).collect();
+    values.push(i64::from(class.level).into());
+    let progression: Option<String> = conn
+        .query_row(&progression_sql, params_from_iter(values.iter()), |row| row.get(0))
+        .optional()?
+        .flatten();
+    let context = SheetUsesContext {
+        sheet,
+        level: class.level,
+        progression: progression.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or(Value::Null),
+    };
+
+    let features_sql = format!(
+        "SELECT f.id, f.name, f.uses_per_rest, f.rest_type, COALESCE(r.uses_spent, 0)
+         FROM all_class_features f
+         LEFT JOIN character_feature_resources r ON r.feature_id = f.id AND r.character_id = ?
+         WHERE f.class_id IN ({}) AND f.level <= ?
+           AND (f.subclass_id IS NULL OR f.subclass_id = ?)
+         ORDER BY f.level, f.name",
+        placeholders
+    );
+    let mut values: Vec<rusqlite::types::Value> = vec![character.id.to_string().into()];
+    values.extend(class_ids.iter().map(|id| id.clone().into()));
+    values.push(i64::from(class.level).into());
+    values.push(class.subclass_id.clone().unwrap_or_default().into());
+
+    let mut stmt = conn.prepare(&features_sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, i32>(4)?,
+        ))
+    })?;
+
+    for row in rows {
+        let (id, name, uses_per_rest, rest_type, spent) = row?;
+        let default = default_uses(&id);
+        let expression = match uses_per_rest.filter(|u| !u.trim().is_empty()) {
+            Some(expression) => expression,
+            None => match default {
+                Some((expression, _)) => expression.to_string(),
+                None => continue,
+            },
+        };
+        let rest_type = rest_type
+            .filter(|r| !r.trim().is_empty())
+            .or_else(|| default.map(|(_, r)| r.to_string()));
+        // Overrides in custom_class_features ersetzen das Kernmerkmal gleicher ID
+        resources.retain(|r: &FeatureResource| r.feature_id != id);
+        resources.push(FeatureResource::new(id, name, expression, rest_type, spent, &context));
+    }
+    Ok(())
+}
+
+/// Loads a single resource of a character.
+pub fn load_feature_resource(conn: &Connection, character: &Character, feature_id: &str) -> AppResult<FeatureResource> {
+    load_feature_resources(conn, character)?
+        .into_iter()
+        .find(|r| r.feature_id == feature_id)
+        .ok_or_else(|| AppError::InvalidInput(format!("Merkmal '{}' hat keine begrenzten Anwendungen", feature_id)))
+}
+
+/// Writes the spent counter of a resource.
+pub fn save_uses_spent(conn: &Connection, character_id: &str, feature_id: &str, uses_spent: i32) -> AppResult<()> {
+    conn.execute(
+        "INSERT INTO character_feature_resources (id, character_id, feature_id, uses_spent, created_at, updated_at)
+         VALUES (?, ?, ?, ?, (unixepoch()), (unixepoch()))
+         ON CONFLICT(character_id, feature_id) DO UPDATE SET
+            uses_spent = excluded.uses_spent,
+            updated_at = (unixepoch())",
+        params![Uuid::new_v4().to_string(), character_id, feature_id, uses_spent],
+    )?;
+    Ok(())
+}
+
+/// Spends `amount` uses of a feature.
+pub fn spend_feature_resource(conn: &Connection, character: &Character, feature_id: &str, amount: i32) -> AppResult<FeatureResource> {
+    let mut resource = load_feature_resource(conn, character, feature_id)?;
+    resource.spend(amount).map_err(AppError::InvalidInput)?;
+    save_uses_spent(conn, &character.id.to_string(), feature_id, resource.uses_spent)?;
+    Ok(resource)
+}
+
+/// Restores `amount` uses of a feature (`None` = all).
+pub fn restore_feature_resource(conn: &Connection, character: &Character, feature_id: &str, amount: Option<i32>) -> AppResult<FeatureResource> {
+    let mut resource = load_feature_resource(conn, character, feature_id)?;
+    resource.restore(amount).map_err(AppError::InvalidInput)?;
+    save_uses_spent(conn, &character.id.to_string(), feature_id, resource.uses_spent)?;
+    Ok(resource)
+}
@@ -0,0 +1,81 @@
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+use crate::core::dice::DiceRng;
+use crate::core::rest::{long_rest, short_rest, RestContext, RestFeature, RestSummary, RestType};
+use crate::db::characters::{class_id_candidates, load_character, save_character};
+use crate::db::resources::{load_feature_resources, save_uses_spent};
+use crate::db::sheet::compute_sheet;
+use crate::error::{AppError, AppResult};
+use crate::types::character::Character;
+
+/// Short rest: spends hit dice, restores Pact Magic and short-rest features, persists the character.
+pub fn take_short_rest(
+    conn: &Connection,
+    character_id: &str,
+    hit_dice_to_spend: i32,
+    seed: Option<u64>,
+) -> AppResult<RestSummary> {
+    let mut character = load_character(conn, character_id)?;
+    let context = load_rest_context(conn, &character)?;
+    let seed = seed.unwrap_or_else(DiceRng::random_seed);
+    let summary = short_rest(&mut character, hit_dice_to_spend, &context, seed)
+        .map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    reset_feature_resources(conn, &character, &context, RestType::Short)?;
+    Ok(summary)
+}
+
+/// Long rest: full HP, all hit dice, all spell slots and features, persists the character.
+pub fn take_long_rest(conn: &Connection, character_id: &str) -> AppResult<RestSummary> {
+    let mut character = load_character(conn, character_id)?;
+    let context = load_rest_context(conn, &character)?;
+    let summary = long_rest(&mut character, &context).map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    reset_feature_resources(conn, &character, &context, RestType::Long)?;
+    Ok(summary)
+}
+
+/// Trefferwürfel aus `core_classes`, KON-Mod. und TP-Maximum aus dem abgeleiteten Bogen,
+/// verbrauchte Merkmals-Ressourcen aus `character_feature_resources`.
+pub fn load_rest_context(conn: &Connection, character: &Character) -> AppResult<RestContext> {
+    let sheet = compute_sheet(conn, character)?;
+    let hit_die = match character.meta.class_id.as_deref() {
+        Some(class_id) => class_hit_die(conn, class_id)?,
+        None => 0,
+    };
+
+    // Nur tatsächlich verbrauchte Ressourcen werden aufgefrischt und gemeldet
+    let features = load_feature_resources(conn, character)?
+        .into_iter()
+        .filter(|r| r.uses_spent > 0)
+        .filter_map(|r| Some(RestFeature { rest_type: r.rest_type?, id: r.feature_id, name: r.name }))
+        .collect();
+
+    Ok(RestContext {
+        hit_die,
+        con_modifier: sheet.ability_modifier("con"),
+        hp_max: sheet.hp_max,
+        features,
+    })
+}
+
+/// Trefferwürfel einer Klasse aus `core_classes` (0 = unbekannt).
+pub fn class_hit_die(conn: &Connection, class_id: &str) -> AppResult<u32> {
+    let class_ids = class_id_candidates(class_id);
+    let sql = format!(
+        "SELECT CAST(json_extract(data, '$.hit_die') AS INTEGER) FROM core_classes
+         WHERE id IN ({}) LIMIT 1",
+        vec!["?"; class_ids.len()].join(", ")
+    );
+    Ok(conn.query_row(&sql, params_from_iter(class_ids.iter()), |row| row.get::<_, Option<u32>>(0))
+        .optional()?
+        .flatten()
+        .unwrap_or(0))
+}
+
+fn reset_feature_resources(conn: &Connection, character: &Character, context: &RestContext, rest_type: RestType) -> AppResult<()> {
+    let character_id = character.id.to_string();
+    for feature in context.features.iter().filter(|f| rest_type.restores(&f.rest_type)) {
+        save_uses_spent(conn, &character_id, &feature.id, 0)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, VecDeque};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use uuid::Uuid;
+use crate::error::AppResult;
+
+/// A character-owned table that is synced row by row.
+pub(crate) struct RowTable<'a> {
+    pub table: &'a str,
+    /// Columns identifying a row within a character. `["id"]` uses the struct's stable id;
+    /// natural keys (e.g. `["type", "ref_id"]`) keep the existing row id, new rows get a UUID.
+    pub key: &'a [&'a str],
+    /// Remaining columns written by the sync; other columns are left untouched.
+    pub columns: &'a [&'a str],
+}
+
+/// Desired state of one row: values for `key` and `columns`, in declaration order.
+pub(crate) struct SyncRow {
+    pub key: Vec<Value>,
+    pub values: Vec<Value>,
+}
+
+impl SyncRow {
+    pub fn new(key: Vec<Value>, values: Vec<Value>) -> Self {
+        Self { key, values }
+    }
+}
+
+pub(crate) fn bool_value(value: bool) -> Value {
+    Value::Integer(value.into())
+}
+
+fn key_string(key: &[Value]) -> String {
+    key.iter().map(|v| format!("{:?}", v)).collect::<Vec<_>>().join("\u{1f}")
+}
+
+/// Writes `rows` as the new state of `table` for a character: inserts new rows, updates changed
+/// ones (bumping `updated_at`) and deletes missing ones. Unchanged rows keep their id and
+/// `created_at`. Repeated natural keys (e.g. a feat taken twice) match existing rows in order.
+pub(crate) fn sync_rows(conn: &Connection, spec: &RowTable, character_id: &str, rows: &[SyncRow]) -> AppResult<()> {
+    let id_is_key = spec.key == ["id"];
+    let select_columns: Vec<&str> = spec.key.iter().chain(spec.columns).copied().collect();
+
+    let mut existing: HashMap<String, VecDeque<(String, Vec<Value>)>> = HashMap::new();
+    {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, {} FROM {} WHERE character_id = ? ORDER BY created_at, rowid",
+            select_columns.join(", "),
+            spec.table
+        ))?;
+        let mut result = stmt.query([character_id])?;
+        while let Some(row) = result.next()? {
+            let id: String = row.get(0)?;
+            let key: Vec<Value> = (0..spec.key.len()).map(|i| row.get(i + 1)).collect::<Result<_, _>>()?;
+            let values: Vec<Value> = (0..spec.columns.len())
+                .map(|i| row.get(i + 1 + spec.key.len()))
+                .collect::<Result<_, _>>()?;
+            existing.entry(key_string(&key)).or_default().push_back((id, values));
+        }
+    }
+
+    let insert_columns: Vec<&str> = if id_is_key {
+        select_columns.clone()
+    } else {
+        std::iter::once("id").chain(select_columns.iter().copied()).collect()
+    };
+    let insert_sql = format!(
+        "INSERT INTO {} (character_id, {}) VALUES (?, {})",
+        spec.table,
+        insert_columns.join(", "),
+        vec!["?"; insert_columns.len()].join(", ")
+    );
+    let update_sql = format!(
+        "UPDATE {} SET {}, updated_at = (unixepoch()) WHERE id = ?",
+        spec.table,
+        spec.columns.iter().map(|c| format!("{} = ?", c)).collect::<Vec<_>>().join(", ")
+    );
+
+    for row in rows {
+        let key = key_string(&row.key);
+        match existing.get_mut(&key).and_then(VecDeque::pop_front) {
+            Some((id, values)) => {
+                if values != row.values {
+                    let params = row.values.iter().cloned().chain(std::iter::once(Value::Text(id)));
+                    conn.execute(&update_sql, params_from_iter(params))?;
+                }
+            }
+            // Doppelte IDs im Struct: nur die erste Zeile zählt
+            None if id_is_key && existing.contains_key(&key) => {}
+            None => {
+                let mut params = vec![Value::Text(character_id.to_string())];
+                if !id_is_key {
+                    params.push(Value::Text(Uuid::new_v4().to_string()));
+                }
+                params.extend(row.key.iter().cloned());
+                params.extend(row.values.iter().cloned());
+                conn.execute(&insert_sql, params_from_iter(params))?;
+                if id_is_key {
+                    existing.entry(key).or_default();
+                }
+            }
+        }
+    }
+
+    // Übrig sind Zeilen, die im Struct nicht mehr vorkommen
+    let delete_sql = format!("DELETE FROM {} WHERE id = ?", spec.table);
+    for (id, _) in existing.into_values().flatten() {
+        conn.execute(&delete_sql, [id])?;
+    }
+    Ok(())
+}
+
+/// Runs `f` inside a savepoint: all its writes are committed together or rolled back on error.
+/// Works inside an open transaction as well as on a plain connection.
+pub(crate) fn atomically<T>(conn: &Connection, name: &str, f: impl FnOnce() -> AppResult<T>) -> AppResult<T> {
+    conn.execute_batch(&format!("SAVEPOINT {}", name))?;
+    match f() {
+        Ok(value) => {
+            conn.execute_batch(&format!("RELEASE {}", name))?;
+            Ok(value)
+        }
+        Err(e) => {
+            let _ = conn.execute_batch(&format!("ROLLBACK TO {name}; RELEASE {name}", name = name));
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::core::armor::{ArmorCategory, ArmorPiece};
+use crate::core::multiclass::normalize_classes;
+use crate::core::sheet::{default_spellcasting_ability, derive_sheet, DerivedSheet, SheetInputs, DEFAULT_SPEED_FT};
+use crate::core::units::METERS_PER_FOOT;
+use crate::error::AppResult;
+use crate::types::character::Character;
+
+/// Computes the full derived sheet for a character.
+pub fn compute_sheet(conn: &Connection, character: &Character) -> AppResult<DerivedSheet> {
+    let inputs = load_sheet_inputs(conn, character)?;
+    Ok(derive_sheet(character, &inputs))
+}
+
+/// Loads the compendium facts (armor, speed, spellcasting ability, spell slots, weight) needed by `derive_sheet`.
+pub fn load_sheet_inputs(conn: &Connection, character: &Character) -> AppResult<SheetInputs> {
+    let mut inputs = SheetInputs::default();
+
+    // 1. Angelegte Rüstungen & Schilde (inkl. magischer Rüstungen); ausgewählt wird am Ende
+    let mut armors: Vec<ArmorPiece> = Vec::new();
+    let mut shields: Vec<ArmorPiece> = Vec::new();
+    let mut loose_armor_bonus: Option<(String, i32)> = None;
+    let mut loose_shield_bonus: Option<(String, i32)> = None;
+    for item in character.inventory.iter().filter(|i| i.is_equipped) {
+        if let Some(piece) = load_armor_piece(conn, &item.item_id)? {
+            push_armor_piece(&mut armors, &mut shields, piece);
+            continue;
+        }
+
+        let Some((name, bonus, is_shield)) = load_magic_armor(conn, &item.item_id)? else { continue };
+        // Magische Rüstung verweist per custom_data.base_item_id auf die zugrunde liegende Rüstung
+        let base_id = item.custom_data.as_ref()
+            .and_then(|d| d.get("base_item_id"))
+            .and_then(|v| v.as_str());
+        match base_id {
+            Some(base_id) => {
+                if let Some(mut piece) = load_armor_piece(conn, base_id)? {
+                    piece.magic_bonus = bonus;
+                    piece.magic_source = Some(name);
+                    push_armor_piece(&mut armors, &mut shields, piece);
+                }
+            }
+            // Ohne Basis wirkt der Bonus auf die ansonsten getragene Rüstung bzw. den Schild
+            None if is_shield => loose_shield_bonus = Some((name, bonus)),
+            None => loose_armor_bonus = Some((name, bonus)),
+        }
+    }
+    for (pieces, loose) in [(&mut armors, loose_armor_bonus), (&mut shields, loose_shield_bonus)] {
+        let Some((name, bonus)) = loose else { continue };
+        for piece in pieces.iter_mut().filter(|p| p.magic_bonus == 0) {
+            piece.magic_bonus = bonus;
+            piece.magic_source = Some(name.clone());
+        }
+    }
+
+    // 2. Geführte Waffen (für Bedingungen wie "wielding == two_handed")
+    let mut wielding = "none";
+    for item in character.inventory.iter().filter(|i| i.is_equipped) {
+        let is_weapon: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM all_weapons_unified WHERE id = ?)",
+            params![item.item_id],
+            |row| row.get(0),
+        )?;
+        if !is_weapon {
+            continue;
+        }
+        let two_handed: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM weapon_property_mappings_unified
+                           WHERE weapon_id = ? AND property_id IN ('zweihaendig', 'two-handed'))",
+            params![item.item_id],
+            |row| row.get(0),
+        )?;
+        let custom_two_handed = item.custom_data.as_ref()
+            .and_then(|d| d.get("two_handed"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if two_handed || custom_two_handed {
+            wielding = "two_handed";
+        } else if wielding == "none" {
+            wielding = "one_handed";
+        }
+    }
+    inputs.wielding = wielding.to_string();
+
+    // 3. Bewegungsrate der Spezies (in Metern gespeichert)
+    if let Some(species_id) = &character.meta.species_id {
+        let speed_m: Option<f64> = conn.query_row(
+            "SELECT json_extract(data, '$.speed') FROM all_species WHERE id = ?",
+            params![species_id],
+            |row| row.get(0),
+        ).optional()?.flatten();
+        inputs.base_speed_ft = speed_m
+            .map(|m| (m / METERS_PER_FOOT).round() as i32)
+            .unwrap_or(DEFAULT_SPEED_FT);
+    }
+
+    // 4. Zauberattribut: explizit am Charakter, sonst aus der ersten zaubernden Klasse
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
+    inputs.spellcasting_ability = character.spellcasting.as_ref()
+        .map(|s| s.ability.clone())
+        .filter(|a| !a.trim().is_empty())
+        .or_else(|| {
+            meta.classes.iter()
+                .find_map(|c| default_spellcasting_ability(&c.class_id))
+                .map(|a| a.to_string())
+        });
+
+    // 5. Zauberplätze aus den Stufentabellen (ohne Klasse: gespeicherte Werte)
+    if !meta.classes.is_empty() {
+        inputs.spell_slots = Some(crate::db::spell_slots::load_slot_maxima(conn, &meta.classes)?);
+    }
+
+    // 6. Getragenes Gewicht (Reittier und magische Behälter zählen nicht)
+    for item in &character.inventory {
+        let location = item.location.as_deref().unwrap_or("Body");
+        if location == "Mount" || location == "MagicContainer" {
+            continue;
+        }
+        inputs.carried_weight_kg += lookup_item_weight(conn, &item.item_id)? * item.quantity as f64;
+    }
+
+    // 7. Bei mehreren angelegten Rüstungen oder Schilden zählt die Kombination mit der höchsten RK
+    select_best_armor(character, &mut inputs, &armors, &shields);
+
+    Ok(inputs)
+}
+
+/// Setzt Rüstung und Schild auf die Kombination mit der höchsten RK. Bei Gleichstand gewinnt
+/// das zuerst im Inventar stehende Teil.
+fn select_best_armor(character: &Character, inputs: &mut SheetInputs, armors: &[ArmorPiece], shields: &[ArmorPiece]) {
+    inputs.armor = armors.first().cloned();
+    inputs.shield = shields.first().cloned();
+    if armors.len() <= 1 && shields.len() <= 1 {
+        return;
+    }
+
+    let options = |pieces: &[ArmorPiece]| -> Vec<Option<ArmorPiece>> {
+        if pieces.is_empty() { vec![None] } else { pieces.iter().cloned().map(Some).collect() }
+    };
+    let mut best: Option<(i32, Option<ArmorPiece>, Option<ArmorPiece>)> = None;
+    for armor in options(armors) {
+        for shield in options(shields) {
+            let candidate = SheetInputs { armor: armor.clone(), shield: shield.clone(), ..inputs.clone() };
+            let armor_class = derive_sheet(character, &candidate).armor_class;
+            if best.as_ref().is_none_or(|(best_ac, _, _)| armor_class > *best_ac) {
+                best = Some((armor_class, armor.clone(), shield));
+            }
+        }
+    }
+    if let Some((_, armor, shield)) = best {
+        inputs.armor = armor;
+        inputs.shield = shield;
+    }
+}
+
+fn load_armor_piece(conn: &Connection, armor_id: &str) -> AppResult<Option<ArmorPiece>> {
+    let piece = conn.query_row(
+        "SELECT name, category, base_ac, ac_bonus, ac_formula, strength_requirement, stealth_disadvantage
+         FROM all_armors WHERE id = ?",
+        params![armor_id],
+        |row| {
+            let name: String = row.get(0)?;
+            let category: String = row.get(1)?;
+            let Some(category) = ArmorCategory::from_db(&category) else { return Ok(None) };
+            Ok(Some(ArmorPiece {
+                base_ac: row.get(2)?,
+                ac_bonus: row.get::<_, Option<i32>>(3)?.unwrap_or(0),
+                ac_formula: row.get(4)?,
+                strength_requirement: row.get(5)?,
+                stealth_disadvantage: row.get::<_, Option<bool>>(6)?.unwrap_or(false),
+                ..ArmorPiece::new(&name, category)
+            }))
+        },
+    ).optional()?;
+    Ok(piece.flatten())
+}
+
+/// Magische Rüstung: (Name, RK-Bonus, ist Schild). Der Bonus steht in `ac_bonus`,
+/// in `data.bonuses.ac` oder nur im Namen ("RÜSTUNG +1").
+fn load_magic_armor(conn: &Connection, item_id: &str) -> AppResult<Option<(String, i32, bool)>> {
+    let row: Option<(String, Option<i32>, Option<String>)> = conn.query_row(
+        "SELECT b.name,
+                COALESCE(a.ac_bonus, json_extract(b.data, '$.bonuses.ac')),
+                json_extract(b.data, '$.raw.meta_line')
+         FROM all_mag_armor a
+         JOIN all_mag_items_base b ON b.id = a.item_base_id
+         WHERE a.item_base_id = ?",
+        params![item_id],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional()?;
+
+    Ok(row.map(|(name, bonus, meta_line)| {
+        let bonus = bonus.or_else(|| bonus_from_name(&name)).unwrap_or(0);
+        let is_shield = name.to_lowercase().contains("schild")
+            || meta_line.is_some_and(|m| m.contains("(Schild)"));
+        (name, bonus, is_shield)
+    }))
+}
+
+fn push_armor_piece(armors: &mut Vec<ArmorPiece>, shields: &mut Vec<ArmorPiece>, piece: ArmorPiece) {
+    if piece.category == ArmorCategory::Shield {
+        shields.push(piece);
+    } else {
+        armors.push(piece);
+    }
+}
+
+pub(crate) fn bonus_from_name(name: &str) -> Option<i32> {
+    let (_, rest) = name.rsplit_once('+')?;
+    rest.trim().parse().ok()
+}
+
+fn lookup_item_weight(conn: &Connection, item_id: &str) -> AppResult<f64> {
+    let views = ["all_weapons_unified", "all_armors", "all_items", "all_gear", "all_tools"];
+    for view in views {
+        let weight: Option<f64> = conn.query_row(
+            &format!("SELECT weight_kg FROM {} WHERE id = ?", view),
+            params![item_id],
+            |row| row.get(0),
+        ).optional()?;
+        if let Some(w) = weight {
+            return Ok(w);
+        }
+    }
+    Ok(0.0)
+}
@@ -0,0 +1,180 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use crate::core::spell_parser::{
+    parse_spell, CastingTime, SpellComponents, SpellDuration, SpellMechanics, SpellRange, SpellScaling,
+};
+use crate::db::row_sync::atomically;
+use crate::db::spells::{load_all_spells, load_spell};
+use crate::error::AppResult;
+use crate::types::spell::Spell;
+
+/// `settings` key holding the fingerprint of the stored mechanics.
+const FINGERPRINT_SETTING: &str = "spell_mechanics_fingerprint";
+
+/// snake_case name of a parsed enum value as stored in `spell_mechanics`.
+fn enum_str<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_value(value).ok()?.as_str().map(str::to_string)
+}
+
+/// Reads a column written by `enum_str` back into the enum.
+fn enum_column<T: DeserializeOwned>(row: &Row, idx: usize) -> rusqlite::Result<Option<T>> {
+    let Some(value) = row.get::<_, Option<String>>(idx)? else { return Ok(None) };
+    serde_json::from_value(Value::String(value))
+        .map(Some)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(e)))
+}
+
+fn required_enum_column<T: DeserializeOwned>(row: &Row, idx: usize) -> rusqlite::Result<T> {
+    enum_column(row, idx)?.ok_or_else(|| rusqlite::Error::InvalidColumnType(idx, "NULL".into(), Type::Null))
+}
+
+fn insert_mechanics(conn: &Connection, spell_id: &str, m: &SpellMechanics) -> AppResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO spell_mechanics (
+            spell_id, verbal, somatic, material, material_text, material_cost_gp, material_consumed,
+            range_type, range_ft, range_m, range_area,
+            duration_type, duration_amount, duration_unit, duration_seconds, duration_up_to, concentration,
+            casting_time_type, casting_time_amount, casting_time_unit, casting_time_seconds, ritual, casting_trigger,
+            scaling_type, scaling_base_level, scaling_dice, updated_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                  ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, unixepoch())",
+        params![
+            spell_id,
+            m.components.verbal,
+            m.components.somatic,
+            m.components.material,
+            m.components.material_text,
+            m.components.material_cost_gp,
+            m.components.material_consumed,
+            enum_str(&m.range.kind),
+            m.range.feet,
+            m.range.meters,
+            m.range.area,
+            enum_str(&m.duration.kind),
+            m.duration.amount,
+            m.duration.unit.map(|u| u.as_str()),
+            m.duration.seconds(),
+            m.duration.up_to,
+            m.duration.concentration,
+            enum_str(&m.casting_time.kind),
+            m.casting_time.amount,
+            m.casting_time.unit.map(|u| u.as_str()),
+            m.casting_time.seconds(),
+            m.casting_time.ritual,
+            m.casting_time.trigger,
+            m.scaling.as_ref().and_then(|s| enum_str(&s.kind)),
+            m.scaling.as_ref().and_then(|s| s.base_level),
+            m.scaling.as_ref().and_then(|s| s.dice.clone()),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Re-parses all spells into `spell_mechanics` in one transaction. Rows of deleted spells are
+/// removed. Parsing only happens when the spell texts or the parser changed since the last
+/// rebuild, so a regular start neither parses nor writes.
+///
+/// # Returns
+/// Number of rewritten spells, 0 if nothing changed
+pub fn rebuild_spell_mechanics(conn: &Connection) -> AppResult<usize> {
+    let spells = load_all_spells(conn)?;
+    let fingerprint = spells_fingerprint(&spells);
+    let stored: Option<String> = conn
+        .query_row("SELECT value FROM main.settings WHERE key = ?", [FINGERPRINT_SETTING], |row| row.get(0))
+        .optional()?;
+    if stored.as_deref() == Some(fingerprint.as_str()) {
+        return Ok(0);
+    }
+
+    atomically(conn, "rebuild_spell_mechanics", || {
+        conn.execute("DELETE FROM spell_mechanics", [])?;
+        for spell in &spells {
+            insert_mechanics(conn, &spell.id, &parse_spell(spell))?;
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO main.settings (key, value) VALUES (?, ?)",
+            params![FINGERPRINT_SETTING, fingerprint],
+        )?;
+        Ok(spells.len())
+    })
+}
+
+/// Fingerprint over all spells and the parser source, so a changed parser also triggers a rebuild.
+fn spells_fingerprint(spells: &[Spell]) -> String {
+    let mut hasher = DefaultHasher::new();
+    include_str!("../core/spell_parser.rs").hash(&mut hasher);
+    for spell in spells {
+        serde_json::to_string(spell).unwrap_or_default().hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// Re-parses a single spell after it was created, changed or deleted. Without the spell
+/// (e.g. a deleted homebrew spell) its row is removed.
+pub fn refresh_spell_mechanics(conn: &Connection, spell_id: &str) -> AppResult<()> {
+    // Ein gelöschter Override gibt den Blick auf den Kernzauber gleicher ID wieder frei
+    match load_spell(conn, spell_id)? {
+        Some(spell) => insert_mechanics(conn, spell_id, &parse_spell(&spell)),
+        None => {
+            conn.execute("DELETE FROM spell_mechanics WHERE spell_id = ?", [spell_id])?;
+            Ok(())
+        }
+    }
+}
+
+/// Parsed components, range, duration, casting time and scaling of a spell, as stored in
+/// `spell_mechanics`.
+pub fn get_spell_mechanics(conn: &Connection, spell_id: &str) -> AppResult<Option<SpellMechanics>> {
+    let mechanics = conn
+        .query_row(
+            "SELECT verbal, somatic, material, material_text, material_cost_gp, material_consumed,
+                    range_type, range_ft, range_m, range_area,
+                    duration_type, duration_amount, duration_unit, duration_up_to, concentration,
+                    casting_time_type, casting_time_amount, casting_time_unit, ritual, casting_trigger,
+                    scaling_type, scaling_base_level, scaling_dice
+             FROM spell_mechanics WHERE spell_id = ?",
+            [spell_id],
+            |row| {
+                let (base_level, dice) = (row.get(21)?, row.get(22)?);
+                let scaling = enum_column(row, 20)?.map(|kind| SpellScaling { kind, base_level, dice });
+                Ok(SpellMechanics {
+                    components: SpellComponents {
+                        verbal: row.get(0)?,
+                        somatic: row.get(1)?,
+                        material: row.get(2)?,
+                        material_text: row.get(3)?,
+                        material_cost_gp: row.get(4)?,
+                        material_consumed: row.get(5)?,
+                    },
+                    range: SpellRange {
+                        kind: required_enum_column(row, 6)?,
+                        feet: row.get(7)?,
+                        meters: row.get(8)?,
+                        area: row.get(9)?,
+                    },
+                    duration: SpellDuration {
+                        kind: required_enum_column(row, 10)?,
+                        amount: row.get(11)?,
+                        unit: enum_column(row, 12)?,
+                        up_to: row.get(13)?,
+                        concentration: row.get(14)?,
+                    },
+                    casting_time: CastingTime {
+                        kind: required_enum_column(row, 15)?,
+                        amount: row.get(16)?,
+                        unit: enum_column(row, 17)?,
+                        ritual: row.get(18)?,
+                        trigger: row.get(19)?,
+                    },
+                    scaling,
+                })
+            },
+        )
+        .optional()?;
+    Ok(mechanics)
+}
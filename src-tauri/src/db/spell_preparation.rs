@@ -0,0 +1,98 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use crate::core::multiclass::normalize_classes;
+use crate::core::spell_preparation::{
+    self, preparation_status, progression_prepared_count, PreparableSpell, PreparationClass,
+    PreparationStatus, SpellInfo,
+};
+use crate::core::spell_slots::{class_slot_maxima, has_progression_slots, progression_spell_slots, ClassSlotInput};
+use crate::db::characters::{load_character, save_character};
+use crate::db::spell_slots::load_progression_data;
+use crate::error::{AppError, AppResult};
+use crate::types::character::Character;
+
+/// Preparable spell candidates together with the current count and limit.
+#[derive(Debug, Serialize)]
+pub struct PreparableSpells {
+    pub status: PreparationStatus,
+    pub spells: Vec<PreparableSpell>,
+}
+
+/// Vorbereitungs-Grenzen je Klasse aus der Stufentabelle.
+pub(crate) fn load_preparation_classes(conn: &Connection, character: &Character) -> AppResult<Vec<PreparationClass>> {
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
+
+    let mut classes = Vec::new();
+    for class in meta.classes {
+        let data = load_progression_data(conn, &class.class_id, class.level)?;
+        let progression = data.as_ref().filter(|d| has_progression_slots(d)).map(progression_spell_slots);
+        classes.push(PreparationClass {
+            limit: data.as_ref().and_then(progression_prepared_count),
+            max_spell_level: class_slot_maxima(&ClassSlotInput { class: class.clone(), progression }).max_spell_level(),
+            class_id: class.class_id,
+        });
+    }
+    Ok(classes)
+}
+
+fn spell_info(row: &rusqlite::Row) -> rusqlite::Result<SpellInfo> {
+    Ok(SpellInfo {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        level: row.get(2)?,
+        classes: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+    })
+}
+
+/// Kompendium-Daten der Zauber des Charakters.
+fn load_character_spell_infos(conn: &Connection, character: &Character) -> AppResult<Vec<SpellInfo>> {
+    let mut stmt = conn.prepare("SELECT id, name, level, classes FROM all_spells WHERE id = ?")?;
+    let mut infos: Vec<SpellInfo> = Vec::new();
+    for entry in &character.spells {
+        if infos.iter().any(|i| i.id == entry.spell_id) {
+            continue;
+        }
+        let mut rows = stmt.query_map(params![entry.spell_id], spell_info)?;
+        if let Some(info) = rows.next() {
+            infos.push(info?);
+        }
+    }
+    Ok(infos)
+}
+
+/// Lists all spells the character may prepare (class list and castable level), level 1 and up.
+pub fn get_preparable_spells(conn: &Connection, character_id: &str) -> AppResult<PreparableSpells> {
+    let character = load_character(conn, character_id)?;
+    let classes = load_preparation_classes(conn, &character)?;
+    let spells = load_character_spell_infos(conn, &character)?;
+
+    let mut stmt = conn.prepare("SELECT id, name, level, classes FROM all_spells WHERE level > 0 ORDER BY level, name")?;
+    let compendium = stmt.query_map([], spell_info)?.collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PreparableSpells {
+        status: preparation_status(&character, &classes, &spells),
+        spells: spell_preparation::preparable_spells(&character, &classes, &compendium),
+    })
+}
+
+/// Prepares or unprepares one spell (`character_spells.id`). Preparing is validated; the
+/// returned issues are the ones that blocked the change, in which case nothing is saved.
+pub fn update_spell_preparation(conn: &Connection, entry_id: &str, is_prepared: bool) -> AppResult<PreparationStatus> {
+    let character_id: String = conn
+        .query_row("SELECT character_id FROM character_spells WHERE id = ?", params![entry_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| AppError::InvalidInput(format!("Zauber {} nicht gefunden", entry_id)))?;
+    let mut character = load_character(conn, &character_id)?;
+    let classes = load_preparation_classes(conn, &character)?;
+    let spells = load_character_spell_infos(conn, &character)?;
+
+    let before = character.spells.clone();
+    let status = spell_preparation::set_prepared(&mut character, entry_id, is_prepared, &classes, &spells)
+        .map_err(AppError::InvalidInput)?;
+    let changed = character.spells.iter().zip(&before).any(|(a, b)| a.is_prepared != b.is_prepared);
+    if changed {
+        save_character(conn, &character)?;
+    }
+    Ok(status)
+}
@@ -0,0 +1,51 @@
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+use serde_json::Value;
+use crate::core::spell_slots::{
+    self, compute_slot_maxima, has_progression_slots, progression_spell_slots, ClassSlotInput, SlotMaxima,
+};
+use crate::core::sheet::DerivedSheet;
+use crate::db::characters::{class_id_candidates, load_character, save_character};
+use crate::db::sheet::compute_sheet;
+use crate::error::{AppError, AppResult};
+use crate::types::character::ClassLevel;
+
+/// `class_specific_data` der Stufentabelle (`all_progression_tables`); eigene Tabellen gehen vor.
+pub(crate) fn load_progression_data(conn: &Connection, class_id: &str, level: i32) -> AppResult<Option<Value>> {
+    let class_ids = class_id_candidates(class_id);
+    let sql = format!(
+        "SELECT class_specific_data FROM all_progression_tables
+         WHERE class_id IN ({}) AND level = ?
+         ORDER BY CASE source WHEN 'custom' THEN 0 ELSE 1 END
+         LIMIT 1",
+        vec!["?"; class_ids.len()].join(", ")
+    );
+    let mut values: Vec<rusqlite::types::Value> = class_ids.into_iter().map(Into::into).collect();
+    values.push(i64::from(level).into());
+
+    let data: Option<String> = conn
+        .query_row(&sql, params_from_iter(values), |row| row.get(0))
+        .optional()?
+        .flatten();
+    Ok(data.and_then(|d| serde_json::from_str(&d).ok()))
+}
+
+/// Zauberplatz-Maxima der Klassen ohne Modifikatoren.
+pub fn load_slot_maxima(conn: &Connection, classes: &[ClassLevel]) -> AppResult<SlotMaxima> {
+    let mut inputs = Vec::new();
+    for class in classes {
+        let progression = load_progression_data(conn, &class.class_id, class.level)?
+            .filter(has_progression_slots)
+            .map(|data| progression_spell_slots(&data));
+        inputs.push(ClassSlotInput { class: class.clone(), progression });
+    }
+    Ok(compute_slot_maxima(&inputs))
+}
+
+/// Stores (or with `None` removes) a manual spell slot maximum as an override modifier
+/// and returns the recomputed sheet.
+pub fn set_spell_slot_override(conn: &Connection, character_id: &str, target: &str, value: Option<i32>) -> AppResult<DerivedSheet> {
+    let mut character = load_character(conn, character_id)?;
+    spell_slots::set_slot_override(&mut character.modifiers, target, value).map_err(AppError::InvalidInput)?;
+    save_character(conn, &character)?;
+    compute_sheet(conn, &character)
+}
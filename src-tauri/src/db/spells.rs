@@ -1,18 +1,16 @@
-use rusqlite::{params, Connection};
-use crate::types::character::Character;
+use rusqlite::{params, Connection, OptionalExtension};
+use crate::types::character::{Character, CharacterSpell};
+use crate::types::spell::Spell;
+use crate::db::row_sync::{bool_value, sync_rows, RowTable, SyncRow};
 use crate::error::AppResult;
+use rusqlite::types::Value;
 
 /// Synchronizes the character_spells table and spell slot columns with the Character struct.
+/// Unchanged spell entries keep their row and `created_at`.
 pub fn sync_spells(conn: &Connection, character: &Character) -> AppResult<()> {
     let char_id = character.id.to_string();
 
-    // 1. Clear existing spells for this character
-    conn.execute(
-        "DELETE FROM character_spells WHERE character_id = ?",
-        params![char_id],
-    )?;
-
-    // 2. Update spell slots in characters table
+    // 1. Update spell slots in characters table
     conn.execute(
         "UPDATE characters SET 
             spell_slots_1 = ?, spell_slots_2 = ?, spell_slots_3 = ?, 
@@ -21,6 +19,7 @@ pub fn sync_spells(conn: &Connection, character: &Character) -> AppResult<()> {
             spell_slots_used_1 = ?, spell_slots_used_2 = ?, spell_slots_used_3 = ?, 
             spell_slots_used_4 = ?, spell_slots_used_5 = ?, spell_slots_used_6 = ?, 
             spell_slots_used_7 = ?, spell_slots_used_8 = ?, spell_slots_used_9 = ?,
+            pact_slots_used = ?,
             updated_at = (unixepoch())
          WHERE id = ?",
         params![
@@ -42,26 +41,87 @@ pub fn sync_spells(conn: &Connection, character: &Character) -> AppResult<()> {
             character.meta.spell_slots_used_7,
             character.meta.spell_slots_used_8,
             character.meta.spell_slots_used_9,
+            character.meta.pact_slots_used,
             char_id
         ],
     )?;
 
-    // 3. Insert spells from the character struct
-    for spell in &character.spells {
-        conn.execute(
-            "INSERT INTO character_spells (
-                id, character_id, spell_id, is_prepared, is_always_prepared, source
-            ) VALUES (?, ?, ?, ?, ?, ?)",
-            params![
-                spell.id,
-                char_id,
-                spell.spell_id,
-                if spell.is_prepared { 1 } else { 0 },
-                if spell.is_always_prepared { 1 } else { 0 },
-                spell.source,
-            ],
-        )?;
-    }
+    // 2. Diff spells against the character struct (matched by entry id)
+    let rows: Vec<SyncRow> = character.spells.iter().map(|spell| SyncRow::new(
+        vec![Value::Text(spell.id.clone())],
+        vec![
+            Value::Text(spell.spell_id.clone()),
+            bool_value(spell.is_prepared),
+            bool_value(spell.is_always_prepared),
+            spell.source.clone().map_or(Value::Null, Value::Text),
+        ],
+    )).collect();
+    sync_rows(conn, &SPELLS_TABLE, &char_id, &rows)
+}
+
+/// Loads a character's spell entries in the order they were added.
+pub fn load_character_spells(conn: &Connection, character_id: &str) -> AppResult<Vec<CharacterSpell>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, spell_id, is_prepared, is_always_prepared, source
+         FROM character_spells
+         WHERE character_id = ?
+         ORDER BY created_at, rowid"
+    )?;
+    let spells = stmt.query_map(params![character_id], |row| {
+        Ok(CharacterSpell {
+            id: row.get(0)?,
+            spell_id: row.get(1)?,
+            is_prepared: row.get::<_, i32>(2)? != 0,
+            is_always_prepared: row.get::<_, i32>(3)? != 0,
+            source: row.get(4)?,
+        })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(spells)
+}
+
+const SPELLS_TABLE: RowTable = RowTable {
+    table: "character_spells",
+    key: &["id"],
+    columns: &["spell_id", "is_prepared", "is_always_prepared", "source"],
+};
+
+/// Columns of `all_spells` in the order expected by [`spell_from_row`].
+const SPELL_COLUMNS: &str = "id, name, level, school, casting_time, range, components, material_components, duration,
+    concentration, ritual, description, higher_levels, classes, data, source";
+
+fn spell_from_row(row: &rusqlite::Row) -> rusqlite::Result<Spell> {
+    let data: String = row.get(14)?;
+    Ok(Spell {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        level: row.get(2)?,
+        school: row.get(3)?,
+        casting_time: row.get(4)?,
+        range: row.get(5)?,
+        components: row.get(6)?,
+        material_components: row.get(7)?,
+        duration: row.get(8)?,
+        concentration: row.get::<_, i32>(9)? != 0,
+        ritual: row.get::<_, i32>(10)? != 0,
+        description: row.get(11)?,
+        higher_levels: row.get(12)?,
+        classes: row.get(13)?,
+        data: serde_json::from_str(&data).unwrap_or_default(),
+        source: row.get(15)?,
+    })
+}
+
+/// Loads a spell (core or custom) by ID.
+pub fn load_spell(conn: &Connection, spell_id: &str) -> AppResult<Option<Spell>> {
+    let sql = format!("SELECT {} FROM all_spells WHERE id = ?", SPELL_COLUMNS);
+    let spell = conn.query_row(&sql, params![spell_id], spell_from_row).optional()?;
+    Ok(spell)
+}
 
-    Ok(())
+/// Loads all spells (core, overrides and homebrew).
+pub fn load_all_spells(conn: &Connection) -> AppResult<Vec<Spell>> {
+    let sql = format!("SELECT {} FROM all_spells ORDER BY level, name", SPELL_COLUMNS);
+    let mut stmt = conn.prepare(&sql)?;
+    let spells = stmt.query_map([], spell_from_row)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(spells)
 }
@@ -1,21 +1,40 @@
+use rusqlite::types::Value;
 use rusqlite::{params, Connection};
-use crate::types::character::Character;
+use crate::core::multiclass::normalize_classes;
+use crate::db::row_sync::{sync_rows, RowTable, SyncRow};
+use crate::types::character::{Character, ClassLevel};
 use crate::error::AppResult;
 
-/// Synchronizes character attributes, health, and death saves with the characters table.
+const CLASSES_TABLE: RowTable = RowTable {
+    table: "character_classes",
+    key: &["class_id"],
+    columns: &["subclass_id", "level", "position"],
+};
+
+/// Synchronizes name, level, class levels, attributes, health, death saves and concentration
+/// with the characters table and `character_classes`.
 pub fn sync_stats(conn: &Connection, character: &Character) -> AppResult<()> {
     let char_id = character.id.to_string();
+    let concentration = character.concentration.as_ref();
+    // Bei genau einer Klasse haben die Einzelfelder Vorrang, siehe normalize_classes
+    let mut meta = character.meta.clone();
+    normalize_classes(&mut meta);
 
     conn.execute(
         "UPDATE characters SET 
+            name = ?, level = ?,
             attr_str = ?, attr_dex = ?, attr_con = ?, 
             attr_int = ?, attr_wis = ?, attr_cha = ?,
             hp_current = ?, hp_max = ?, hp_temp = ?,
             hit_dice_max = ?, hit_dice_used = ?,
             death_saves_successes = ?, death_saves_failures = ?,
+            concentration_spell_id = ?, concentration_spell_name = ?, concentration_slot_level = ?,
+            concentration_duration = ?, concentration_started_at = ?,
             updated_at = (unixepoch())
          WHERE id = ?",
         params![
+            meta.name,
+            meta.level,
             character.attributes.str,
             character.attributes.dex,
             character.attributes.con,
@@ -29,9 +48,35 @@ pub fn sync_stats(conn: &Connection, character: &Character) -> AppResult<()> {
             character.health.hit_dice_used,
             character.health.death_saves.successes,
             character.health.death_saves.failures,
+            concentration.map(|c| c.spell_id.clone()),
+            concentration.map(|c| c.spell_name.clone()),
+            concentration.and_then(|c| c.slot_level),
+            concentration.and_then(|c| c.duration.clone()),
+            concentration.and_then(|c| c.started_at),
             char_id
         ],
     )?;
 
-    Ok(())
+    let rows: Vec<SyncRow> = meta.classes.iter().enumerate().map(|(position, class)| {
+        SyncRow::new(
+            vec![Value::Text(class.class_id.clone())],
+            vec![
+                class.subclass_id.clone().map_or(Value::Null, Value::Text),
+                Value::Integer(class.level.into()),
+                Value::Integer(position as i64),
+            ],
+        )
+    }).collect();
+    sync_rows(conn, &CLASSES_TABLE, &char_id, &rows)
+}
+
+/// Loads a character's class levels, starting class first.
+pub fn load_classes(conn: &Connection, character_id: &str) -> AppResult<Vec<ClassLevel>> {
+    let mut stmt = conn.prepare(
+        "SELECT class_id, subclass_id, level FROM character_classes WHERE character_id = ? ORDER BY position"
+    )?;
+    let classes = stmt.query_map(params![character_id], |row| {
+        Ok(ClassLevel { class_id: row.get(0)?, subclass_id: row.get(1)?, level: row.get(2)? })
+    })?.collect::<Result<Vec<_>, _>>()?;
+    Ok(classes)
 }
@@ -1,8 +1,8 @@
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use tauri::State;
+use crate::db::characters::{LEGACY_STORAGE_VERSION, NORMALIZED_STORAGE_VERSION};
 use crate::db::Database;
-use crate::error::map_lock_error;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationError {
@@ -69,12 +69,13 @@ pub fn validate_schema_integrity(conn: &Connection) -> Vec<ValidationError> {
         });
     }
 
-    // 5. Check character inventory synchronization
+    // 5. Check character inventory synchronization (only legacy rows still carry an inventory in data)
     let unsynced_inventories: i32 = conn.query_row(
         "SELECT COUNT(*) FROM characters c
          LEFT JOIN character_inventory_legacy_view l ON l.character_id = c.id
-         WHERE json(json_extract(c.data, '$.inventory')) != json(COALESCE(l.inventory_json, '[]'))",
-        [],
+         WHERE c.storage_version = ?
+           AND json(json_extract(c.data, '$.inventory')) != json(COALESCE(l.inventory_json, '[]'))",
+        [LEGACY_STORAGE_VERSION],
         |row| row.get(0)
     ).unwrap_or(0);
 
@@ -85,11 +86,25 @@ pub fn validate_schema_integrity(conn: &Connection) -> Vec<ValidationError> {
         });
     }
 
+    // 6. Check for characters that could not be converted to the normalized format
+    let legacy_characters: i32 = conn.query_row(
+        "SELECT COUNT(*) FROM characters WHERE storage_version < ?",
+        [NORMALIZED_STORAGE_VERSION],
+        |row| row.get(0)
+    ).unwrap_or(0);
+
+    if legacy_characters > 0 {
+        errors.push(ValidationError {
+            category: "Character Storage".to_string(),
+            message: format!("{} characters are not yet stored in the normalized format", legacy_characters)
+        });
+    }
+
     errors
 }
 
 #[tauri::command]
 pub async fn run_schema_validation(state: State<'_, Database>) -> Result<Vec<ValidationError>, String> {
-    let conn = map_lock_error(state.0.lock()).map_err(|e| e.to_string())?;
+    let conn = state.read().map_err(|e| e.to_string())?;
     Ok(validate_schema_integrity(&conn))
 }
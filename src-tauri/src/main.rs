@@ -71,6 +71,7 @@ fn main() {
             commands::character::update_inventory_item,
             commands::character::get_character_spells,
             commands::character::update_spell_preparation,
+            commands::character::get_preparable_spells,
             commands::character::get_class_starting_equipment_options,
             commands::character::get_starting_equipment,
             commands::character::clear_starting_equipment,
@@ -88,6 +89,28 @@ fn main() {
             commands::features::get_class_features,
             commands::features::create_custom_class_feature,
             commands::subclasses::get_subclasses,
+            commands::sheet::get_derived_sheet,
+            commands::sheet::get_character_attacks,
+            commands::dice::roll_dice,
+            commands::rest::take_short_rest,
+            commands::rest::take_long_rest,
+            commands::resources::get_feature_resources,
+            commands::resources::spend_feature_resource,
+            commands::resources::restore_feature_resource,
+            commands::health::apply_damage,
+            commands::health::apply_healing,
+            commands::health::set_temp_hp,
+            commands::health::roll_death_save,
+            commands::conditions::add_condition,
+            commands::conditions::remove_condition,
+            commands::conditions::set_exhaustion,
+            commands::concentration::start_concentration,
+            commands::concentration::end_concentration,
+            commands::level_up::preview_level_up,
+            commands::level_up::apply_level_up,
+            commands::level_up::get_level_history,
+            commands::spell_slots::set_spell_slot_override,
+            commands::casting::cast_spell,
             commands::pdf::export_character_pdf,
             commands::pdf::save_pdf_bytes,
             commands::files::backup_database,
@@ -95,6 +118,8 @@ fn main() {
             commands::files::export_character,
             commands::settings::get_setting,
             commands::settings::set_setting,
+            commands::settings::get_database_location,
+            commands::settings::set_database_location,
             commands::compendium::get_all_spells,
             commands::compendium::get_all_species,
             commands::compendium::get_all_classes,
@@ -113,6 +138,9 @@ fn main() {
             commands::compendium::get_weapons_minimal,
             commands::compendium::get_items_minimal,
             commands::compendium::get_spells_minimal,
+            commands::compendium::get_spell_mechanics,
+            commands::compendium::search_compendium,
+            commands::compendium::fulltext_search,
             commands::logging::write_log,
             commands::logging::export_logs,
             commands::logging::read_logs,
@@ -77,7 +77,7 @@ pub fn validate_core_data(conn: &Connection) -> Result<ValidationReport, String>
 pub async fn validate_core_compendium(
     db: tauri::State<'_, crate::db::Database>,
 ) -> Result<ValidationReport, String> {
-    let conn = db.0.lock().map_err(|e| format!("Lock error: {}", e))?;
+    let conn = db.read().map_err(|e| e.to_string())?;
     validate_core_data(&conn)
 }
 
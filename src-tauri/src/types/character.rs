@@ -22,6 +22,10 @@ pub struct Character {
     pub inventory: Vec<CharacterItem>,
     #[serde(default)]
     pub spells: Vec<CharacterSpell>,
+    #[serde(default)]
+    pub conditions: Vec<CharacterCondition>,
+    #[serde(default)]
+    pub concentration: Option<Concentration>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -30,9 +34,19 @@ pub struct CharacterMeta {
     pub level: i32,
     #[serde(default)]
     pub fighting_styles: Option<Vec<String>>,
+    /// Waffen-IDs, deren Meisterschaft gewählt wurde (None = noch keine Auswahl gespeichert)
+    #[serde(default)]
+    pub weapon_masteries: Option<Vec<String>>,
+    /// Gewählte Optionen von Auswahl-Merkmalen (Merkmals-ID -> Option)
+    #[serde(default)]
+    pub feature_choices: Option<std::collections::HashMap<String, String>>,
     pub species_id: Option<String>,
+    /// Startklasse; bei Mehrklassen-Charakteren der erste Eintrag von `classes`
     pub class_id: Option<String>,
     pub subclass_id: Option<String>,
+    /// Stufen je Klasse. `level` ist die Gesamtstufe (siehe `core::multiclass::normalize_classes`)
+    #[serde(default)]
+    pub classes: Vec<ClassLevel>,
     pub background_id: Option<String>,
     pub origin_id: Option<String>,
     pub alignment: Option<String>,
@@ -114,6 +128,9 @@ pub struct CharacterMeta {
     pub spell_slots_used_8: i32,
     #[serde(default)]
     pub spell_slots_used_9: i32,
+    /// Verbrauchte Paktmagie-Plätze (eigener Vorrat, siehe `core::spell_slots`)
+    #[serde(default)]
+    pub pact_slots_used: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -126,6 +143,17 @@ pub struct HealthPool {
     #[serde(default)]
     pub use_rolled_hp: Option<bool>, // true = gewürfelt, false/None = Durchschnitt
     pub death_saves: DeathSaves,
+    /// Trefferwürfel nach Würfelgröße; `hit_dice_max`/`hit_dice_used` sind die Summen
+    #[serde(default)]
+    pub hit_dice: Vec<HitDicePool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HitDicePool {
+    /// Seitenzahl, z.B. 10 für W10
+    pub die: u32,
+    pub max: i32,
+    pub used: i32,
 }
 
 impl Default for HealthPool {
@@ -138,10 +166,52 @@ impl Default for HealthPool {
             hit_dice_used: 0,
             use_rolled_hp: None,
             death_saves: DeathSaves::default(),
+            hit_dice: Vec::new(),
         }
     }
 }
 
+/// Stufen in einer Klasse.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ClassLevel {
+    pub class_id: String,
+    #[serde(default)]
+    pub subclass_id: Option<String>,
+    pub level: i32,
+}
+
+/// Aktiver Zustand (z.B. "poisoned", "exhaustion") mit Quelle und Dauer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CharacterCondition {
+    pub condition_id: String,
+    /// Stufe bei Erschöpfung (1-6)
+    #[serde(default)]
+    pub level: Option<i32>,
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Freitext, z.B. "1 Minute" oder "bis Rettungswurf gelingt"
+    #[serde(default)]
+    pub duration: Option<String>,
+    /// "short" oder "long": endet mit dieser Rast
+    #[serde(default)]
+    pub ends_on_rest: Option<String>,
+}
+
+/// Zauber, auf den sich der Charakter gerade konzentriert.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Concentration {
+    pub spell_id: String,
+    pub spell_name: String,
+    /// Grad des verwendeten Zauberplatzes
+    #[serde(default)]
+    pub slot_level: Option<i32>,
+    #[serde(default)]
+    pub duration: Option<String>,
+    /// Unix-Zeitstempel des Beginns
+    #[serde(default)]
+    pub started_at: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DeathSaves {
     pub successes: i32,
@@ -239,6 +309,100 @@ pub struct Attributes {
     pub cha: i32,
 }
 
+impl CharacterMeta {
+    /// Maximale Zauberplätze eines Grades (1-9).
+    pub fn spell_slots(&self, level: i32) -> i32 {
+        match level {
+            1 => self.spell_slots_1,
+            2 => self.spell_slots_2,
+            3 => self.spell_slots_3,
+            4 => self.spell_slots_4,
+            5 => self.spell_slots_5,
+            6 => self.spell_slots_6,
+            7 => self.spell_slots_7,
+            8 => self.spell_slots_8,
+            9 => self.spell_slots_9,
+            _ => 0,
+        }
+    }
+
+    /// Verbrauchte Zauberplätze eines Grades (1-9).
+    pub fn spell_slots_used(&self, level: i32) -> i32 {
+        match level {
+            1 => self.spell_slots_used_1,
+            2 => self.spell_slots_used_2,
+            3 => self.spell_slots_used_3,
+            4 => self.spell_slots_used_4,
+            5 => self.spell_slots_used_5,
+            6 => self.spell_slots_used_6,
+            7 => self.spell_slots_used_7,
+            8 => self.spell_slots_used_8,
+            9 => self.spell_slots_used_9,
+            _ => 0,
+        }
+    }
+
+    pub fn set_spell_slots(&mut self, level: i32, slots: i32) {
+        let slot = match level {
+            1 => &mut self.spell_slots_1,
+            2 => &mut self.spell_slots_2,
+            3 => &mut self.spell_slots_3,
+            4 => &mut self.spell_slots_4,
+            5 => &mut self.spell_slots_5,
+            6 => &mut self.spell_slots_6,
+            7 => &mut self.spell_slots_7,
+            8 => &mut self.spell_slots_8,
+            9 => &mut self.spell_slots_9,
+            _ => return,
+        };
+        *slot = slots;
+    }
+
+    pub fn set_spell_slots_used(&mut self, level: i32, used: i32) {
+        let slot = match level {
+            1 => &mut self.spell_slots_used_1,
+            2 => &mut self.spell_slots_used_2,
+            3 => &mut self.spell_slots_used_3,
+            4 => &mut self.spell_slots_used_4,
+            5 => &mut self.spell_slots_used_5,
+            6 => &mut self.spell_slots_used_6,
+            7 => &mut self.spell_slots_used_7,
+            8 => &mut self.spell_slots_used_8,
+            9 => &mut self.spell_slots_used_9,
+            _ => return,
+        };
+        *slot = used;
+    }
+}
+
+impl Attributes {
+    /// Liefert den Attributswert zu einem Kürzel ("str", "DEX", ...).
+    pub fn get(&self, ability: &str) -> Option<i32> {
+        match ability.trim().to_lowercase().as_str() {
+            "str" => Some(self.str),
+            "dex" => Some(self.dex),
+            "con" => Some(self.con),
+            "int" => Some(self.int),
+            "wis" => Some(self.wis),
+            "cha" => Some(self.cha),
+            _ => None,
+        }
+    }
+
+    /// Veränderlicher Zugriff auf einen Attributswert.
+    pub fn get_mut(&mut self, ability: &str) -> Option<&mut i32> {
+        match ability.trim().to_lowercase().as_str() {
+            "str" => Some(&mut self.str),
+            "dex" => Some(&mut self.dex),
+            "con" => Some(&mut self.con),
+            "int" => Some(&mut self.int),
+            "wis" => Some(&mut self.wis),
+            "cha" => Some(&mut self.cha),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Modifier {
     pub id: String,
@@ -25,6 +25,16 @@ import {
   MagicItem,
   CharacterItem,
   CharacterSpell,
+  PreparableSpells,
+  PreparationStatus,
+  CompendiumEntity,
+  CompendiumFilters,
+  CompendiumSort,
+  CompendiumPage,
+  CompendiumSearchResult,
+  FulltextEntity,
+  FulltextHit,
+  DatabaseLocation,
 } from "./types";
 
 export const characterApi = {
@@ -57,8 +67,11 @@ export const characterApi = {
   async updateSpellPreparation(
     id: string,
     is_prepared: boolean,
-  ): Promise<void> {
-    await invoke("update_spell_preparation", { id, is_prepared });
+  ): Promise<PreparationStatus> {
+    return await invoke("update_spell_preparation", { id, is_prepared });
+  },
+  async getPreparableSpells(characterId: string): Promise<PreparableSpells> {
+    return await invoke("get_preparable_spells", { character_id: characterId });
   },
   async invoke(
     command: string,
@@ -108,6 +121,28 @@ export const compendiumApi = {
   async getMagicItems(): Promise<MagicItem[]> {
     return await invoke("get_all_magic_items");
   },
+  async search(
+    entity: CompendiumEntity,
+    query?: string,
+    filters?: CompendiumFilters,
+    sort?: CompendiumSort,
+    page?: CompendiumPage,
+  ): Promise<CompendiumSearchResult> {
+    return await invoke("search_compendium", {
+      entity,
+      query,
+      filters,
+      sort,
+      page,
+    });
+  },
+  async fulltextSearch(
+    query: string,
+    entities?: FulltextEntity[],
+    limit?: number,
+  ): Promise<FulltextHit[]> {
+    return await invoke("fulltext_search", { query, entities, limit });
+  },
   async importPhbData(): Promise<void> {
     return await invoke("import_phb_data");
   },
@@ -160,3 +195,13 @@ export const homebrewApi = {
     await invoke("delete_custom_entry", { id, tableType });
   },
 };
+
+export const settingsApi = {
+  async getDatabaseLocation(): Promise<DatabaseLocation> {
+    return await invoke("get_database_location");
+  },
+  /** Takes effect after a restart; `null` resets to the default location */
+  async setDatabaseLocation(path: string | null): Promise<DatabaseLocation> {
+    return await invoke("set_database_location", { path });
+  },
+};
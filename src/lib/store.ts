@@ -631,7 +631,10 @@ export const useCharacterStore = create<CharacterState>((set, get) => ({
       );
       set({ currentCharacter: { ...currentCharacter, spells: nextSpells } });
 
-      await characterApi.updateSpellPreparation(id, isPrepared);
+      const status = await characterApi.updateSpellPreparation(id, isPrepared);
+      if (status.issues.length > 0) {
+        set({ error: status.messages.join("\n") });
+      }
       await refreshSpells();
     } catch (err) {
       set({ error: (err as Error).message });
@@ -10,6 +10,8 @@ export interface Character {
   feats: string[]; // List of feat IDs
   inventory: CharacterItem[];
   spells: CharacterSpell[];
+  conditions?: CharacterCondition[];
+  concentration?: Concentration | null;
 }
 
 export interface CharacterMeta {
@@ -17,9 +19,12 @@ export interface CharacterMeta {
   player_name?: string;
   level: number;
   fighting_styles?: string[]; // z.B. ["zwei-waffen-kampf"] - Kampfstil-IDs aus der DB
+  weapon_masteries?: string[]; // Waffen-IDs mit gewählter Waffenmeisterschaft
+  feature_choices?: Record<string, string>; // Merkmals-ID -> gewählte Option
   species_id?: string;
-  class_id?: string;
+  class_id?: string; // Startklasse (= classes[0])
   subclass_id?: string;
+  classes?: ClassLevel[]; // Stufen je Klasse, Summe = level
   background_id?: string;
   origin_id?: string;
   alignment?: string;
@@ -85,6 +90,7 @@ export interface CharacterMeta {
   spell_slots_used_7?: number;
   spell_slots_used_8?: number;
   spell_slots_used_9?: number;
+  pact_slots_used?: number; // Paktmagie, eigener Vorrat (Maxima im Derived Sheet)
 }
 
 export interface CharacterAppearance {
@@ -104,6 +110,41 @@ export interface CharacterSpell {
   source?: string;
 }
 
+export type PreparationIssue =
+  | { code: "limit_exceeded"; prepared: number; limit: number }
+  | { code: "no_preparing_class" }
+  | { code: "unknown_spell"; spell_id: string }
+  | { code: "not_on_class_list"; spell_id: string; spell_name: string }
+  | {
+      code: "level_too_high";
+      spell_id: string;
+      spell_name: string;
+      level: number;
+      max_level: number;
+    };
+
+export interface PreparationStatus {
+  prepared: number;
+  limit: number | null;
+  issues: PreparationIssue[]; // leer = Vorbereitung gültig
+  messages: string[]; // lesbare Texte zu issues
+}
+
+export interface PreparableSpell {
+  spell_id: string;
+  name: string;
+  level: number;
+  class_ids: string[];
+  entry_id: string | null; // character_spells.id, falls schon beim Charakter
+  is_prepared: boolean;
+  is_always_prepared: boolean;
+}
+
+export interface PreparableSpells {
+  status: PreparationStatus;
+  spells: PreparableSpell[];
+}
+
 export interface CharacterSpellcasting {
   ability: keyof Attributes;
   save_dc: number;
@@ -112,12 +153,25 @@ export interface CharacterSpellcasting {
   prepared_spells: string[]; // List of spell IDs
 }
 
+export interface ClassLevel {
+  class_id: string;
+  subclass_id?: string;
+  level: number;
+}
+
+export interface HitDicePool {
+  die: number; // z.B. 10 für W10
+  max: number;
+  used: number;
+}
+
 export interface HealthPool {
   current: number;
   max: number;
   temp: number;
   hit_dice_max: number;
   hit_dice_used: number;
+  hit_dice?: HitDicePool[]; // nur bei Mehrklassen-Charakteren
   use_rolled_hp?: boolean; // true = gewürfelt, false/undefined = Durchschnitt
   death_saves: {
     successes: number;
@@ -125,6 +179,22 @@ export interface HealthPool {
   };
 }
 
+export interface CharacterCondition {
+  condition_id: string; // z.B. "poisoned", "exhaustion"
+  level?: number; // Erschöpfungsstufe 1-6
+  source?: string;
+  duration?: string;
+  ends_on_rest?: 'short' | 'long';
+}
+
+export interface Concentration {
+  spell_id: string;
+  spell_name: string;
+  slot_level?: number;
+  duration?: string;
+  started_at?: number; // Unix-Zeitstempel
+}
+
 export interface CharacterProficiencies {
   skills: string[]; // List of skill IDs or names
   saving_throws: (keyof Attributes)[];
@@ -490,3 +560,75 @@ export interface CustomBackground {
   parent_id?: string;
   is_homebrew?: boolean;
 }
+
+export type CompendiumEntity =
+  | "spells"
+  | "weapons"
+  | "armor"
+  | "items"
+  | "magic_items"
+  | "feats"
+  | "backgrounds";
+
+export interface CompendiumFilters {
+  spell_levels?: number[];
+  schools?: string[];
+  class?: string;
+  concentration?: boolean;
+  ritual?: boolean;
+  categories?: string[];
+  weapon_property?: string;
+  rarities?: string[];
+  requires_attunement?: boolean;
+  cost_min?: number;
+  cost_max?: number;
+  weight_min?: number;
+  weight_max?: number;
+  source?: "core" | "override" | "homebrew";
+}
+
+export interface CompendiumSort {
+  field: string;
+  descending?: boolean;
+}
+
+export interface CompendiumPage {
+  page: number;
+  page_size?: number;
+}
+
+export interface CompendiumSearchResult {
+  entity: CompendiumEntity;
+  total: number;
+  page: number;
+  page_size: number;
+  items: Record<string, unknown>[];
+}
+
+export type FulltextEntity =
+  | "spell"
+  | "feat"
+  | "class_feature"
+  | "magic_item"
+  | "item";
+
+export interface FulltextHit {
+  entity: FulltextEntity;
+  id: string;
+  name: string;
+  /** Name with matches wrapped in <mark> */
+  name_highlighted: string;
+  /** Description excerpt with matches wrapped in <mark> */
+  snippet: string;
+  /** BM25 rank, lower is better */
+  rank: number;
+}
+
+export interface DatabaseLocation {
+  /** Per-user database with characters, homebrew and settings */
+  user_database: string;
+  /** Where the user database path comes from */
+  source: "environment" | "setting" | "default";
+  /** Bundled compendium, attached read-only */
+  compendium: string;
+}